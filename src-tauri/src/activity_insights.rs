@@ -0,0 +1,176 @@
+/*!
+ * ================================================================================================
+ * ACTIVITY INSIGHTS
+ * ================================================================================================
+ *
+ * Longer-term record of when the user opens the app and completes habits, plus how they
+ * respond to reminders. Backs the `intelligent_timing` and `adaptive_frequency` settings:
+ *
+ * - intelligent timing moves each streak-protection slot to the hour nearby in which the
+ *   user is usually active
+ * - adaptive frequency spaces out reminders the user keeps ignoring
+ *
+ * Every function takes the current time explicitly so the policy is deterministic.
+ *
+ * @version 1.0.0
+ */
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Days of activity kept in the histogram
+pub const HISTORY_DAYS: i64 = 28;
+
+/// Below this many weighted samples the histogram is too thin to move reminders
+pub const MIN_SAMPLES: u32 = 10;
+
+/// How many hours a streak-protection slot may move in either direction
+pub const SLOT_SEARCH_RADIUS: u32 = 2;
+
+/// Activity within this long after a reminder counts as responding to it
+pub const ENGAGEMENT_WINDOW_HOURS: i64 = 2;
+
+/// Longest gap adaptive frequency will put between two reminders of the same kind
+pub const MAX_BACKOFF_DAYS: i64 = 7;
+
+/// Completions say more about when the user acts than app sessions do
+const COMPLETION_WEIGHT: u32 = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DayActivity {
+    pub sessions: [u32; 24],
+    pub completions: [u32; 24],
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReminderEngagement {
+    pub last_sent: Option<DateTime<Local>>,
+    /// The last reminder has not been answered yet and its window is still open
+    pub awaiting_response: bool,
+    pub consecutive_ignored: u32,
+    pub sent: u32,
+    pub engaged: u32,
+}
+
+impl ReminderEngagement {
+    /// Close the response window of the last reminder if it has expired unanswered
+    fn resolve(&mut self, now: DateTime<Local>) {
+        if let Some(last_sent) = self.last_sent {
+            if self.awaiting_response && now - last_sent > Duration::hours(ENGAGEMENT_WINDOW_HOURS) {
+                self.awaiting_response = false;
+                self.consecutive_ignored += 1;
+            }
+        }
+    }
+
+    /// Minimum number of days between reminders given how many in a row were ignored.
+    /// One day until three are ignored, then doubling up to `MAX_BACKOFF_DAYS`.
+    pub fn backoff_days(&self) -> i64 {
+        let doublings = self.consecutive_ignored.saturating_sub(2).min(8);
+        (1i64 << doublings).min(MAX_BACKOFF_DAYS)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActivityInsights {
+    pub days: BTreeMap<NaiveDate, DayActivity>,
+    /// Keyed by reminder kind, e.g. "streak_protection"
+    pub engagement: BTreeMap<String, ReminderEngagement>,
+}
+
+impl ActivityInsights {
+    pub fn record_session(&mut self, at: DateTime<Local>) {
+        self.day_mut(at).sessions[at.hour() as usize] += 1;
+        self.record_engagement(at);
+        self.prune(at.date_naive());
+    }
+
+    pub fn record_completion(&mut self, at: DateTime<Local>) {
+        self.day_mut(at).completions[at.hour() as usize] += 1;
+        self.record_engagement(at);
+        self.prune(at.date_naive());
+    }
+
+    fn day_mut(&mut self, at: DateTime<Local>) -> &mut DayActivity {
+        self.days.entry(at.date_naive()).or_default()
+    }
+
+    fn prune(&mut self, today: NaiveDate) {
+        let cutoff = today - Duration::days(HISTORY_DAYS);
+        self.days.retain(|day, _| *day > cutoff);
+    }
+
+    /// Weighted activity per hour of day over the retained history
+    pub fn hourly_activity(&self) -> [u32; 24] {
+        let mut hours = [0u32; 24];
+        for day in self.days.values() {
+            for (hour, total) in hours.iter_mut().enumerate() {
+                *total += day.sessions[hour] + COMPLETION_WEIGHT * day.completions[hour];
+            }
+        }
+        hours
+    }
+
+    /// The hour within `SLOT_SEARCH_RADIUS` of `hour` with the most activity. Ties go to the
+    /// hour closest to the original, then the earlier one. Returns `hour` unchanged while
+    /// there is not enough history.
+    pub fn preferred_hour_near(&self, hour: u32) -> u32 {
+        let activity = self.hourly_activity();
+        if activity.iter().sum::<u32>() < MIN_SAMPLES {
+            return hour;
+        }
+
+        let low = hour.saturating_sub(SLOT_SEARCH_RADIUS);
+        let high = (hour + SLOT_SEARCH_RADIUS).min(23);
+        (low..=high)
+            .max_by_key(|candidate| {
+                let distance = candidate.abs_diff(hour);
+                // max_by_key keeps the last maximum, so invert the tie-breakers
+                (activity[*candidate as usize], std::cmp::Reverse(distance), std::cmp::Reverse(*candidate))
+            })
+            .unwrap_or(hour)
+    }
+
+    pub fn record_reminder_sent(&mut self, kind: &str, at: DateTime<Local>) {
+        let engagement = self.engagement.entry(kind.to_string()).or_default();
+        engagement.resolve(at);
+        if engagement.awaiting_response {
+            // Superseded before its window closed without an answer
+            engagement.consecutive_ignored += 1;
+        }
+        engagement.last_sent = Some(at);
+        engagement.awaiting_response = true;
+        engagement.sent += 1;
+    }
+
+    /// Credit any reminder whose response window is open at `at`
+    fn record_engagement(&mut self, at: DateTime<Local>) {
+        for engagement in self.engagement.values_mut() {
+            engagement.resolve(at);
+            if engagement.awaiting_response {
+                engagement.awaiting_response = false;
+                engagement.consecutive_ignored = 0;
+                engagement.engaged += 1;
+            }
+        }
+    }
+
+    /// Whether a reminder of `kind` should be held back because recent ones were ignored
+    pub fn is_backed_off(&self, kind: &str, now: DateTime<Local>) -> bool {
+        let Some(engagement) = self.engagement.get(kind) else {
+            return false;
+        };
+        let Some(last_sent) = engagement.last_sent else {
+            return false;
+        };
+
+        let mut engagement = engagement.clone();
+        engagement.resolve(now);
+        if engagement.consecutive_ignored < 3 {
+            return false;
+        }
+
+        now.date_naive() - last_sent.date_naive() < Duration::days(engagement.backoff_days())
+    }
+}
@@ -0,0 +1,685 @@
+/*!
+ * ================================================================================================
+ * APP DATA
+ * ================================================================================================
+ *
+ * The canonical copy of the user's habits, points, rewards, goals and achievements, mirroring
+ * `StoredData` in `src/types/index.ts`. It lives in `habitquest.db` in the app data directory,
+ * behind the `Storage` trait, so clearing the webview's storage no longer loses a user's
+ * history; with encryption on, it's `habitquest.db.sealed` instead. `app_data.json` is its
+ * import and export format. Every change is first appended to the event log, which the
+ * database can be rebuilt from. The frontend loads the data on start and patches it as state
+ * changes; every write refreshes the habit streaks, progress and settings the background
+ * service works from.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_settings::{self, AppSettings};
+use crate::backups::{BackupReason, BackupStore};
+use crate::background_notifications::BackgroundNotificationService;
+use crate::clock::app_now;
+use crate::encryption::{self, LockedError};
+use crate::event_log::{events_between, Event, EventLog, LoggedEvent, EVENT_LOG_FILE};
+use crate::habit_streaks::{HabitFrequency, HabitStreak};
+use crate::notification_history::{HistoryEntry, NotificationHistoryStore};
+use crate::persistence::{self, Schema};
+use crate::storage::{CompletionQuery, CompletionRecord, SqliteStorage, Storage, StorageResult, XpEntry};
+use crate::weekly_review::{Goal, ProgressData, Redemption};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Schema history of `app_data.json`: v0 is a bare `StoredData` export, v1 added the envelope
+pub const APP_DATA_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+/// The JSON form of the data, imported into the database on first run and kept as an export
+pub const APP_DATA_FILE: &str = "app_data.json";
+
+pub const DATABASE_FILE: &str = "habitquest.db";
+
+/// The database while encryption is enabled, kept in memory and sealed to this file
+pub const SEALED_DATABASE_FILE: &str = "habitquest.db.sealed";
+
+/// Sent with the updated data when the backend changes it on its own
+pub const APP_DATA_CHANGED_EVENT: &str = "app-data-changed";
+
+// ================================================================================================
+// TYPES
+// ================================================================================================
+
+/// A completion in a habit's `completions`, keyed by period. Data from before completions were
+/// timestamped only records `true`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Completion {
+    At(DateTime<Local>),
+    Legacy(bool),
+}
+
+impl Completion {
+    pub fn at(self) -> Option<DateTime<Local>> {
+        match self {
+            Completion::At(at) => Some(at),
+            Completion::Legacy(_) => None,
+        }
+    }
+}
+
+/// Mirrors `Habit` in `src/types/index.ts`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Habit {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub frequency: HabitFrequency,
+    pub category: String,
+    pub xp_on_complete: u32,
+    #[serde(default)]
+    pub streak: u32,
+    #[serde(default)]
+    pub best_streak: u32,
+    #[serde(default)]
+    pub last_completed_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub completions: BTreeMap<String, Completion>,
+    #[serde(default = "default_true")]
+    pub is_recurring: bool,
+    /// Day of a one-off habit, as picked in the habit form
+    #[serde(default)]
+    pub specific_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Habit {
+    /// Complete the habit in the period containing `at` the way the frontend's `toggleComplete`
+    /// does. Returns false if it was already completed in that period.
+    pub fn complete(&mut self, at: DateTime<Local>) -> bool {
+        let key = self.frequency.period_key(at.date_naive());
+        if self.completions.contains_key(&key) {
+            return false;
+        }
+
+        // As in the frontend, a streak only grows from a completion in an earlier period
+        let last_key = self.last_completed_at.map(|last| self.frequency.period_key(last.date_naive()));
+        if last_key.is_some_and(|last_key| last_key != key) {
+            self.streak += 1;
+        }
+        self.best_streak = self.best_streak.max(self.streak);
+        self.completions.insert(key, Completion::At(at));
+        self.last_completed_at = Some(at);
+        true
+    }
+
+    /// What the streak warnings, digests and weekly review need to know about this habit
+    pub fn habit_streak(&self) -> HabitStreak {
+        let mut completions: Vec<_> = self.completions.values().filter_map(|completion| completion.at()).collect();
+        completions.sort();
+
+        HabitStreak {
+            habit_id: self.id.clone(),
+            habit_name: self.title.clone(),
+            frequency: self.frequency,
+            streak: self.streak,
+            last_completed_at: self.last_completed_at,
+            is_recurring: self.is_recurring,
+            specific_date: self.specific_date,
+            xp_on_complete: self.xp_on_complete,
+            category: self.category.clone(),
+            completions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Rarity {
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+/// Mirrors `Reward` in `src/types/index.ts`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Reward {
+    pub id: String,
+    pub name: String,
+    pub cost: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<Rarity>,
+}
+
+/// Mirrors `InventoryItem` in `src/types/index.ts`: a redeemed reward
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryItem {
+    /// Id of the reward redeemed; missing on items from the oldest versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub cost: u32,
+    pub redeemed_at: DateTime<Local>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<Rarity>,
+}
+
+/// Mirrors `Achievement` in `src/types/index.ts`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Achievement {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub icon: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unlocked_at: Option<DateTime<Local>>,
+    pub progress: f64,
+    pub target: f64,
+    pub category: String,
+}
+
+/// Mirrors `UserStats` in `src/types/index.ts`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserStats {
+    pub total_habits_completed: u32,
+    pub longest_streak: u32,
+    pub total_days_active: u32,
+    pub favorite_category: String,
+    pub weekly_average: f64,
+    pub monthly_average: f64,
+}
+
+/// Mirrors `StoredData` in `src/types/index.ts`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppData {
+    pub habits: Vec<Habit>,
+    pub points: u32,
+    #[serde(rename = "totalXP")]
+    pub total_xp: u32,
+    pub goals: BTreeMap<String, Goal>,
+    pub inventory: Vec<InventoryItem>,
+    pub shop: Vec<Reward>,
+    pub categories: Vec<String>,
+    pub achievements: Vec<Achievement>,
+    pub user_stats: UserStats,
+    pub settings: AppSettings,
+    pub version: String,
+}
+
+impl AppData {
+    /// Reject data the rest of the backend can't tell apart, such as two habits sharing an id
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for habit in &self.habits {
+            if habit.id.trim().is_empty() {
+                return Err(format!("Habit \"{}\" has no id", habit.title));
+            }
+            if !ids.insert(habit.id.as_str()) {
+                return Err(format!("More than one habit has the id {}", habit.id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Complete `habit_id` in the period containing `at`, crediting its XP and twice that in
+    /// points. Returns false if it was already completed in that period.
+    pub fn complete_habit(&mut self, habit_id: &str, at: DateTime<Local>) -> Result<bool, String> {
+        let habit = self
+            .habits
+            .iter_mut()
+            .find(|habit| habit.id == habit_id)
+            .ok_or_else(|| format!("No habit has the id {}", habit_id))?;
+        if !habit.complete(at) {
+            return Ok(false);
+        }
+
+        let xp = habit.xp_on_complete;
+        self.total_xp = self.total_xp.saturating_add(xp);
+        self.points = self.points.saturating_add(xp.saturating_mul(2));
+        Ok(true)
+    }
+
+    pub fn habit_streaks(&self) -> Vec<HabitStreak> {
+        self.habits.iter().map(Habit::habit_streak).collect()
+    }
+
+    pub fn progress(&self) -> ProgressData {
+        ProgressData {
+            goals: self.goals.clone(),
+            inventory: self
+                .inventory
+                .iter()
+                .map(|item| Redemption { name: item.name.clone(), cost: item.cost, redeemed_at: item.redeemed_at })
+                .collect(),
+        }
+    }
+}
+
+/// Fields of `AppData` to replace; the ones left out keep their stored values
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppDataPatch {
+    pub habits: Option<Vec<Habit>>,
+    pub points: Option<u32>,
+    #[serde(rename = "totalXP")]
+    pub total_xp: Option<u32>,
+    pub goals: Option<BTreeMap<String, Goal>>,
+    pub inventory: Option<Vec<InventoryItem>>,
+    pub shop: Option<Vec<Reward>>,
+    pub categories: Option<Vec<String>>,
+    pub achievements: Option<Vec<Achievement>>,
+    pub user_stats: Option<UserStats>,
+    pub settings: Option<AppSettings>,
+    pub version: Option<String>,
+}
+
+impl AppDataPatch {
+    pub fn apply(self, data: &mut AppData) {
+        fn replace<T>(field: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+
+        replace(&mut data.habits, self.habits);
+        replace(&mut data.points, self.points);
+        replace(&mut data.total_xp, self.total_xp);
+        replace(&mut data.goals, self.goals);
+        replace(&mut data.inventory, self.inventory);
+        replace(&mut data.shop, self.shop);
+        replace(&mut data.categories, self.categories);
+        replace(&mut data.achievements, self.achievements);
+        replace(&mut data.user_stats, self.user_stats);
+        replace(&mut data.settings, self.settings);
+        replace(&mut data.version, self.version);
+    }
+}
+
+// ================================================================================================
+// STORE
+// ================================================================================================
+
+pub struct AppDataStore {
+    /// `None` until opened in setup, or if the database couldn't be opened
+    storage: Mutex<Option<Box<dyn Storage>>>,
+    /// The record of every change, which the storage is a projection of
+    events: Mutex<Option<EventLog>>,
+    app_handle: AppHandle,
+}
+
+impl AppDataStore {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            storage: Mutex::new(None),
+            events: Mutex::new(None),
+            app_handle,
+        }
+    }
+
+    /// Run `f` on the open storage
+    pub fn with_storage<T>(&self, f: impl FnOnce(&mut dyn Storage) -> StorageResult<T>) -> Result<T, String> {
+        let mut storage = self.storage.lock().unwrap();
+        let Some(storage) = storage.as_mut() else {
+            return Err(if encryption::is_locked() { LockedError.to_string() } else { "App data storage is not open".to_string() });
+        };
+        f(storage.as_mut()).map_err(|e| e.to_string())
+    }
+
+    /// The stored data; `None` until the frontend first saves, so it can tell a new install
+    /// from an empty one
+    pub fn data(&self) -> Result<Option<AppData>, String> {
+        self.with_storage(|storage| storage.load_app_data())
+    }
+
+    /// Record settings synced on their own, without creating the stored data: the frontend
+    /// syncs its settings before it has loaded the habits
+    pub fn set_settings(&self, settings: AppSettings) {
+        let now = app_now(&self.app_handle);
+        let result = self.with_storage(|storage| match storage.load_app_data()? {
+            Some(data) if data.settings != settings => {
+                let updated = AppData { settings, ..data.clone() };
+                self.commit(storage, Some(&data), &updated, now)
+            }
+            _ => Ok(()),
+        });
+        if let Err(e) = result {
+            log::warn!("Could not save app data: {}", e);
+        }
+    }
+
+    /// Validate and persist `update` applied to the stored data, then hand the habits,
+    /// progress and settings to the rest of the backend
+    pub fn update(&self, update: impl FnOnce(&mut AppData)) -> Result<AppData, String> {
+        self.try_update(|data| {
+            update(data);
+            Ok(())
+        })
+        .map(|((), data)| data)
+    }
+
+    /// `update` for a change that can fail, in which case nothing is saved
+    pub fn try_update<T>(&self, update: impl FnOnce(&mut AppData) -> Result<T, String>) -> Result<(T, AppData), String> {
+        let now = app_now(&self.app_handle);
+        let (result, previous_settings, data) = self.with_storage(|storage| {
+            let stored = storage.load_app_data()?;
+            let mut data = stored.clone().unwrap_or_default();
+            let result = update(&mut data)?;
+            data.validate()?;
+
+            self.commit(storage, stored.as_ref(), &data, now)?;
+            Ok((result, stored.map(|stored| stored.settings), data))
+        })?;
+
+        self.share(&data, previous_settings.as_ref())?;
+        Ok((result, data))
+    }
+
+    /// Complete a habit from outside the webview, such as a notification's "Mark done", and send
+    /// the webview the updated data so its next patch doesn't undo it. Returns false if the
+    /// habit was already completed in the current period.
+    pub fn complete_habit(&self, habit_id: &str) -> Result<bool, String> {
+        let now = app_now(&self.app_handle);
+        let (completed, data) = self.try_update(|data| data.complete_habit(habit_id, now))?;
+        if completed {
+            let _ = self.app_handle.emit(APP_DATA_CHANGED_EVENT, &data);
+        }
+        Ok(completed)
+    }
+
+    /// Undo the changes logged after event `seq`, by logging the changes back to the data as
+    /// it was then. The undo is logged like any other change, so it can be undone in turn.
+    pub fn revert_to(&self, seq: u64) -> Result<AppData, String> {
+        let reverted = {
+            let log = self.events.lock().unwrap();
+            let log = log.as_ref().ok_or("Event log is not open")?;
+            log.replay_until(seq).map_err(|e| e.to_string())?
+        };
+        let reverted = reverted.ok_or_else(|| format!("There was no app data yet at event {}", seq))?;
+        let data = self.update(|data| *data = reverted)?;
+        let _ = self.app_handle.emit(APP_DATA_CHANGED_EVENT, &data);
+        Ok(data)
+    }
+
+    /// Log the events from `before` to `after`, then save `after` to the storage
+    fn commit(&self, storage: &mut dyn Storage, before: Option<&AppData>, after: &AppData, at: DateTime<Local>) -> StorageResult<()> {
+        let events = events_between(before, after);
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let snapshot_seq = {
+            let mut log = self.events.lock().unwrap();
+            let log = log.as_mut().ok_or("Event log is not open")?;
+            log.append(at, events)?;
+            match log.needs_snapshot().then(|| log.save_snapshot(Some(after))) {
+                Some(Ok(())) => Some(log.last_seq()),
+                Some(Err(e)) => {
+                    log::warn!("Could not snapshot the event log: {}", e);
+                    None
+                }
+                None => None,
+            }
+        };
+        if let Some(seq) = snapshot_seq {
+            self.checkpoint_activity(seq);
+        }
+        storage.save_app_data(after, at)
+    }
+
+    /// Append an event that doesn't change the app data, returning its position in the log
+    pub fn append_event(&self, at: DateTime<Local>, event: Event) -> Result<u64, String> {
+        let mut log = self.events.lock().unwrap();
+        let log = log.as_mut().ok_or("Event log is not open")?;
+        log.append(at, vec![event]).map_err(|e| e.to_string())?;
+        Ok(log.last_seq())
+    }
+
+    /// Save the activity data along with a snapshot of the log at `seq`, so loading replays
+    /// only the activity logged after it
+    fn checkpoint_activity(&self, seq: u64) {
+        if let Some(service) = self.app_handle.try_state::<BackgroundNotificationService>() {
+            service.save_activity_checkpoint(seq);
+        }
+    }
+
+    /// Logged events after `seq`, oldest first
+    pub fn events_after(&self, seq: u64) -> Result<Vec<LoggedEvent>, String> {
+        let log = self.events.lock().unwrap();
+        let log = log.as_ref().ok_or("Event log is not open")?;
+        log.events_after(seq).map_err(|e| e.to_string())
+    }
+
+    /// Run `f` with no change being written, so the database and event log stay a matching pair
+    pub fn without_writes<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _storage = self.storage.lock().unwrap();
+        let _events = self.events.lock().unwrap();
+        f()
+    }
+
+    /// Close the database and event log, e.g. while their files are replaced. Writes fail until
+    /// the store is opened again.
+    pub fn close(&self) {
+        let mut storage = self.storage.lock().unwrap();
+        let mut events = self.events.lock().unwrap();
+        *storage = None;
+        *events = None;
+    }
+
+    /// Push the parts of `data` the background service and settings store keep their own copy of
+    fn share(&self, data: &AppData, previous_settings: Option<&AppSettings>) -> Result<(), String> {
+        if let Some(service) = self.app_handle.try_state::<BackgroundNotificationService>() {
+            service.set_habit_streaks(data.habit_streaks());
+            service.set_progress(data.progress());
+        }
+        if previous_settings != Some(&data.settings) {
+            app_settings::apply_app_settings(&self.app_handle, data.settings.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Replace the stored data with a JSON export, such as `app_data.json`, backing up the
+    /// current data first
+    pub fn import_json(&self, path: &Path) -> Result<AppData, String> {
+        let json = persistence::read_json(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let imported: AppData = persistence::decode(&json, &APP_DATA_SCHEMA)
+            .map_err(|e| format!("Could not import {}: {}", path.display(), e))?;
+        if let Some(backups) = self.app_handle.try_state::<BackupStore>() {
+            backups.create(BackupReason::BeforeImport)?;
+        }
+        self.update(|data| *data = imported)
+    }
+
+    /// Write the stored data to `path` as JSON
+    pub fn export_json(&self, path: &Path) -> Result<(), String> {
+        let data = self.data()?.unwrap_or_default();
+        persistence::encode(&data, &APP_DATA_SCHEMA)
+            .map_err(|e| e.to_string())
+            .and_then(|json| persistence::write_atomic(path, json.as_bytes()).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Could not export app data to {}: {}", path.display(), e))
+    }
+
+    /// Add a sent notification to the log kept with the app data
+    pub fn record_notification(&self, entry: &HistoryEntry) {
+        if let Err(e) = self.with_storage(|storage| storage.record_notifications(std::slice::from_ref(entry))) {
+            log::warn!("Could not store notification: {}", e);
+        }
+    }
+
+    /// Open the event log and the database, rebuilding the database from the log if they
+    /// disagree, and share the data with the services registered so far. On first run the
+    /// log starts from the database, or from `app_data.json` before there was one.
+    pub fn open(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if encryption::is_locked() {
+            return Err(Box::new(LockedError));
+        }
+        let data_dir = self.app_handle.path().app_data_dir()
+            .map_err(|e| format!("Could not resolve app data directory: {}", e))?;
+        fs::create_dir_all(&data_dir)?;
+        let now = app_now(&self.app_handle);
+
+        let mut storage = if encryption::is_enabled() {
+            SqliteStorage::open_sealed(&data_dir.join(SEALED_DATABASE_FILE))?
+        } else {
+            SqliteStorage::open(&data_dir.join(DATABASE_FILE))?
+        };
+        if let Some(history) = self.app_handle.try_state::<NotificationHistoryStore>() {
+            storage.record_notifications(&history.entries())?;
+        }
+
+        let mut log = EventLog::open(&data_dir)?;
+        let data = match log.replay()? {
+            Some(data) => {
+                if storage.load_app_data()?.as_ref() != Some(&data) {
+                    log::info!("Rebuilding {} from {}", DATABASE_FILE, EVENT_LOG_FILE);
+                    storage.save_app_data(&data, now)?;
+                }
+                Some(data)
+            }
+            None => {
+                let existing = match storage.load_app_data()? {
+                    Some(data) => Some(data),
+                    None => persistence::load_json::<AppData>(&data_dir.join(APP_DATA_FILE), &APP_DATA_SCHEMA)?
+                        .map(|loaded| persistence::report_recovery(&self.app_handle, loaded)),
+                };
+                if let Some(data) = &existing {
+                    log::info!("Starting {} from the stored data", EVENT_LOG_FILE);
+                    log.append(now, events_between(None, data))?;
+                    storage.save_app_data(data, now)?;
+                }
+                existing
+            }
+        };
+        // Activity logged since the activity data was last saved
+        if let Some(service) = self.app_handle.try_state::<BackgroundNotificationService>() {
+            service.replay_activity_events(&log.events_after(service.activity_event_seq())?);
+        }
+        if log.needs_snapshot() {
+            log.save_snapshot(data.as_ref())?;
+            self.checkpoint_activity(log.last_seq());
+        }
+
+        *self.storage.lock().unwrap() = Some(Box::new(storage));
+        *self.events.lock().unwrap() = Some(log);
+
+        if let Some(data) = data {
+            self.share(&data, None)?;
+        }
+        Ok(())
+    }
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+/// The stored data, or `None` if the frontend has never saved any
+#[tauri::command]
+pub async fn load_app_data(app_handle: AppHandle) -> Result<Option<AppData>, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.data()
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn save_app_data(app_handle: AppHandle, data: AppData) -> Result<(), String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.update(|stored| *stored = data).map(|_| ())
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+/// Replace the fields present in `patch`, returning the updated data
+#[tauri::command]
+pub async fn patch_app_data(app_handle: AppHandle, patch: AppDataPatch) -> Result<AppData, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.update(|stored| patch.apply(stored))
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn import_app_data(app_handle: AppHandle, path: String) -> Result<AppData, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.import_json(Path::new(&path))
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn export_app_data(app_handle: AppHandle, path: String) -> Result<(), String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.export_json(Path::new(&path))
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+/// Timestamped completions in a range of days, e.g. every Tuesday of a quarter
+#[tauri::command]
+pub async fn query_completions(app_handle: AppHandle, query: CompletionQuery) -> Result<Vec<CompletionRecord>, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.with_storage(|storage| storage.completions(&query))
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn get_xp_ledger(app_handle: AppHandle, from: NaiveDate, to: NaiveDate) -> Result<Vec<XpEntry>, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.with_storage(|storage| storage.xp_ledger(from, to))
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+/// Logged changes after `after_seq`, for auditing
+#[tauri::command]
+pub async fn get_event_log(app_handle: AppHandle, after_seq: Option<u64>, limit: Option<usize>) -> Result<Vec<LoggedEvent>, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        let mut events = store.events_after(after_seq.unwrap_or(0))?;
+        events.truncate(limit.unwrap_or(usize::MAX));
+        Ok(events)
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
+
+/// Undo every change logged after event `seq`, returning the data as it was then
+#[tauri::command]
+pub async fn revert_app_data(app_handle: AppHandle, seq: u64) -> Result<AppData, String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.revert_to(seq)
+    } else {
+        Err("App data not initialized".to_string())
+    }
+}
@@ -0,0 +1,175 @@
+/*!
+ * ================================================================================================
+ * APP SETTINGS
+ * ================================================================================================
+ *
+ * Backend copy of the frontend's `AppSettings`, synced whenever the user changes them. The
+ * backend only needs a few of these: `language` to pick the notification locale and
+ * `startupBehavior` for launches at login.
+ * Stored as `app_settings.json` next to the notification config.
+ *
+ * @version 1.0.0
+ */
+
+use crate::messages;
+use crate::app_data::AppDataStore;
+use crate::persistence::{self, Schema};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Id of the system tray icon, so its tooltip can be updated after a language change
+pub const TRAY_ICON_ID: &str = "main-tray";
+
+fn default_language() -> String {
+    messages::FALLBACK_LOCALE.to_string()
+}
+
+/// What a launch at system startup does
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupBehavior {
+    /// Stay in the tray and show what's due today
+    #[default]
+    Summary,
+    /// Stay in the tray without a notification
+    Silent,
+    /// Show the main window as on a normal launch
+    OpenWindow,
+}
+
+/// Schema history of `app_settings.json`: v1 added the envelope
+pub const APP_SETTINGS_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+/// Mirrors `AppSettings` in `src/types/index.ts`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub gradient_colors: Vec<String>,
+    #[serde(default)]
+    pub animations: Option<bool>,
+    #[serde(default)]
+    pub sound_effects: Option<bool>,
+    #[serde(default)]
+    pub notifications: Option<bool>,
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub start_of_week: Option<u8>,
+    #[serde(default)]
+    pub startup_behavior: StartupBehavior,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            gradient_colors: Vec::new(),
+            animations: None,
+            sound_effects: None,
+            notifications: None,
+            language: default_language(),
+            start_of_week: None,
+            startup_behavior: StartupBehavior::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Catalog locale for `language`
+    pub fn locale(&self) -> &'static str {
+        messages::resolve_locale(&self.language)
+    }
+}
+
+pub struct AppSettingsStore {
+    settings: Mutex<AppSettings>,
+    app_handle: AppHandle,
+}
+
+impl AppSettingsStore {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            settings: Mutex::new(AppSettings::default()),
+            app_handle,
+        }
+    }
+
+    pub fn settings(&self) -> AppSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn update(&self, new_settings: AppSettings) {
+        let mut settings = self.settings.lock().unwrap();
+        *settings = new_settings;
+
+        if let Err(e) = self.save_to_file(&settings) {
+            log::warn!("Could not save app settings: {}", e);
+        }
+    }
+
+    fn save_to_file(&self, settings: &AppSettings) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+
+        persistence::save_json(&config_dir.join("app_settings.json"), settings, &APP_SETTINGS_SCHEMA)
+    }
+
+    pub fn load_from_file(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+
+        let settings_path = config_dir.join("app_settings.json");
+        if let Some(loaded) = persistence::load_json::<AppSettings>(&settings_path, &APP_SETTINGS_SCHEMA)? {
+            let settings = persistence::report_recovery(&self.app_handle, loaded);
+            *self.settings.lock().unwrap() = settings;
+        }
+
+        Ok(())
+    }
+}
+
+/// Locale for user-facing backend strings, English until the frontend has synced its settings
+pub fn current_locale(app_handle: &AppHandle) -> &'static str {
+    app_handle
+        .try_state::<AppSettingsStore>()
+        .map_or(messages::FALLBACK_LOCALE, |store| store.settings().locale())
+}
+
+/// Localized message for `key` in the user's current locale
+pub fn localized(app_handle: &AppHandle, key: &str, params: &[(&str, &str)]) -> String {
+    messages::message(current_locale(app_handle), key, params)
+}
+
+/// Store `settings` as the backend's copy, refreshing the tray tooltip if the language changed
+pub fn apply_app_settings(app_handle: &AppHandle, settings: AppSettings) -> Result<(), String> {
+    if let Some(store) = app_handle.try_state::<AppSettingsStore>() {
+        let language_changed = store.settings().locale() != settings.locale();
+        store.update(settings);
+
+        if language_changed {
+            if let Some(tray) = app_handle.tray_by_id(TRAY_ICON_ID) {
+                let tooltip = localized(app_handle, "tray.tooltip", &[]);
+                tray.set_tooltip(Some(tooltip)).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    } else {
+        Err("App settings not initialized".to_string())
+    }
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn sync_app_settings(app_handle: AppHandle, settings: AppSettings) -> Result<(), String> {
+    if let Some(store) = app_handle.try_state::<AppDataStore>() {
+        store.set_settings(settings.clone());
+    }
+    apply_app_settings(&app_handle, settings)
+}
@@ -1,4 +1,4 @@
-/**
+/*!
  * ================================================================================================
  * BACKGROUND NOTIFICATION SERVICE
  * ================================================================================================
@@ -9,14 +9,36 @@
  * @version 1.0.0
  */
 
-use chrono::{DateTime, Local, Timelike};
+use crate::activity_insights::ActivityInsights;
+use crate::app_data::AppDataStore;
+use crate::clock::SharedClock;
+use crate::digests::{DigestConfig, EveningRecap, MorningDigest};
+use crate::encryption;
+use crate::event_log::{Event, LoggedEvent};
+use crate::habit_reminders::{HabitReminder, HABIT_REMINDERS_SCHEMA};
+use crate::habit_streaks::{HabitFrequency, HabitStreak, StreakWarningsSent, HABIT_STREAKS_SCHEMA};
+use crate::messages;
+use crate::notification_budget::{BudgetCategory, BudgetLimits, NotificationBudgets};
+use crate::persistence::{self, Schema};
+use crate::quiet_hours::QuietHours;
+use crate::random_reminders::{random_reminder_times, RandomReminderPlan};
+use crate::startup_summary::StartupSummary;
+use crate::weekly_review::{self, ProgressData, WeeklyReview, WeeklyReviewConfig, PROGRESS_SCHEMA};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
 use tauri::{AppHandle, Manager};
+use tokio::sync::watch;
 
+/// Schema history of `notification_config.json`: v1 added the envelope
+pub const NOTIFICATION_CONFIG_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+/// Fields missing from an older file take their defaults
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NotificationConfig {
     pub enabled: bool,
     pub streak_reminders: bool,
@@ -24,11 +46,50 @@ pub struct NotificationConfig {
     pub reminder_start_hour: u32,
     pub reminder_end_hour: u32,
     pub max_reminders_per_day: u32,
+    /// Streaks at least this long are warned about before their period ends
     pub streak_warning_threshold: u32,
     pub sound_enabled: bool,
     pub intelligent_timing: bool,
     pub adaptive_frequency: bool,
     pub streak_protection_hours: Vec<u32>,
+    #[serde(default = "default_inactivity_threshold_hours")]
+    pub inactivity_threshold_hours: u32,
+    /// When unset, quiet hours are derived from `reminder_start_hour`/`reminder_end_hour`
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Per-category limits over a rolling 24 hours. When unset, streak protection and random
+    /// reminders are each limited to `max_reminders_per_day`.
+    #[serde(default)]
+    pub budgets: Option<BudgetLimits>,
+    /// Random reminders per day, capped by the random reminder budget
+    #[serde(default = "default_random_reminders_per_day")]
+    pub random_reminders_per_day: u32,
+    #[serde(default = "default_random_reminder_spacing_minutes")]
+    pub random_reminder_spacing_minutes: u32,
+    /// Fixed seed for reproducible random schedules. A per-install seed is used when unset.
+    #[serde(default)]
+    pub random_reminder_seed: Option<u64>,
+    /// Morning digest and evening recap times
+    #[serde(default)]
+    pub digests: DigestConfig,
+    #[serde(default)]
+    pub weekly_review: WeeklyReviewConfig,
+}
+
+fn default_inactivity_threshold_hours() -> u32 {
+    20
+}
+
+fn default_random_reminders_per_day() -> u32 {
+    2
+}
+
+fn default_random_reminder_spacing_minutes() -> u32 {
+    90
+}
+
+fn new_random_seed() -> u64 {
+    rand::random()
 }
 
 impl Default for NotificationConfig {
@@ -45,17 +106,127 @@ impl Default for NotificationConfig {
             intelligent_timing: true,
             adaptive_frequency: true,
             streak_protection_hours: vec![12, 18, 20],
+            inactivity_threshold_hours: default_inactivity_threshold_hours(),
+            quiet_hours: None,
+            budgets: None,
+            random_reminders_per_day: default_random_reminders_per_day(),
+            random_reminder_spacing_minutes: default_random_reminder_spacing_minutes(),
+            random_reminder_seed: None,
+            digests: DigestConfig::default(),
+            weekly_review: WeeklyReviewConfig::default(),
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Quiet hours every notification path must respect. An active window that can't be turned
+    /// into quiet hours is refused by `update_notification_config`, so only an older saved config
+    /// can have one; the default quiet hours stand in for it.
+    pub fn effective_quiet_hours(&self) -> QuietHours {
+        self.quiet_hours.clone().unwrap_or_else(|| {
+            QuietHours::from_active_window(self.reminder_start_hour, self.reminder_end_hour).unwrap_or_default()
+        })
+    }
+
+    pub fn effective_budget_limits(&self) -> BudgetLimits {
+        self.budgets
+            .unwrap_or_else(|| BudgetLimits::from_daily_cap(self.max_reminders_per_day))
+    }
+
+    /// Random reminders are drawn inside the active window, before quiet hours are applied
+    pub fn random_reminder_plan(&self) -> RandomReminderPlan {
+        let hour_start = |hour: u32| NaiveTime::from_hms_opt(hour.min(23), 0, 0).unwrap_or(NaiveTime::MIN);
+        RandomReminderPlan {
+            count: self.random_reminders_per_day.min(self.effective_budget_limits().random),
+            window_start: hour_start(self.reminder_start_hour),
+            window_end: if self.reminder_end_hour >= 24 {
+                NaiveTime::from_hms_opt(23, 59, 0).unwrap_or(NaiveTime::MIN)
+            } else {
+                hour_start(self.reminder_end_hour)
+            },
+            min_spacing_minutes: self.random_reminder_spacing_minutes,
         }
     }
 }
 
+/// Schema history of `activity_data.json`: v1 added the envelope and dropped the daily
+/// notification counter that per-category budgets replaced; v2 dropped the habit completions
+/// kept apart from the app data; v3 dropped the weekly review streak snapshots
+pub const ACTIVITY_DATA_SCHEMA: Schema =
+    Schema { migrations: &[drop_daily_notification_counter, drop_habit_completions, drop_review_streaks] };
+
+fn drop_daily_notification_counter(mut data: serde_json::Value) -> Result<serde_json::Value, String> {
+    let activity = data.as_object_mut().ok_or("activity data is not an object")?;
+    activity.remove("notifications_sent_today");
+    activity.remove("last_notification_date");
+    Ok(data)
+}
+
+fn drop_habit_completions(mut data: serde_json::Value) -> Result<serde_json::Value, String> {
+    let activity = data.as_object_mut().ok_or("activity data is not an object")?;
+    activity.remove("habit_completions");
+    Ok(data)
+}
+
+fn drop_review_streaks(mut data: serde_json::Value) -> Result<serde_json::Value, String> {
+    let activity = data.as_object_mut().ok_or("activity data is not an object")?;
+    activity.remove("review_streaks");
+    Ok(data)
+}
+
+/// Fields missing from an older file take their defaults
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ActivityData {
     pub last_activity: DateTime<Local>,
     pub daily_sessions: Vec<DateTime<Local>>,
-    pub habit_completions: std::collections::HashMap<String, DateTime<Local>>,
-    pub notifications_sent_today: u32,
-    pub last_notification_date: Option<DateTime<Local>>,
+    /// `last_activity` value at the time the last streak-protection reminder was sent.
+    /// A reminder is only sent once per inactivity episode, so this must differ from
+    /// `last_activity` before another one can fire.
+    #[serde(default)]
+    pub streak_reminder_sent_for: Option<DateTime<Local>>,
+    /// Reminders pushed back from a notification's "Snooze" button
+    #[serde(default)]
+    pub snoozed_reminders: Vec<SnoozedReminder>,
+    /// Longer-term session/completion histogram and reminder engagement
+    #[serde(default)]
+    pub insights: ActivityInsights,
+    /// Notifications sent per budget category over the last 24 hours
+    #[serde(default)]
+    pub budgets: NotificationBudgets,
+    /// Streak-at-risk warnings sent per habit in its current period
+    #[serde(default)]
+    pub streak_warnings_sent: HashMap<String, StreakWarningsSent>,
+    /// Per-install seed for random reminders when the config doesn't fix one
+    #[serde(default = "new_random_seed")]
+    pub random_seed: u64,
+    /// Last event log entry reflected here; later activity is replayed from the log on load
+    #[serde(default)]
+    pub event_seq: u64,
+}
+
+impl ActivityData {
+    pub fn record_session(&mut self, now: DateTime<Local>) {
+        self.last_activity = now;
+        self.daily_sessions.push(now);
+        self.insights.record_session(now);
+
+        // Clean old sessions (keep only last 24 hours)
+        self.daily_sessions.retain(|session| {
+            (now - *session).num_hours() <= 24
+        });
+    }
+
+    /// Learn from the time of day of a completion; the completion itself is in the app data
+    pub fn record_habit_completion(&mut self, now: DateTime<Local>) {
+        self.insights.record_completion(now);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnoozedReminder {
+    pub reminder: Reminder,
+    pub due: DateTime<Local>,
 }
 
 impl Default for ActivityData {
@@ -63,9 +234,13 @@ impl Default for ActivityData {
         Self {
             last_activity: Local::now(),
             daily_sessions: Vec::new(),
-            habit_completions: std::collections::HashMap::new(),
-            notifications_sent_today: 0,
-            last_notification_date: None,
+            streak_reminder_sent_for: None,
+            snoozed_reminders: Vec::new(),
+            insights: ActivityInsights::default(),
+            budgets: NotificationBudgets::default(),
+            streak_warnings_sent: HashMap::new(),
+            random_seed: new_random_seed(),
+            event_seq: 0,
         }
     }
 }
@@ -73,88 +248,93 @@ impl Default for ActivityData {
 pub struct BackgroundNotificationService {
     config: Arc<Mutex<NotificationConfig>>,
     activity_data: Arc<Mutex<ActivityData>>,
+    habit_reminders: Arc<Mutex<Vec<HabitReminder>>>,
+    habit_streaks: Arc<Mutex<Vec<HabitStreak>>>,
+    progress: Arc<Mutex<ProgressData>>,
     app_handle: AppHandle,
+    clock: SharedClock,
+    schedule_changed: watch::Sender<()>,
 }
 
 impl BackgroundNotificationService {
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: AppHandle, clock: SharedClock) -> Self {
+        let activity = ActivityData { last_activity: clock.now(), ..ActivityData::default() };
         Self {
             config: Arc::new(Mutex::new(NotificationConfig::default())),
-            activity_data: Arc::new(Mutex::new(ActivityData::default())),
+            activity_data: Arc::new(Mutex::new(activity)),
+            habit_reminders: Arc::new(Mutex::new(Vec::new())),
+            habit_streaks: Arc::new(Mutex::new(Vec::new())),
+            progress: Arc::new(Mutex::new(ProgressData::default())),
             app_handle,
+            clock,
+            schedule_changed: watch::channel(()).0,
         }
     }
 
-    pub fn start_background_service(&self) {
-        let config = Arc::clone(&self.config);
-        let activity_data = Arc::clone(&self.activity_data);
-        let app_handle = self.app_handle.clone();
-
-        thread::spawn(move || {
-            loop {
-                // Sleep for 1 hour between checks
-                thread::sleep(Duration::from_secs(3600));
+    /// Current time according to the service's clock
+    pub fn now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
 
-                let should_send_notification = {
-                    let config_guard = config.lock().unwrap();
-                    let mut activity_guard = activity_data.lock().unwrap();
+    /// Receiver that is notified whenever a change may move the next reminder deadline
+    pub fn subscribe_schedule_changes(&self) -> watch::Receiver<()> {
+        self.schedule_changed.subscribe()
+    }
 
-                    if !config_guard.enabled {
-                        continue;
-                    }
+    /// The next instant strictly after `now` at which reminder policy needs to be evaluated
+    pub fn next_deadline(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let config = self.config.lock().unwrap();
+        let habit_reminders = self.habit_reminders.lock().unwrap();
+        let habit_streaks = self.habit_streaks.lock().unwrap();
+        let activity = self.activity_data.lock().unwrap();
+        next_reminder_deadline(&config, &habit_reminders, &habit_streaks, &activity, now)
+    }
 
-                    // Check if it's within active hours
-                    let now = Local::now();
-                    let current_hour = now.hour();
-                    
-                    if current_hour < config_guard.reminder_start_hour || current_hour > config_guard.reminder_end_hour {
-                        continue;
-                    }
+    /// Single decision point for all reminder policy. Returns the reminders that came due
+    /// after `since` up to `now`, with missed ones coalesced; the dispatcher charges the
+    /// budgeted ones when they are shown.
+    pub fn due_reminders(&self, since: DateTime<Local>, now: DateTime<Local>) -> Vec<DueReminder> {
+        let config = self.config.lock().unwrap();
+        let habit_reminders = self.habit_reminders.lock().unwrap();
+        let habit_streaks = self.habit_streaks.lock().unwrap();
+        let mut activity = self.activity_data.lock().unwrap();
 
-                    // Check if we've already sent max notifications today
-                    let is_new_day = activity_guard
-                        .last_notification_date
-                        .map_or(true, |last| last.date_naive() != now.date_naive());
+        let reminders = reminders_due_since(&config, &habit_reminders, &habit_streaks, &mut activity, since, now);
+        if !reminders.is_empty() {
+            let _ = self.save_activity_to_file(&activity);
+        }
+        reminders
+    }
 
-                    if is_new_day {
-                        activity_guard.notifications_sent_today = 0;
-                        activity_guard.last_notification_date = Some(now);
-                    }
+    /// What's due at `now`, for the notification shown on a launch at system startup
+    pub fn startup_summary(&self, now: DateTime<Local>) -> StartupSummary {
+        StartupSummary::build(&self.habit_streaks.lock().unwrap(), now)
+    }
 
-                    if activity_guard.notifications_sent_today >= config_guard.max_reminders_per_day {
-                        continue;
-                    }
+    pub fn budget_available(&self, category: BudgetCategory, now: DateTime<Local>) -> bool {
+        let limits = self.config.lock().unwrap().effective_budget_limits();
+        let activity = self.activity_data.lock().unwrap();
+        activity.budgets.has_room(category, &limits, now)
+    }
 
-                    // Check if user has been inactive for more than 12 hours
-                    let hours_since_activity = (now - activity_guard.last_activity).num_hours();
-                    
-                    if hours_since_activity >= 12 {
-                        activity_guard.notifications_sent_today += 1;
-                        true
-                    } else {
-                        false
-                    }
-                };
+    pub fn consume_budget(&self, category: BudgetCategory, now: DateTime<Local>) {
+        let mut activity = self.activity_data.lock().unwrap();
+        activity.budgets.record(category, now);
 
-                if should_send_notification {
-                    let _ = Self::send_background_notification(&app_handle);
-                }
-            }
-        });
+        let _ = self.save_activity_to_file(&activity);
     }
 
-    fn send_background_notification(app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-        use tauri_plugin_notification::NotificationExt;
+    /// Re-deliver `reminder` once `until` has passed
+    pub fn snooze_reminder(&self, reminder: Reminder, until: DateTime<Local>) {
+        let mut activity = self.activity_data.lock().unwrap();
+        activity.snoozed_reminders.push(SnoozedReminder { reminder, due: until });
 
-        let _notification = app_handle
-            .notification()
-            .builder()
-            .title("🎯 HabitQuest Reminder")
-            .body("Don't forget to check in with your habits today! Your streaks are waiting for you.")
-            .icon("habitquest-icon")
-            .show()?;
+        let _ = self.save_activity_to_file(&activity);
+        self.schedule_changed.send_replace(());
+    }
 
-        Ok(())
+    pub fn is_quiet_time(&self, now: DateTime<Local>) -> bool {
+        self.config.lock().unwrap().effective_quiet_hours().is_quiet(now)
     }
 
     pub fn update_config(&self, new_config: NotificationConfig) {
@@ -163,83 +343,773 @@ impl BackgroundNotificationService {
         
         // Save to persistent storage
         let _ = self.save_config_to_file(&config);
+
+        // Let the scheduler recompute its next deadline
+        self.schedule_changed.send_replace(());
     }
 
     pub fn record_activity(&self) {
+        let now = self.clock.now();
         let mut activity = self.activity_data.lock().unwrap();
-        let now = Local::now();
-        activity.last_activity = now;
-        activity.daily_sessions.push(now);
+        activity.record_session(now);
+        self.log_activity(&mut activity, Event::ActivityRecorded, now);
+    }
 
-        // Clean old sessions (keep only last 24 hours)
-        activity.daily_sessions.retain(|session| {
-            (now - *session).num_hours() <= 24
-        });
+    /// Append activity to the event log rather than rewriting `activity_data.json` each time.
+    /// The file is saved when the log is snapshotted, and whenever anything else in it changes.
+    fn log_activity(&self, activity: &mut ActivityData, event: Event, now: DateTime<Local>) {
+        match self.app_handle.try_state::<AppDataStore>().map(|store| store.append_event(now, event)) {
+            Some(Ok(seq)) => activity.event_seq = seq,
+            Some(Err(e)) => log::warn!("Could not log activity: {}", e),
+            None => {}
+        }
+    }
 
-        // Save to persistent storage
+    /// Save the activity data as of event `seq`, which the event log was just snapshotted at
+    pub fn save_activity_checkpoint(&self, seq: u64) {
+        let mut activity = self.activity_data.lock().unwrap();
+        activity.event_seq = activity.event_seq.max(seq);
         let _ = self.save_activity_to_file(&activity);
     }
 
-    pub fn record_habit_completion(&self, habit_id: String) {
+    pub fn activity_event_seq(&self) -> u64 {
+        self.activity_data.lock().unwrap().event_seq
+    }
+
+    /// Apply the activity in `events` that the activity data doesn't reflect yet. It's saved
+    /// with the next snapshot of the log.
+    pub fn replay_activity_events(&self, events: &[LoggedEvent]) {
         let mut activity = self.activity_data.lock().unwrap();
-        activity.habit_completions.insert(habit_id, Local::now());
-        
-        // Save to persistent storage
-        let _ = self.save_activity_to_file(&activity);
+        let applied = activity.event_seq;
+        for logged in events.iter().filter(|logged| logged.seq > applied) {
+            match &logged.event {
+                Event::ActivityRecorded => activity.record_session(logged.at),
+                Event::HabitCompletionRecorded { .. } => activity.record_habit_completion(logged.at),
+                _ => {}
+            }
+            activity.event_seq = logged.seq;
+        }
+    }
+
+    pub fn habit_reminders(&self) -> Vec<HabitReminder> {
+        self.habit_reminders.lock().unwrap().clone()
+    }
+
+    /// Insert a reminder, replacing any existing one with the same id
+    pub fn upsert_habit_reminder(&self, reminder: HabitReminder) {
+        let mut habit_reminders = self.habit_reminders.lock().unwrap();
+        crate::habit_reminders::upsert(&mut habit_reminders, reminder);
+
+        let _ = self.save_habit_reminders_to_file(&habit_reminders);
+        self.schedule_changed.send_replace(());
+    }
+
+    /// Returns false if no reminder with that id exists
+    pub fn remove_habit_reminder(&self, id: &str) -> bool {
+        let mut habit_reminders = self.habit_reminders.lock().unwrap();
+        if !crate::habit_reminders::remove(&mut habit_reminders, id) {
+            return false;
+        }
+
+        let _ = self.save_habit_reminders_to_file(&habit_reminders);
+        self.schedule_changed.send_replace(());
+        true
+    }
+
+    /// Replace the synced habit streaks with the frontend's current ones
+    pub fn set_habit_streaks(&self, streaks: Vec<HabitStreak>) {
+        let mut habit_streaks = self.habit_streaks.lock().unwrap();
+        if *habit_streaks == streaks {
+            return;
+        }
+        *habit_streaks = streaks;
+
+        let _ = self.save_habit_streaks_to_file(&habit_streaks);
+        self.schedule_changed.send_replace(());
+    }
+
+    /// Replace the synced goals and redeemed rewards with the frontend's current ones
+    pub fn set_progress(&self, new_progress: ProgressData) {
+        let mut progress = self.progress.lock().unwrap();
+        if *progress == new_progress {
+            return;
+        }
+        *progress = new_progress;
+
+        let _ = self.save_progress_to_file(&progress);
+    }
+
+    /// Write the Markdown and HTML review of the week starting `week_start` to the reviews
+    /// directory, returning the Markdown report's path. Refused while encryption is enabled, as
+    /// the reports are meant to be opened outside the app and would be readable without the
+    /// passphrase.
+    pub fn write_weekly_review(&self, week_start: NaiveDate) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        if encryption::is_enabled() {
+            return Err("Weekly reviews aren't written while encryption is enabled".into());
+        }
+        let review = WeeklyReview::build(week_start, &self.habit_streaks.lock().unwrap(), &self.progress.lock().unwrap());
+
+        let reviews_dir = self.app_handle.path().app_data_dir()
+            .map_err(|e| format!("Could not resolve app data directory: {}", e))?
+            .join(weekly_review::REVIEWS_DIR);
+        let markdown_path = review.write_reports(&reviews_dir)?;
+
+        Ok(markdown_path)
+    }
+
+    /// Complete a habit in the app data, if it isn't already in this period, and learn from
+    /// the time of day. Reported by the webview after its own completions, and by "Mark done".
+    pub fn record_habit_completion(&self, habit_id: &str) -> Result<(), String> {
+        self.app_handle
+            .try_state::<AppDataStore>()
+            .ok_or("App data not initialized")?
+            .complete_habit(habit_id)?;
+
+        let now = self.clock.now();
+        let mut activity = self.activity_data.lock().unwrap();
+        activity.record_habit_completion(now);
+        self.log_activity(&mut activity, Event::HabitCompletionRecorded { habit_id: habit_id.to_string() }, now);
+        Ok(())
     }
 
     fn save_config_to_file(&self, config: &NotificationConfig) -> Result<(), Box<dyn std::error::Error>> {
-        use std::fs;
-        
         let config_dir = self.app_handle.path().app_config_dir()
             .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
         
-        fs::create_dir_all(&config_dir)?;
-        let config_path = config_dir.join("notification_config.json");
-        let config_json = serde_json::to_string_pretty(config)?;
-        fs::write(config_path, config_json)?;
-        Ok(())
+        persistence::save_json(&config_dir.join("notification_config.json"), config, &NOTIFICATION_CONFIG_SCHEMA)
     }
 
     fn save_activity_to_file(&self, activity: &ActivityData) -> Result<(), Box<dyn std::error::Error>> {
-        use std::fs;
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
         
+        persistence::save_json(&config_dir.join("activity_data.json"), activity, &ACTIVITY_DATA_SCHEMA)
+    }
+
+    fn save_habit_reminders_to_file(&self, habit_reminders: &[HabitReminder]) -> Result<(), Box<dyn std::error::Error>> {
         let config_dir = self.app_handle.path().app_config_dir()
             .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
         
-        fs::create_dir_all(&config_dir)?;
-        let activity_path = config_dir.join("activity_data.json");
-        let activity_json = serde_json::to_string_pretty(activity)?;
-        fs::write(activity_path, activity_json)?;
-        Ok(())
+        persistence::save_json(&config_dir.join("habit_reminders.json"), habit_reminders, &HABIT_REMINDERS_SCHEMA)
     }
 
-    pub fn load_from_files(&self) -> Result<(), Box<dyn std::error::Error>> {
-        use std::fs;
+    fn save_habit_streaks_to_file(&self, habit_streaks: &[HabitStreak]) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
         
+        persistence::save_json(&config_dir.join("habit_streaks.json"), habit_streaks, &HABIT_STREAKS_SCHEMA)
+    }
+
+    fn save_progress_to_file(&self, progress: &ProgressData) -> Result<(), Box<dyn std::error::Error>> {
         let config_dir = self.app_handle.path().app_config_dir()
             .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+        
+        persistence::save_json(&config_dir.join("progress.json"), progress, &PROGRESS_SCHEMA)
+    }
+
+    /// Forget the loaded data without saving, such as when the data is locked
+    pub fn unload(&self) {
+        *self.config.lock().unwrap() = NotificationConfig::default();
+        *self.activity_data.lock().unwrap() = ActivityData { last_activity: self.clock.now(), ..ActivityData::default() };
+        self.habit_reminders.lock().unwrap().clear();
+        self.habit_streaks.lock().unwrap().clear();
+        *self.progress.lock().unwrap() = ProgressData::default();
+        self.schedule_changed.send_replace(());
+    }
+
+    /// Load every data file. A file that fails to load is reported but doesn't stop the others
+    /// from loading, and keeps its defaults in memory.
+    pub fn load_from_files(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+        let mut failures = Vec::new();
 
         // Load config
         let config_path = config_dir.join("notification_config.json");
-        if config_path.exists() {
-            let config_json = fs::read_to_string(config_path)?;
-            let config: NotificationConfig = serde_json::from_str(&config_json)?;
+        if let Some(config) = self.load_file::<NotificationConfig>(&config_path, &NOTIFICATION_CONFIG_SCHEMA, &mut failures) {
             let mut config_guard = self.config.lock().unwrap();
             *config_guard = config;
+            self.schedule_changed.send_replace(());
         }
 
         // Load activity data
         let activity_path = config_dir.join("activity_data.json");
-        if activity_path.exists() {
-            let activity_json = fs::read_to_string(activity_path)?;
-            let activity: ActivityData = serde_json::from_str(&activity_json)?;
+        if let Some(activity) = self.load_file::<ActivityData>(&activity_path, &ACTIVITY_DATA_SCHEMA, &mut failures) {
             let mut activity_guard = self.activity_data.lock().unwrap();
             *activity_guard = activity;
         }
 
-        Ok(())
+        // Load habit reminders
+        let reminders_path = config_dir.join("habit_reminders.json");
+        if let Some(habit_reminders) = self.load_file::<Vec<HabitReminder>>(&reminders_path, &HABIT_REMINDERS_SCHEMA, &mut failures) {
+            let mut reminders_guard = self.habit_reminders.lock().unwrap();
+            *reminders_guard = habit_reminders;
+            self.schedule_changed.send_replace(());
+        }
+
+        // Load habit streaks
+        let streaks_path = config_dir.join("habit_streaks.json");
+        if let Some(habit_streaks) = self.load_file::<Vec<HabitStreak>>(&streaks_path, &HABIT_STREAKS_SCHEMA, &mut failures) {
+            let mut streaks_guard = self.habit_streaks.lock().unwrap();
+            *streaks_guard = habit_streaks;
+            self.schedule_changed.send_replace(());
+        }
+
+        // Load goals and redeemed rewards
+        let progress_path = config_dir.join("progress.json");
+        if let Some(progress) = self.load_file::<ProgressData>(&progress_path, &PROGRESS_SCHEMA, &mut failures) {
+            let mut progress_guard = self.progress.lock().unwrap();
+            *progress_guard = progress;
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("; ").into())
+        }
+    }
+
+    fn load_file<T: DeserializeOwned>(&self, path: &Path, schema: &Schema, failures: &mut Vec<String>) -> Option<T> {
+        match persistence::load_json(path, schema) {
+            Ok(loaded) => loaded.map(|loaded| persistence::report_recovery(&self.app_handle, loaded)),
+            Err(e) => {
+                failures.push(e.to_string());
+                None
+            }
+        }
+    }
+}
+
+// ================================================================================================
+// REMINDER POLICY
+// ================================================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Reminder {
+    /// The user has been inactive past the configured threshold without completing a habit today
+    StreakProtection { hours_inactive: i64 },
+    /// One of the day's randomized check-ins, `index` counting from zero
+    Random { index: u32, count: u32 },
+    /// A reminder the user scheduled for a specific habit
+    HabitSpecific { reminder_id: String, habit_id: String, habit_name: Option<String> },
+    /// A habit's streak ends with its period soon and it hasn't been completed in it yet
+    StreakAtRisk {
+        habit_id: String,
+        habit_name: String,
+        frequency: HabitFrequency,
+        streak: u32,
+        hours_left: i64,
+        /// The last warning this period
+        final_warning: bool,
+    },
+    /// Today's due habits and the XP they're worth
+    MorningDigest { digest: MorningDigest },
+    /// Today's progress and streak changes
+    EveningRecap { recap: EveningRecap },
+    /// The review of the week starting `week_start` was written to the reviews directory
+    WeeklyReview { week_start: NaiveDate },
+    /// Reminders whose time passed while the app was suspended or the clock jumped, sent as one
+    CatchUp { missed: Vec<Reminder> },
+    /// A notification requested by the frontend, kept so it can be snoozed
+    Custom { title: String, body: String, habit_id: Option<String> },
+}
+
+impl Reminder {
+    /// The habit this reminder is about, if any
+    pub fn habit_id(&self) -> Option<&str> {
+        match self {
+            Reminder::StreakProtection { .. }
+            | Reminder::Random { .. }
+            | Reminder::MorningDigest { .. }
+            | Reminder::EveningRecap { .. }
+            | Reminder::WeeklyReview { .. }
+            | Reminder::CatchUp { .. } => None,
+            Reminder::HabitSpecific { habit_id, .. } | Reminder::StreakAtRisk { habit_id, .. } => Some(habit_id),
+            Reminder::Custom { habit_id, .. } => habit_id.as_deref(),
+        }
+    }
+
+    /// Why this reminder fired, as recorded in the notification history
+    pub fn trigger_reason(&self) -> String {
+        match self {
+            Reminder::StreakProtection { hours_inactive } => {
+                format!("No activity for {} hours and no habit completed today", hours_inactive)
+            }
+            Reminder::Random { index, count } => format!("Random check-in {} of {} today", index + 1, count),
+            Reminder::HabitSpecific { reminder_id, habit_id, .. } => {
+                format!("Scheduled reminder {} for habit {}", reminder_id, habit_id)
+            }
+            Reminder::StreakAtRisk { habit_id, streak, hours_left, .. } => {
+                format!("{}-period streak of habit {} ends in {} hours", streak, habit_id, hours_left)
+            }
+            Reminder::MorningDigest { digest } => format!("Morning digest with {} habits due", digest.due_habits.len()),
+            Reminder::EveningRecap { recap } => format!(
+                "Evening recap with {} of {} habits completed",
+                recap.completed.len(),
+                recap.completed.len() + recap.remaining.len()
+            ),
+            Reminder::WeeklyReview { week_start } => format!("Weekly review of the week of {} written", week_start),
+            Reminder::CatchUp { missed } => format!("Caught up on {} reminders missed while suspended", missed.len()),
+            Reminder::Custom { .. } => "Requested by the app".to_string(),
+        }
+    }
+
+    /// Title and body shown in the notification, in `locale`
+    pub fn notification_text(&self, locale: &str) -> (String, String) {
+        match self {
+            Reminder::StreakProtection { hours_inactive } => (
+                messages::message(locale, "streak_protection.title", &[]),
+                messages::message(
+                    locale,
+                    "streak_protection.body",
+                    &[("hours_inactive", &hours_inactive.to_string())],
+                ),
+            ),
+            Reminder::Random { index, .. } => {
+                let variant = *index as usize % RANDOM_REMINDER_VARIANTS;
+                (
+                    messages::message(locale, &format!("random.{}.title", variant), &[]),
+                    messages::message(locale, &format!("random.{}.body", variant), &[]),
+                )
+            }
+            Reminder::HabitSpecific { habit_name, .. } => (
+                messages::message(locale, "habit_reminder.title", &[]),
+                match habit_name {
+                    Some(name) => messages::message(locale, "habit_reminder.body", &[("habit", name)]),
+                    None => messages::message(locale, "habit_reminder.body_unnamed", &[]),
+                },
+            ),
+            Reminder::StreakAtRisk { habit_name, frequency, streak, hours_left, final_warning, .. } => {
+                let title_key = if *final_warning { "streak_at_risk.title_final" } else { "streak_at_risk.title" };
+                let unit = messages::message(locale, &format!("streak_at_risk.unit.{}", frequency_key(*frequency)), &[]);
+                let streak = streak.to_string();
+                let hours = hours_left.to_string();
+                let days = (hours_left / 24).to_string();
+                let mut params = vec![("habit", habit_name.as_str()), ("streak", streak.as_str()), ("unit", unit.as_str())];
+                let body_key = match *hours_left {
+                    hours_left if hours_left < 1 => "streak_at_risk.body_soon",
+                    hours_left if hours_left < 48 => {
+                        params.push(("hours_left", hours.as_str()));
+                        "streak_at_risk.body"
+                    }
+                    _ => {
+                        params.push(("days_left", days.as_str()));
+                        "streak_at_risk.body_days"
+                    }
+                };
+                (messages::message(locale, title_key, &[]), messages::message(locale, body_key, &params))
+            }
+            Reminder::MorningDigest { digest } => digest.notification_text(locale),
+            Reminder::EveningRecap { recap } => recap.notification_text(locale),
+            Reminder::WeeklyReview { week_start } => (
+                messages::message(locale, "weekly_review.title", &[]),
+                messages::message(
+                    locale,
+                    "weekly_review.body",
+                    &[
+                        ("week_start", &week_start.format("%Y-%m-%d").to_string()),
+                        ("file", &format!("{}/{}.md", weekly_review::REVIEWS_DIR, weekly_review::report_name(*week_start))),
+                    ],
+                ),
+            ),
+            Reminder::CatchUp { missed } => (
+                messages::message(locale, "catch_up.title", &[]),
+                messages::message(locale, "catch_up.body", &[("count", &missed.len().to_string())]),
+            ),
+            Reminder::Custom { title, body, .. } => (title.clone(), body.clone()),
+        }
+    }
+}
+
+/// Catalog key suffix for a habit frequency
+fn frequency_key(frequency: HabitFrequency) -> &'static str {
+    match frequency {
+        HabitFrequency::Daily => "daily",
+        HabitFrequency::Weekly => "weekly",
+        HabitFrequency::Monthly => "monthly",
+        HabitFrequency::Yearly => "yearly",
+    }
+}
+
+/// How long after a scheduled slot it is still considered due, to absorb timer jitter
+const SLOT_GRACE_MINUTES: i64 = 5;
+
+/// How far back a catch-up pass looks for missed reminders; older ones are dropped
+const MAX_CATCH_UP_HOURS: i64 = 24;
+
+/// How many days past today `next_reminder_deadline` looks for a slot
+const DEADLINE_LOOKAHEAD_DAYS: i64 = 7;
+
+/// Reminder kind keys used for engagement tracking
+const STREAK_PROTECTION_KIND: &str = "streak_protection";
+const RANDOM_REMINDER_KIND: &str = "random_reminder";
+
+/// Number of random check-in messages in the catalog (`random.0` .. `random.2`)
+const RANDOM_REMINDER_VARIANTS: usize = 3;
+
+/// Concrete times at which streak protection is checked on the given day, in order.
+/// Hours outside 0-23 are ignored, as are local times skipped by a DST transition.
+/// With `intelligent_timing`, each hour moves to the nearby hour the user is usually active in.
+fn streak_protection_slots(config: &NotificationConfig, insights: &ActivityInsights, day: NaiveDate) -> Vec<DateTime<Local>> {
+    let mut hours: Vec<u32> = config
+        .streak_protection_hours
+        .iter()
+        .copied()
+        .filter(|h| *h < 24)
+        .map(|hour| if config.intelligent_timing { insights.preferred_hour_near(hour) } else { hour })
+        .collect();
+    hours.sort_unstable();
+    hours.dedup();
+
+    hours
+        .into_iter()
+        .filter_map(|hour| {
+            let time = NaiveTime::from_hms_opt(hour, 0, 0)?;
+            Local.from_local_datetime(&day.and_time(time)).earliest()
+        })
+        .collect()
+}
+
+/// Next time strictly after `now` at which a reminder may fire
+pub fn next_reminder_deadline(
+    config: &NotificationConfig,
+    habit_reminders: &[HabitReminder],
+    habit_streaks: &[HabitStreak],
+    activity: &ActivityData,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    if !config.enabled {
+        return None;
+    }
+
+    // A week ahead covers weekday-only habit reminders and days a DST change skips a slot on
+    let today = now.date_naive();
+    (0..=DEADLINE_LOOKAHEAD_DAYS)
+        .map(|offset| today + Duration::days(offset))
+        .flat_map(|day| {
+            let mut slots = streak_protection_slots(config, &activity.insights, day);
+            slots.extend(random_reminder_slots(config, activity, day));
+            slots.extend(habit_reminders.iter().filter_map(|reminder| reminder.occurrence_on(day)));
+            slots.extend(digest_slots(config, day));
+            slots
+        })
+        .chain(streak_warning_slots(config, habit_streaks, now))
+        .chain(activity.snoozed_reminders.iter().map(|snoozed| snoozed.due))
+        .filter(|slot| *slot > now)
+        .min()
+}
+
+/// Today's random reminder times, minus any that land in quiet hours
+fn random_reminder_slots(config: &NotificationConfig, activity: &ActivityData, day: NaiveDate) -> Vec<DateTime<Local>> {
+    if !config.random_reminders {
+        return Vec::new();
+    }
+
+    let seed = config.random_reminder_seed.unwrap_or(activity.random_seed);
+    let quiet_hours = config.effective_quiet_hours();
+    random_reminder_times(day, seed, &config.random_reminder_plan())
+        .into_iter()
+        .filter_map(|time| Local.from_local_datetime(&day.and_time(time)).earliest())
+        .filter(|slot| !quiet_hours.is_quiet(*slot))
+        .collect()
+}
+
+/// Streak-at-risk warning times in the current and next period of each habit. Whether a
+/// streak is actually at risk is only checked when a warning comes due.
+fn streak_warning_slots(config: &NotificationConfig, habit_streaks: &[HabitStreak], now: DateTime<Local>) -> Vec<DateTime<Local>> {
+    if !config.streak_reminders {
+        return Vec::new();
+    }
+
+    let quiet_hours = config.effective_quiet_hours();
+    habit_streaks
+        .iter()
+        .flat_map(|habit| {
+            let current_end = habit.frequency.period_bounds(now).map(|(_, end)| end);
+            let next_end = current_end.and_then(|end| habit.frequency.period_bounds(end)).map(|(_, end)| end);
+            current_end
+                .into_iter()
+                .chain(next_end)
+                .flat_map(|end| habit.warning_times(config.streak_warning_threshold, &quiet_hours, end))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Morning digest, evening recap and weekly review times on `day`, minus any that land in
+/// quiet hours
+fn digest_slots(config: &NotificationConfig, day: NaiveDate) -> Vec<DateTime<Local>> {
+    let quiet_hours = config.effective_quiet_hours();
+    config
+        .digests
+        .morning_on(day)
+        .into_iter()
+        .chain(config.digests.evening_on(day))
+        .chain(config.weekly_review.occurrence_on(day))
+        .filter(|slot| !quiet_hours.is_quiet(*slot))
+        .collect()
+}
+
+fn in_slot(slot: DateTime<Local>, now: DateTime<Local>) -> bool {
+    now >= slot && now - slot < Duration::minutes(SLOT_GRACE_MINUTES)
+}
+
+/// Whether `now` falls inside one of today's streak-protection slots
+fn in_streak_protection_slot(config: &NotificationConfig, insights: &ActivityInsights, now: DateTime<Local>) -> bool {
+    streak_protection_slots(config, insights, now.date_naive())
+        .into_iter()
+        .any(|slot| in_slot(slot, now))
+}
+
+/// A reminder picked by `decide_reminders`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueReminder {
+    pub reminder: Reminder,
+    /// Budget the reminder is charged to when delivered
+    pub budget: Option<BudgetCategory>,
+}
+
+/// Decide which reminders are due at `now`. Mutates `activity` to account for the reminders it
+/// returns (inactivity episode, engagement), so callers must deliver everything returned.
+/// Leaves room in each budget for what it picks but does not charge it; the dispatcher does.
+pub fn decide_reminders(
+    config: &NotificationConfig,
+    habit_reminders: &[HabitReminder],
+    habit_streaks: &[HabitStreak],
+    activity: &mut ActivityData,
+    now: DateTime<Local>,
+) -> Vec<DueReminder> {
+    if !config.enabled || config.effective_quiet_hours().is_quiet(now) {
+        return Vec::new();
+    }
+
+    // Snoozed reminders were already counted against the budget when first delivered
+    let (snoozed, pending): (Vec<_>, Vec<_>) = activity
+        .snoozed_reminders
+        .drain(..)
+        .partition(|snoozed| snoozed.due <= now);
+    activity.snoozed_reminders = pending;
+
+    let mut due: Vec<DueReminder> = snoozed
+        .into_iter()
+        .map(|snoozed| DueReminder { reminder: snoozed.reminder, budget: None })
+        .collect();
+
+    let limits = config.effective_budget_limits();
+    let habit_room = activity.budgets.remaining(BudgetCategory::HabitSpecific, &limits, now) as usize;
+    let mut habit_specific = habit_specific_reminders(habit_reminders, habit_streaks, now);
+    habit_specific.extend(streak_at_risk_reminders(config, habit_streaks, activity, now));
+    due.extend(
+        habit_specific
+            .into_iter()
+            .take(habit_room)
+            .map(|reminder| DueReminder { reminder, budget: Some(BudgetCategory::HabitSpecific) }),
+    );
+
+    if activity.budgets.has_room(BudgetCategory::StreakProtection, &limits, now) {
+        if let Some(reminder) = streak_protection_reminder(config, habit_streaks, activity, now) {
+            due.push(DueReminder { reminder, budget: Some(BudgetCategory::StreakProtection) });
+        }
+    }
+
+    let digest_room = activity.budgets.remaining(BudgetCategory::Digest, &limits, now) as usize;
+    due.extend(
+        digest_reminders(config, habit_streaks, now)
+            .into_iter()
+            .take(digest_room)
+            .map(|reminder| DueReminder { reminder, budget: Some(BudgetCategory::Digest) }),
+    );
+
+    if activity.budgets.has_room(BudgetCategory::Random, &limits, now) {
+        if let Some(reminder) = random_reminder(config, activity, now) {
+            due.push(DueReminder { reminder, budget: Some(BudgetCategory::Random) });
+        }
+    }
+
+    due
+}
+
+/// Reminders that came due in `(since, now]`, each decided at its own deadline. Those whose
+/// deadline passed more than the slot grace period ago were missed (suspend, clock jump) and
+/// are coalesced into a single `Reminder::CatchUp` when there are several, which isn't charged
+/// to any budget.
+pub fn reminders_due_since(
+    config: &NotificationConfig,
+    habit_reminders: &[HabitReminder],
+    habit_streaks: &[HabitStreak],
+    activity: &mut ActivityData,
+    since: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Vec<DueReminder> {
+    let mut due = Vec::new();
+    let mut missed = Vec::new();
+
+    let mut checked = since.max(now - Duration::hours(MAX_CATCH_UP_HOURS));
+    while let Some(deadline) = next_reminder_deadline(config, habit_reminders, habit_streaks, activity, checked)
+        .filter(|deadline| *deadline <= now)
+    {
+        let reminders = decide_reminders(config, habit_reminders, habit_streaks, activity, deadline);
+        if now - deadline < Duration::minutes(SLOT_GRACE_MINUTES) {
+            due.extend(reminders);
+        } else {
+            missed.extend(reminders);
+        }
+        checked = deadline;
+    }
+
+    if missed.len() > 1 {
+        let missed = missed.into_iter().map(|due| due.reminder).collect();
+        due.insert(0, DueReminder { reminder: Reminder::CatchUp { missed }, budget: None });
+    } else {
+        due.splice(0..0, missed);
     }
+    due
+}
+
+fn completed_on(habit: &HabitStreak, day: NaiveDate) -> bool {
+    habit.last_completed_at.is_some_and(|completed_at| completed_at.date_naive() == day)
+}
+
+/// Habit reminders whose time slot is now, skipping habits already completed today
+fn habit_specific_reminders(habit_reminders: &[HabitReminder], habit_streaks: &[HabitStreak], now: DateTime<Local>) -> Vec<Reminder> {
+    habit_reminders
+        .iter()
+        .filter(|reminder| {
+            reminder
+                .occurrence_on(now.date_naive())
+                .is_some_and(|slot| in_slot(slot, now))
+        })
+        .filter(|reminder| {
+            !habit_streaks
+                .iter()
+                .filter(|habit| habit.habit_id == reminder.habit_id)
+                .any(|habit| completed_on(habit, now.date_naive()))
+        })
+        .map(|reminder| Reminder::HabitSpecific {
+            reminder_id: reminder.id.clone(),
+            habit_id: reminder.habit_id.clone(),
+            habit_name: reminder.habit_name.clone(),
+        })
+        .collect()
+}
+
+/// Warnings for streaks at or above `streak_warning_threshold` whose period ends soon without
+/// a completion. Each habit gets one notification per warning time reached; if several were
+/// reached since the last check, only the most urgent one is sent.
+fn streak_at_risk_reminders(
+    config: &NotificationConfig,
+    habit_streaks: &[HabitStreak],
+    activity: &mut ActivityData,
+    now: DateTime<Local>,
+) -> Vec<Reminder> {
+    activity.streak_warnings_sent.retain(|_, sent| sent.period_end > now);
+    if !config.streak_reminders {
+        return Vec::new();
+    }
+
+    let quiet_hours = config.effective_quiet_hours();
+    let mut reminders = Vec::new();
+    for habit in habit_streaks {
+        let Some(period_end) = habit.at_risk_until(habit.last_completed_at, now) else {
+            continue;
+        };
+
+        let warning_times = habit.warning_times(config.streak_warning_threshold, &quiet_hours, period_end);
+        let reached = warning_times.iter().filter(|time| **time <= now).count();
+        let already_sent = activity
+            .streak_warnings_sent
+            .get(&habit.habit_id)
+            .filter(|sent| sent.period_end == period_end)
+            .map_or(0, |sent| sent.stages);
+        if reached <= already_sent {
+            continue;
+        }
+
+        activity
+            .streak_warnings_sent
+            .insert(habit.habit_id.clone(), StreakWarningsSent { period_end, stages: reached });
+        reminders.push(Reminder::StreakAtRisk {
+            habit_id: habit.habit_id.clone(),
+            habit_name: habit.habit_name.clone(),
+            frequency: habit.frequency,
+            streak: habit.streak,
+            hours_left: (period_end - now).num_hours(),
+            final_warning: reached == warning_times.len(),
+        });
+    }
+    reminders
+}
+
+/// The morning digest, evening recap or weekly review whose slot is now. Digests are skipped
+/// when there's nothing to report; the weekly review covers the last full week.
+fn digest_reminders(config: &NotificationConfig, habit_streaks: &[HabitStreak], now: DateTime<Local>) -> Vec<Reminder> {
+    let today = now.date_naive();
+    let mut reminders = Vec::new();
+    if config.digests.morning_on(today).is_some_and(|slot| in_slot(slot, now)) {
+        if let Some(digest) = MorningDigest::build(habit_streaks, now) {
+            reminders.push(Reminder::MorningDigest { digest });
+        }
+    }
+    if config.digests.evening_on(today).is_some_and(|slot| in_slot(slot, now)) {
+        if let Some(recap) = EveningRecap::build(habit_streaks, now) {
+            reminders.push(Reminder::EveningRecap { recap });
+        }
+    }
+    if config.weekly_review.occurrence_on(today).is_some_and(|slot| in_slot(slot, now)) {
+        reminders.push(Reminder::WeeklyReview { week_start: weekly_review::previous_week_start(today) });
+    }
+    reminders
+}
+
+/// A streak-protection reminder fires once per inactivity episode, after the user has been
+/// inactive for `inactivity_threshold_hours` and only if no habit was completed today.
+/// With `adaptive_frequency`, it is held back for a while after several were ignored in a row.
+fn streak_protection_reminder(
+    config: &NotificationConfig,
+    habit_streaks: &[HabitStreak],
+    activity: &mut ActivityData,
+    now: DateTime<Local>,
+) -> Option<Reminder> {
+    if !config.streak_reminders || !in_streak_protection_slot(config, &activity.insights, now) {
+        return None;
+    }
+
+    if config.adaptive_frequency && activity.insights.is_backed_off(STREAK_PROTECTION_KIND, now) {
+        log::info!("Streak protection reminder backed off after repeatedly being ignored");
+        return None;
+    }
+
+    if habit_streaks.iter().any(|habit| completed_on(habit, now.date_naive())) {
+        return None;
+    }
+
+    let hours_inactive = (now - activity.last_activity).num_hours();
+    if hours_inactive < i64::from(config.inactivity_threshold_hours) {
+        return None;
+    }
+
+    if activity.streak_reminder_sent_for == Some(activity.last_activity) {
+        return None;
+    }
+
+    activity.streak_reminder_sent_for = Some(activity.last_activity);
+    activity.insights.record_reminder_sent(STREAK_PROTECTION_KIND, now);
+    Some(Reminder::StreakProtection { hours_inactive })
+}
+
+/// The random check-in whose slot is now, if any
+fn random_reminder(config: &NotificationConfig, activity: &mut ActivityData, now: DateTime<Local>) -> Option<Reminder> {
+    let slots = random_reminder_slots(config, activity, now.date_naive());
+    let index = slots.iter().position(|slot| in_slot(*slot, now))?;
+
+    if config.adaptive_frequency && activity.insights.is_backed_off(RANDOM_REMINDER_KIND, now) {
+        log::info!("Random reminder backed off after repeatedly being ignored");
+        return None;
+    }
+
+    activity.insights.record_reminder_sent(RANDOM_REMINDER_KIND, now);
+    Some(Reminder::Random { index: index as u32, count: slots.len() as u32 })
 }
 
 // ================================================================================================
@@ -248,20 +1118,8 @@ impl BackgroundNotificationService {
 
 #[tauri::command]
 pub async fn start_background_notifications(app_handle: AppHandle) -> Result<(), String> {
-    let service = BackgroundNotificationService::new(app_handle.clone());
-    
-    // Load existing configuration
-    if let Err(e) = service.load_from_files() {
-        eprintln!("Warning: Could not load notification data: {}", e);
-    }
-    
-    // Start the background service
-    service.start_background_service();
-    
-    // Store service in app state for later access
-    app_handle.manage(service);
-    
-    Ok(())
+    // Kept for older frontends - the reminder scheduler owns the background loop now
+    crate::scheduler::start_reminder_scheduler(app_handle).await
 }
 
 #[tauri::command] 
@@ -269,6 +1127,15 @@ pub async fn update_notification_config(
     app_handle: AppHandle,
     config: NotificationConfig,
 ) -> Result<(), String> {
+    match &config.quiet_hours {
+        Some(quiet_hours) => quiet_hours.validate()?,
+        None => {
+            QuietHours::from_active_window(config.reminder_start_hour, config.reminder_end_hour)?;
+        }
+    }
+    config.digests.validate()?;
+    config.weekly_review.validate()?;
+
     if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
         service.update_config(config);
         Ok(())
@@ -293,8 +1160,7 @@ pub async fn record_habit_completion_backend(
     habit_id: String,
 ) -> Result<(), String> {
     if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
-        service.record_habit_completion(habit_id);
-        Ok(())
+        service.record_habit_completion(&habit_id)
     } else {
         Err("Background notification service not initialized".to_string())
     }
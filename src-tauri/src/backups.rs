@@ -0,0 +1,707 @@
+/*!
+ * ================================================================================================
+ * BACKUPS
+ * ================================================================================================
+ *
+ * Rotating snapshots of everything the backend persists: the JSON files in the app config
+ * directory, and the database, event log and JSON files in the app data directory. A snapshot
+ * is taken daily by the reminder scheduler, at startup before any file is migrated to a newer
+ * schema, before an import and before a restore. Each one is a directory under `backups/` in
+ * the app data directory with a copy of every file and a manifest. It's written under a
+ * temporary name and renamed once complete, so a listed backup is never partial.
+ *
+ * Daily backups are thinned to the newest of each of the last 7 days, 4 weeks and 12 months;
+ * of the others only the last few are kept. A restore checks that every file in the backup
+ * loads and copies them all beside the live files before any is replaced. It backs up the
+ * current data first, so it can be undone, and put back should the restore fail partway.
+ *
+ * Backups hold the files as they are on disk, so with encryption on they're encrypted too, and
+ * they're re-encrypted along with the live files when it's turned on or off or the passphrase
+ * changes. Manifests are never encrypted, so backups can be listed while the data is locked.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_data::{AppData, AppDataStore, APP_DATA_FILE, APP_DATA_SCHEMA, DATABASE_FILE, SEALED_DATABASE_FILE};
+use crate::app_settings::{AppSettings, AppSettingsStore, APP_SETTINGS_SCHEMA};
+use crate::background_notifications::{
+    ActivityData, BackgroundNotificationService, NotificationConfig, ACTIVITY_DATA_SCHEMA, NOTIFICATION_CONFIG_SCHEMA,
+};
+use crate::clock::app_now;
+use crate::event_log::{self, Snapshot, EVENT_LOG_FILE, EVENT_SNAPSHOT_SCHEMA, SNAPSHOT_FILE};
+use crate::habit_reminders::{HabitReminder, HABIT_REMINDERS_SCHEMA};
+use crate::habit_streaks::{HabitStreak, HABIT_STREAKS_SCHEMA};
+use crate::notification_history::{NotificationHistory, NotificationHistoryStore, NOTIFICATION_HISTORY_SCHEMA};
+use crate::persistence::{self, Schema};
+use crate::scheduler::ReminderScheduler;
+use crate::storage;
+use crate::weekly_review::{ProgressData, PROGRESS_SCHEMA};
+use chrono::{DateTime, Datelike, Local};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Directory in the app data directory that holds one directory per backup
+pub const BACKUPS_DIR: &str = "backups";
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Suffix of a backup still being written
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Schema history of a backup's `manifest.json`
+pub const BACKUP_MANIFEST_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+/// Event emitted after a backup is restored, so the frontend reloads its data
+pub const RESTORED_EVENT: &str = "app-data-restored";
+
+/// Age of the newest scheduled backup at which the next one is taken
+pub const BACKUP_INTERVAL_HOURS: i64 = 24;
+
+// ================================================================================================
+// TYPES
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupReason {
+    Scheduled,
+    BeforeMigration,
+    BeforeImport,
+    BeforeRestore,
+}
+
+impl BackupReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupReason::Scheduled => "scheduled",
+            BackupReason::BeforeMigration => "before_migration",
+            BackupReason::BeforeImport => "before_import",
+            BackupReason::BeforeRestore => "before_restore",
+        }
+    }
+}
+
+/// Which of the app's directories a file lives in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Location {
+    Config,
+    Data,
+}
+
+impl Location {
+    fn as_str(self) -> &'static str {
+        match self {
+            Location::Config => "config",
+            Location::Data => "data",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BackupFile {
+    pub location: Location,
+    pub name: String,
+    pub size: u64,
+}
+
+/// A backup's manifest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Backup {
+    /// Name of the backup's directory
+    pub id: String,
+    pub created_at: DateTime<Local>,
+    pub reason: BackupReason,
+    /// Version of the app that took it
+    pub app_version: String,
+    pub files: Vec<BackupFile>,
+}
+
+/// How many backups are kept. Scheduled backups are kept as the newest of each of the last
+/// `daily` days, `weekly` ISO weeks and `monthly` months; the others as the newest `unscheduled`.
+pub struct Retention {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+    pub unscheduled: usize,
+}
+
+pub const RETENTION: Retention = Retention { daily: 7, weekly: 4, monthly: 12, unscheduled: 5 };
+
+/// The app config and app data directories a backup is taken from and restored to
+#[derive(Debug, Clone)]
+pub struct DataDirs {
+    pub config: PathBuf,
+    pub data: PathBuf,
+}
+
+impl DataDirs {
+    pub fn of(app_handle: &AppHandle) -> Result<Self, String> {
+        let config = app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+        let data = app_handle.path().app_data_dir()
+            .map_err(|e| format!("Could not resolve app data directory: {}", e))?;
+        Ok(Self { config, data })
+    }
+
+    pub fn backups(&self) -> PathBuf {
+        self.data.join(BACKUPS_DIR)
+    }
+
+    fn live(&self, location: Location, name: &str) -> PathBuf {
+        match location {
+            Location::Config => self.config.join(name),
+            Location::Data => self.data.join(name),
+        }
+    }
+}
+
+// ================================================================================================
+// BACKED UP FILES
+// ================================================================================================
+
+type JsonCheck = fn(&str, &Schema) -> Result<(), Box<dyn Error>>;
+
+enum FileKind {
+    /// A file in a schema envelope, usable if it decodes
+    Json(&'static Schema, JsonCheck),
+    Database,
+    EventLog,
+}
+
+struct KnownFile {
+    location: Location,
+    name: &'static str,
+    kind: FileKind,
+}
+
+fn check_json<T: DeserializeOwned>(json: &str, schema: &Schema) -> Result<(), Box<dyn Error>> {
+    persistence::decode::<T>(json, schema).map(|_| ())
+}
+
+/// Everything a backup holds. `.bak` and temp files are left out: a restored file is valid.
+const FILES: &[KnownFile] = &[
+    KnownFile { location: Location::Config, name: "notification_config.json", kind: FileKind::Json(&NOTIFICATION_CONFIG_SCHEMA, check_json::<NotificationConfig>) },
+    KnownFile { location: Location::Config, name: "activity_data.json", kind: FileKind::Json(&ACTIVITY_DATA_SCHEMA, check_json::<ActivityData>) },
+    KnownFile { location: Location::Config, name: "habit_reminders.json", kind: FileKind::Json(&HABIT_REMINDERS_SCHEMA, check_json::<Vec<HabitReminder>>) },
+    KnownFile { location: Location::Config, name: "habit_streaks.json", kind: FileKind::Json(&HABIT_STREAKS_SCHEMA, check_json::<Vec<HabitStreak>>) },
+    KnownFile { location: Location::Config, name: "progress.json", kind: FileKind::Json(&PROGRESS_SCHEMA, check_json::<ProgressData>) },
+    KnownFile { location: Location::Config, name: "notification_history.json", kind: FileKind::Json(&NOTIFICATION_HISTORY_SCHEMA, check_json::<NotificationHistory>) },
+    KnownFile { location: Location::Config, name: "app_settings.json", kind: FileKind::Json(&APP_SETTINGS_SCHEMA, check_json::<AppSettings>) },
+    KnownFile { location: Location::Data, name: DATABASE_FILE, kind: FileKind::Database },
+    KnownFile { location: Location::Data, name: SEALED_DATABASE_FILE, kind: FileKind::Database },
+    KnownFile { location: Location::Data, name: EVENT_LOG_FILE, kind: FileKind::EventLog },
+    KnownFile { location: Location::Data, name: SNAPSHOT_FILE, kind: FileKind::Json(&EVENT_SNAPSHOT_SCHEMA, check_json::<Snapshot>) },
+    KnownFile { location: Location::Data, name: APP_DATA_FILE, kind: FileKind::Json(&APP_DATA_SCHEMA, check_json::<AppData>) },
+];
+
+/// Whether loading the files in `dirs` would migrate any of them to a newer schema. Files that
+/// can't be read are left for loading to report.
+pub fn needs_migration(dirs: &DataDirs) -> bool {
+    FILES.iter().any(|file| {
+        let path = dirs.live(file.location, file.name);
+        match file.kind {
+            FileKind::Json(schema, _) => persistence::read_json(&path)
+                .ok()
+                .and_then(|json| persistence::stored_version(&json).ok())
+                .is_some_and(|version| version < u64::from(schema.version())),
+            FileKind::Database => path.exists()
+                && storage::database_version(&path).is_ok_and(|version| (version as usize) < storage::MIGRATIONS.len()),
+            FileKind::EventLog => false,
+        }
+    })
+}
+
+fn check_file(file: &KnownFile, path: &Path) -> Result<(), Box<dyn Error>> {
+    match file.kind {
+        FileKind::Json(schema, check) => check(&persistence::read_json(path)?, schema),
+        FileKind::Database => storage::check_database(path),
+        FileKind::EventLog => event_log::check_log(path),
+    }
+}
+
+// ================================================================================================
+// BACKUP DIRECTORY
+// ================================================================================================
+
+/// Copy every file that exists in `dirs` into a new backup and return its manifest, or `None`
+/// if there is nothing to back up yet. The caller keeps the database and event log from
+/// changing meanwhile.
+pub fn create_backup(dirs: &DataDirs, reason: BackupReason, at: DateTime<Local>) -> Result<Option<Backup>, Box<dyn Error>> {
+    if !FILES.iter().any(|file| dirs.live(file.location, file.name).exists()) {
+        return Ok(None);
+    }
+
+    let backups_dir = dirs.backups();
+    fs::create_dir_all(&backups_dir)?;
+    let stem = format!("{}-{}", at.format("%Y%m%d-%H%M%S"), reason.as_str());
+    let id = (1..)
+        .map(|n| if n == 1 { stem.clone() } else { format!("{}-{}", stem, n) })
+        .find(|id| !backups_dir.join(id).exists())
+        .unwrap_or(stem);
+
+    let partial = backups_dir.join(format!("{}{}", id, PARTIAL_SUFFIX));
+    let _ = fs::remove_dir_all(&partial);
+    let mut backup = Backup {
+        id: id.clone(),
+        created_at: at,
+        reason,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: Vec::new(),
+    };
+
+    if let Err(e) = copy_files(dirs, &partial, &mut backup) {
+        let _ = fs::remove_dir_all(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, backups_dir.join(&id))?;
+    Ok(Some(backup))
+}
+
+fn copy_files(dirs: &DataDirs, dir: &Path, backup: &mut Backup) -> Result<(), Box<dyn Error>> {
+    for file in FILES {
+        let source = dirs.live(file.location, file.name);
+        if !source.exists() {
+            continue;
+        }
+
+        let target = dir.join(file.location.as_str()).join(file.name);
+        fs::create_dir_all(dir.join(file.location.as_str()))?;
+        match file.kind {
+            FileKind::Database => storage::copy_database(&source, &target)?,
+            _ => {
+                fs::copy(&source, &target)?;
+            }
+        }
+        File::open(&target)?.sync_all()?;
+
+        backup.files.push(BackupFile {
+            location: file.location,
+            name: file.name.to_string(),
+            size: fs::metadata(&target)?.len(),
+        });
+    }
+    write_manifest(dir, backup)
+}
+
+fn write_manifest(dir: &Path, backup: &Backup) -> Result<(), Box<dyn Error>> {
+    let json = persistence::encode(backup, &BACKUP_MANIFEST_SCHEMA)?;
+    persistence::write_atomic(&dir.join(MANIFEST_FILE), json.as_bytes())?;
+    Ok(())
+}
+
+/// Directory of the backup `id`, which must be the name of a complete backup
+fn backup_dir(dirs: &DataDirs, id: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let plain_name = Path::new(id).file_name().is_some_and(|name| name == id);
+    if !plain_name || id.starts_with('.') || id.ends_with(PARTIAL_SUFFIX) {
+        return Err(format!("{:?} is not a backup", id).into());
+    }
+
+    let dir = dirs.backups().join(id);
+    if !dir.join(MANIFEST_FILE).exists() {
+        return Err(format!("No backup {}", id).into());
+    }
+    Ok(dir)
+}
+
+fn read_manifest(dir: &Path) -> Result<Backup, Box<dyn Error>> {
+    persistence::decode(&fs::read_to_string(dir.join(MANIFEST_FILE))?, &BACKUP_MANIFEST_SCHEMA)
+}
+
+/// Every complete backup, newest first. Directories without a readable manifest are skipped.
+pub fn read_backups(dirs: &DataDirs) -> Result<Vec<Backup>, Box<dyn Error>> {
+    let entries = match fs::read_dir(dirs.backups()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut backups = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_dir() || path.to_string_lossy().ends_with(PARTIAL_SUFFIX) {
+            continue;
+        }
+        match read_manifest(&path) {
+            Ok(backup) => backups.push(backup),
+            Err(e) => log::warn!("Ignoring backup {}: {}", path.display(), e),
+        }
+    }
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// The manifest of backup `id` once every file in it is checked to load with this version of
+/// the app
+pub fn validate_backup(dirs: &DataDirs, id: &str) -> Result<Backup, Box<dyn Error>> {
+    let dir = backup_dir(dirs, id)?;
+    let backup = read_manifest(&dir)?;
+    if backup.files.is_empty() {
+        return Err(format!("Backup {} is empty", id).into());
+    }
+
+    for entry in &backup.files {
+        let file = FILES
+            .iter()
+            .find(|file| file.location == entry.location && file.name == entry.name)
+            .ok_or_else(|| format!("Backup {} holds an unknown file {}", id, entry.name))?;
+        let path = dir.join(entry.location.as_str()).join(&entry.name);
+
+        let size = fs::metadata(&path).map_err(|e| format!("{} is missing from backup {}: {}", entry.name, id, e))?.len();
+        if size != entry.size {
+            return Err(format!("{} in backup {} is {} bytes, expected {}", entry.name, id, size, entry.size).into());
+        }
+        check_file(file, &path).map_err(|e| format!("{} in backup {} can't be used: {}", entry.name, id, e))?;
+    }
+    Ok(backup)
+}
+
+/// Replace the live files in `dirs` with those of a validated backup. Every file is first
+/// copied next to the one it replaces, so a failure to read the backup leaves the live files
+/// untouched, then each is swapped in with a rename. Files the backup doesn't have are removed,
+/// along with the last-known-good copy of every file, so nothing newer is mixed in. The stores
+/// must reload afterwards, with the database and event log closed meanwhile.
+pub fn restore_files(dirs: &DataDirs, backup: &Backup) -> Result<(), Box<dyn Error>> {
+    let dir = backup_dir(dirs, &backup.id)?;
+    let in_backup = |file: &KnownFile| backup.files.iter().any(|entry| entry.location == file.location && entry.name == file.name);
+
+    let staged = stage_files(dirs, &dir, FILES.iter().filter(|file| in_backup(file)));
+    if let Err(e) = staged {
+        for file in FILES {
+            let _ = fs::remove_file(staged_path(&dirs.live(file.location, file.name)));
+        }
+        return Err(e);
+    }
+
+    for file in FILES {
+        let live = dirs.live(file.location, file.name);
+        if in_backup(file) {
+            fs::rename(staged_path(&live), &live)?;
+        } else {
+            remove_if_exists(&live)?;
+        }
+        remove_if_exists(&persistence::backup_path(&live))?;
+
+        // A journal left by the replaced database would be rolled back into the restored one
+        if let FileKind::Database = file.kind {
+            remove_if_exists(&live.with_file_name(format!("{}-journal", file.name)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Where a file from a backup waits to replace the live file at `live`
+fn staged_path(live: &Path) -> PathBuf {
+    live.with_file_name(format!("{}.restoring", live.file_name().unwrap_or_default().to_string_lossy()))
+}
+
+fn stage_files<'a>(dirs: &DataDirs, backup_dir: &Path, files: impl Iterator<Item = &'a KnownFile>) -> Result<(), Box<dyn Error>> {
+    for file in files {
+        let staged = staged_path(&dirs.live(file.location, file.name));
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(backup_dir.join(file.location.as_str()).join(file.name), &staged)?;
+        File::open(&staged)?.sync_all()?;
+    }
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+pub fn remove_backup(dirs: &DataDirs, id: &str) -> Result<(), Box<dyn Error>> {
+    fs::remove_dir_all(backup_dir(dirs, id)?)?;
+    Ok(())
+}
+
+// ================================================================================================
+// ENCRYPTION
+// ================================================================================================
+
+/// Rewrite the files found by `path_of` as they're saved now: sealed with the current key, or
+/// in plain text with encryption off
+fn reseal_dir(path_of: impl Fn(Location, &str) -> PathBuf) -> Result<(), Box<dyn Error>> {
+    for file in FILES {
+        let path = path_of(file.location, file.name);
+        match file.kind {
+            FileKind::Json(..) => persistence::reseal_file(&path)?,
+            FileKind::EventLog => event_log::reseal_log(&path)?,
+            // Both database files at once, below
+            FileKind::Database => {}
+        }
+    }
+    storage::reseal_database(&path_of(Location::Data, DATABASE_FILE), &path_of(Location::Data, SEALED_DATABASE_FILE))?;
+    Ok(())
+}
+
+/// Re-encrypt the live files and every backup with the current key, or decrypt them with
+/// encryption off, after it's turned on or off or the passphrase changes. The caller keeps the
+/// stores from writing meanwhile.
+pub fn reseal_files(dirs: &DataDirs) -> Result<(), Box<dyn Error>> {
+    reseal_dir(|location, name| dirs.live(location, name))?;
+
+    for mut backup in read_backups(dirs)? {
+        let dir = backup_dir(dirs, &backup.id)?;
+        reseal_dir(|location, name| dir.join(location.as_str()).join(name))
+            .map_err(|e| format!("Could not re-encrypt backup {}: {}", backup.id, e))?;
+
+        backup.files = FILES
+            .iter()
+            .filter_map(|file| {
+                let size = fs::metadata(dir.join(file.location.as_str()).join(file.name)).ok()?.len();
+                Some(BackupFile { location: file.location, name: file.name.to_string(), size })
+            })
+            .collect();
+        write_manifest(&dir, &backup)?;
+    }
+    Ok(())
+}
+
+// ================================================================================================
+// RETENTION
+// ================================================================================================
+
+/// Ids of the newest backup in each of the first `periods` distinct periods of `newest_first`
+fn newest_per_period<K: PartialEq>(newest_first: &[&Backup], periods: usize, period: impl Fn(&Backup) -> K) -> Vec<String> {
+    let mut kept: Vec<(K, String)> = Vec::new();
+    for backup in newest_first {
+        if kept.len() == periods {
+            break;
+        }
+        let key = period(backup);
+        if kept.last().map_or(true, |(last, _)| *last != key) {
+            kept.push((key, backup.id.clone()));
+        }
+    }
+    kept.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Ids of the backups `retention` no longer keeps
+pub fn expired_backups(backups: &[Backup], retention: &Retention) -> Vec<String> {
+    let mut newest_first: Vec<&Backup> = backups.iter().collect();
+    newest_first.sort_by_key(|backup| Reverse(backup.created_at));
+    let (scheduled, unscheduled): (Vec<&Backup>, Vec<&Backup>) =
+        newest_first.into_iter().partition(|backup| backup.reason == BackupReason::Scheduled);
+
+    let mut kept: HashSet<String> = HashSet::new();
+    kept.extend(newest_per_period(&scheduled, retention.daily, |backup| backup.created_at.date_naive()));
+    kept.extend(newest_per_period(&scheduled, retention.weekly, |backup| backup.created_at.iso_week()));
+    kept.extend(newest_per_period(&scheduled, retention.monthly, |backup| (backup.created_at.year(), backup.created_at.month())));
+    kept.extend(unscheduled.iter().take(retention.unscheduled).map(|backup| backup.id.clone()));
+
+    backups.iter().filter(|backup| !kept.contains(&backup.id)).map(|backup| backup.id.clone()).collect()
+}
+
+/// Delete the backups `retention` no longer keeps, and any left partial by a crash
+pub fn prune_backups(dirs: &DataDirs, retention: &Retention) -> Result<Vec<String>, Box<dyn Error>> {
+    if let Ok(entries) = fs::read_dir(dirs.backups()) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().ends_with(PARTIAL_SUFFIX) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    let expired = expired_backups(&read_backups(dirs)?, retention);
+    for id in &expired {
+        remove_backup(dirs, id)?;
+    }
+    Ok(expired)
+}
+
+// ================================================================================================
+// STORE
+// ================================================================================================
+
+pub struct BackupStore {
+    /// When the newest scheduled backup was taken, once looked up
+    last_scheduled: Mutex<Option<DateTime<Local>>>,
+    app_handle: AppHandle,
+}
+
+impl BackupStore {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            last_scheduled: Mutex::new(None),
+            app_handle,
+        }
+    }
+
+    /// Back up the data now and prune old backups. The database and event log are copied with
+    /// writes held off so they match.
+    pub fn create(&self, reason: BackupReason) -> Result<Option<Backup>, String> {
+        let dirs = DataDirs::of(&self.app_handle)?;
+        let now = app_now(&self.app_handle);
+        let created = match self.app_handle.try_state::<AppDataStore>() {
+            Some(store) => store.without_writes(|| create_backup(&dirs, reason, now)),
+            None => create_backup(&dirs, reason, now),
+        }
+        .map_err(|e| format!("Could not back up the app data: {}", e))?;
+
+        if let Some(backup) = &created {
+            log::info!("Backed up the app data to {}", backup.id);
+        }
+        match prune_backups(&dirs, &RETENTION) {
+            Ok(pruned) if !pruned.is_empty() => log::info!("Deleted {} expired backups", pruned.len()),
+            Ok(_) => {}
+            Err(e) => log::warn!("Could not prune backups: {}", e),
+        }
+        Ok(created)
+    }
+
+    /// Back up the data if loading it is about to migrate a file. Runs in setup, before any
+    /// store loads.
+    pub fn backup_before_migration(&self) {
+        match DataDirs::of(&self.app_handle) {
+            Ok(dirs) if needs_migration(&dirs) => {
+                log::info!("Data from an older version of HabitQuest found - backing it up before migrating");
+                if let Err(e) = self.create(BackupReason::BeforeMigration) {
+                    log::error!("{}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("{}", e),
+        }
+    }
+
+    /// Take the scheduled backup once the last one is `BACKUP_INTERVAL_HOURS` old
+    pub fn backup_if_due(&self, now: DateTime<Local>) {
+        let last = *self.last_scheduled.lock().unwrap();
+        let last = last.or_else(|| {
+            let dirs = DataDirs::of(&self.app_handle).ok()?;
+            read_backups(&dirs)
+                .ok()?
+                .into_iter()
+                .find(|backup| backup.reason == BackupReason::Scheduled)
+                .map(|backup| backup.created_at)
+        });
+        let due = last.map_or(true, |last| last > now || now - last >= chrono::Duration::hours(BACKUP_INTERVAL_HOURS));
+        if !due {
+            *self.last_scheduled.lock().unwrap() = last;
+            return;
+        }
+
+        if let Err(e) = self.create(BackupReason::Scheduled) {
+            log::error!("{}", e);
+        }
+        // Also after a failure, so it's retried at the next interval rather than every check
+        *self.last_scheduled.lock().unwrap() = Some(now);
+    }
+
+    pub fn list(&self) -> Result<Vec<Backup>, String> {
+        read_backups(&DataDirs::of(&self.app_handle)?).map_err(|e| format!("Could not list backups: {}", e))
+    }
+
+    /// Replace the live data with backup `id` once it's validated, after backing up the current
+    /// data. Should the restore fail partway, that backup is put back instead. The scheduler is
+    /// paused meanwhile and every store reloads from the restored files.
+    pub fn restore(&self, id: &str) -> Result<Backup, String> {
+        let dirs = DataDirs::of(&self.app_handle)?;
+        let backup = validate_backup(&dirs, id).map_err(|e| format!("Could not restore backup {}: {}", id, e))?;
+        let before_restore = self.create(BackupReason::BeforeRestore)?;
+
+        let scheduler_was_running = self
+            .app_handle
+            .try_state::<ReminderScheduler>()
+            .is_some_and(|scheduler| scheduler.stop());
+        if let Some(store) = self.app_handle.try_state::<AppDataStore>() {
+            store.close();
+        }
+
+        let restored = restore_files(&dirs, &backup);
+        if let (Err(e), Some(before_restore)) = (&restored, &before_restore) {
+            log::error!("Restoring backup {} failed partway ({}) - putting back the data from before it", id, e);
+            if let Err(e) = restore_files(&dirs, before_restore) {
+                log::error!("Could not put back backup {}: {}", before_restore.id, e);
+            }
+        }
+        // Whether or not every file was swapped in, the stores pick up what's on disk now
+        reload_stores(&self.app_handle);
+        if scheduler_was_running {
+            if let Some(scheduler) = self.app_handle.try_state::<ReminderScheduler>() {
+                scheduler.start(self.app_handle.clone());
+            }
+        }
+        restored.map_err(|e| format!("Could not restore backup {}: {}", id, e))?;
+
+        log::info!("Restored backup {}", id);
+        let _ = self.app_handle.emit(RESTORED_EVENT, &backup);
+        Ok(backup)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let dirs = DataDirs::of(&self.app_handle)?;
+        remove_backup(&dirs, id).map_err(|e| format!("Could not delete backup {}: {}", id, e))
+    }
+}
+
+/// Load every store again from the files, in the order setup loads them. Stores start over
+/// from their defaults, so a file the restored data doesn't have leaves nothing newer behind.
+pub(crate) fn reload_stores(app_handle: &AppHandle) {
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        service.unload();
+        if let Err(e) = service.load_from_files() {
+            log::warn!("Could not load notification data: {}", e);
+        }
+    }
+    if let Some(history_store) = app_handle.try_state::<NotificationHistoryStore>() {
+        history_store.unload();
+        if let Err(e) = history_store.load_from_file() {
+            log::warn!("Could not load notification history: {}", e);
+        }
+    }
+    if let Some(settings_store) = app_handle.try_state::<AppSettingsStore>() {
+        if let Err(e) = settings_store.load_from_file() {
+            log::warn!("Could not load app settings: {}", e);
+        }
+    }
+    if let Some(app_data_store) = app_handle.try_state::<AppDataStore>() {
+        if let Err(e) = app_data_store.open() {
+            log::warn!("Could not load app data: {}", e);
+        }
+    }
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+/// Every backup, newest first
+#[tauri::command]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<Backup>, String> {
+    if let Some(backups) = app_handle.try_state::<BackupStore>() {
+        backups.list()
+    } else {
+        Err("Backups not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn restore_backup(app_handle: AppHandle, id: String) -> Result<Backup, String> {
+    if let Some(backups) = app_handle.try_state::<BackupStore>() {
+        backups.restore(&id)
+    } else {
+        Err("Backups not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn delete_backup(app_handle: AppHandle, id: String) -> Result<(), String> {
+    if let Some(backups) = app_handle.try_state::<BackupStore>() {
+        backups.delete(&id)
+    } else {
+        Err("Backups not initialized".to_string())
+    }
+}
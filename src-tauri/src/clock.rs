@@ -0,0 +1,224 @@
+/*!
+ * ================================================================================================
+ * CLOCK
+ * ================================================================================================
+ *
+ * Source of the current time for all reminder logic. The app uses the system clock; tests use
+ * a manual clock they can move forward, and debug builds can be started with
+ * `--simulate-now=<offset|datetime>` to shift or freeze time for manual QA:
+ *
+ * - `--simulate-now=+36h`, `-2d`, `+90m`: run the clock shifted by the offset
+ * - `--simulate-now=2025-03-30T01:30`: freeze the clock at that local time
+ *
+ * `DriftDetector` compares elapsed wall-clock time against monotonic time, which stops while
+ * the machine is suspended and ignores manual clock changes, so the scheduler can notice both.
+ *
+ * @version 1.0.0
+ */
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+/// Command-line flag that selects a simulated clock in debug builds
+pub const SIMULATE_NOW_FLAG: &str = "--simulate-now";
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// Clock shared between the service, the scheduler and the commands
+pub type SharedClock = Arc<dyn Clock>;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// System time shifted by a fixed offset, so time still passes
+#[derive(Debug, Clone, Copy)]
+pub struct OffsetClock {
+    offset: Duration,
+}
+
+impl OffsetClock {
+    pub fn new(offset: Duration) -> Self {
+        Self { offset }
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now() + self.offset
+    }
+}
+
+/// Always returns the same instant
+#[derive(Debug, Clone, Copy)]
+pub struct FrozenClock {
+    at: DateTime<Local>,
+}
+
+impl FrozenClock {
+    pub fn new(at: DateTime<Local>) -> Self {
+        Self { at }
+    }
+}
+
+impl Clock for FrozenClock {
+    fn now(&self) -> DateTime<Local> {
+        self.at
+    }
+}
+
+/// Clock that only moves when told to, for simulating days of use in tests
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Local>>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Local>) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+
+    pub fn set(&self, at: DateTime<Local>) {
+        *self.now.lock().unwrap() = at;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Parsed value of `--simulate-now`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulatedNow {
+    Offset(Duration),
+    Frozen(NaiveDateTime),
+}
+
+impl SimulatedNow {
+    /// Parse a signed offset like `+36h`, `-2d` or `+90m`, or a local datetime like
+    /// `2025-03-30T01:30` (seconds optional)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if let Some(sign) = value.chars().next().filter(|c| *c == '+' || *c == '-') {
+            let rest = &value[1..];
+            let invalid = || format!("Invalid clock offset: {}", value);
+            let (unit_start, unit) = rest.char_indices().last().ok_or_else(invalid)?;
+            let amount = &rest[..unit_start];
+            if amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let amount: i64 = amount.parse().map_err(|_| invalid())?;
+            let amount = if sign == '-' { -amount } else { amount };
+            let offset = match unit {
+                'm' => Duration::try_minutes(amount),
+                'h' => Duration::try_hours(amount),
+                'd' => Duration::try_days(amount),
+                _ => return Err(format!("Unknown clock offset unit in {} (use m, h or d)", value)),
+            };
+            let offset = offset.ok_or_else(|| format!("Clock offset out of range: {}", value))?;
+            return Ok(SimulatedNow::Offset(offset));
+        }
+
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+            .map(SimulatedNow::Frozen)
+            .map_err(|_| format!("Invalid simulated time: {} (expected an offset like +36h or a datetime like 2025-03-30T01:30)", value))
+    }
+
+    pub fn into_clock(self) -> Result<SharedClock, String> {
+        match self {
+            SimulatedNow::Offset(offset) => {
+                if Local::now().checked_add_signed(offset).is_none() {
+                    return Err(format!("Clock offset out of range: {}", offset));
+                }
+                Ok(Arc::new(OffsetClock::new(offset)))
+            }
+            SimulatedNow::Frozen(at) => {
+                // A time skipped by a DST change has no local instant; a repeated one takes the first
+                let at = Local
+                    .from_local_datetime(&at)
+                    .earliest()
+                    .ok_or_else(|| format!("{} does not exist in the local time zone", at))?;
+                Ok(Arc::new(FrozenClock::new(at)))
+            }
+        }
+    }
+}
+
+/// Clock selected by `--simulate-now=<value>` among `args`, if the flag is present
+pub fn simulated_clock_from_args(args: impl IntoIterator<Item = String>) -> Result<Option<SharedClock>, String> {
+    let prefix = format!("{}=", SIMULATE_NOW_FLAG);
+    args.into_iter()
+        .find_map(|arg| arg.strip_prefix(&prefix).map(str::to_string))
+        .map(|value| SimulatedNow::parse(&value).and_then(SimulatedNow::into_clock))
+        .transpose()
+}
+
+/// Difference between elapsed wall-clock and monotonic time that counts as drift rather than
+/// timer jitter
+pub const DRIFT_TOLERANCE_SECONDS: i64 = 120;
+
+/// A jump of the wall clock relative to monotonic time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockDrift {
+    /// The wall clock moved further than monotonic time: the machine was suspended, or the
+    /// clock was set forward
+    Forward(Duration),
+    /// The clock was set back
+    Backward(Duration),
+}
+
+/// Tracks wall-clock and monotonic time between checks
+#[derive(Debug, Clone, Copy)]
+pub struct DriftDetector {
+    wall: DateTime<Local>,
+    monotonic: Instant,
+}
+
+impl DriftDetector {
+    pub fn new(wall: DateTime<Local>, monotonic: Instant) -> Self {
+        Self { wall, monotonic }
+    }
+
+    /// Drift since the previous check, if beyond the tolerance. Both readings become the new
+    /// baseline either way.
+    pub fn check(&mut self, wall: DateTime<Local>, monotonic: Instant) -> Option<ClockDrift> {
+        let wall_elapsed = wall - self.wall;
+        let monotonic_elapsed = Duration::from_std(monotonic.saturating_duration_since(self.monotonic))
+            .unwrap_or_else(|_| Duration::zero());
+        self.wall = wall;
+        self.monotonic = monotonic;
+
+        let drift = wall_elapsed - monotonic_elapsed;
+        if drift > Duration::seconds(DRIFT_TOLERANCE_SECONDS) {
+            Some(ClockDrift::Forward(drift))
+        } else if drift < -Duration::seconds(DRIFT_TOLERANCE_SECONDS) {
+            Some(ClockDrift::Backward(-drift))
+        } else {
+            None
+        }
+    }
+}
+
+/// Current time according to the app's managed clock, or the system clock before it is set up
+pub fn app_now(app_handle: &AppHandle) -> DateTime<Local> {
+    app_handle
+        .try_state::<SharedClock>()
+        .map_or_else(Local::now, |clock| clock.now())
+}
@@ -0,0 +1,206 @@
+/*!
+ * ================================================================================================
+ * DIGESTS
+ * ================================================================================================
+ *
+ * The morning digest (today's due habits and the XP they're worth) and the evening recap
+ * (completed vs remaining, XP earned and streak changes), built from the habit streaks of the
+ * app data. Scheduled through `DigestConfig`.
+ *
+ * @version 1.0.0
+ */
+
+use crate::habit_streaks::HabitStreak;
+use crate::messages;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DigestConfig {
+    pub morning_enabled: bool,
+    pub morning_hour: u32,
+    pub morning_minute: u32,
+    pub evening_enabled: bool,
+    pub evening_hour: u32,
+    pub evening_minute: u32,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            morning_enabled: true,
+            morning_hour: 8,
+            morning_minute: 30,
+            evening_enabled: true,
+            evening_hour: 20,
+            evening_minute: 30,
+        }
+    }
+}
+
+impl DigestConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (hour, minute) in [(self.morning_hour, self.morning_minute), (self.evening_hour, self.evening_minute)] {
+            if hour > 23 || minute > 59 {
+                return Err(format!("Invalid digest time {:02}:{:02}", hour, minute));
+            }
+        }
+        Ok(())
+    }
+
+    /// When the morning digest goes out on `day`, if enabled
+    pub fn morning_on(&self, day: NaiveDate) -> Option<DateTime<Local>> {
+        if !self.morning_enabled {
+            return None;
+        }
+        occurrence(day, self.morning_hour, self.morning_minute)
+    }
+
+    /// When the evening recap goes out on `day`, if enabled
+    pub fn evening_on(&self, day: NaiveDate) -> Option<DateTime<Local>> {
+        if !self.evening_enabled {
+            return None;
+        }
+        occurrence(day, self.evening_hour, self.evening_minute)
+    }
+}
+
+/// Local time on `day`, skipped if a DST change removes it
+fn occurrence(day: NaiveDate, hour: u32, minute: u32) -> Option<DateTime<Local>> {
+    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+    Local.from_local_datetime(&day.and_time(time)).earliest()
+}
+
+/// A habit and its streak, as listed in a recap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecapStreak {
+    pub habit_name: String,
+    pub streak: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MorningDigest {
+    pub due_habits: Vec<String>,
+    /// XP for completing every due habit
+    pub xp_available: u32,
+}
+
+impl MorningDigest {
+    /// Nothing is sent when no habit is due
+    pub fn build(habits: &[HabitStreak], now: DateTime<Local>) -> Option<Self> {
+        let due: Vec<&HabitStreak> = habits
+            .iter()
+            .filter(|habit| habit.scheduled_in_period(now))
+            .filter(|habit| !habit.completed_in_period(habit.last_completed_at, now))
+            .collect();
+        if due.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            due_habits: due.iter().map(|habit| habit.habit_name.clone()).collect(),
+            xp_available: due.iter().map(|habit| habit.xp_on_complete).sum(),
+        })
+    }
+
+    pub fn notification_text(&self, locale: &str) -> (String, String) {
+        (
+            messages::message(locale, "digest.morning_title", &[]),
+            messages::message(
+                locale,
+                "digest.morning_body",
+                &[
+                    ("count", &self.due_habits.len().to_string()),
+                    ("xp", &self.xp_available.to_string()),
+                    ("habits", &messages::list(locale, self.due_habits.clone())),
+                ],
+            ),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EveningRecap {
+    pub completed: Vec<String>,
+    /// Due habits not completed yet in their period
+    pub remaining: Vec<String>,
+    pub xp_earned: u32,
+    /// Streaks a completion today extended
+    pub streaks_extended: Vec<RecapStreak>,
+    /// Streaks that end tonight unless completed
+    pub streaks_at_risk: Vec<RecapStreak>,
+}
+
+impl EveningRecap {
+    /// Nothing is sent without any habits
+    pub fn build(habits: &[HabitStreak], now: DateTime<Local>) -> Option<Self> {
+        if habits.is_empty() {
+            return None;
+        }
+
+        let today = now.date_naive();
+        let tomorrow = today.succ_opt()?;
+        let completed_today = |completed_at: Option<DateTime<Local>>| completed_at.is_some_and(|at| at.date_naive() == today);
+
+        let mut recap = Self {
+            completed: Vec::new(),
+            remaining: Vec::new(),
+            xp_earned: 0,
+            streaks_extended: Vec::new(),
+            streaks_at_risk: Vec::new(),
+        };
+        for habit in habits {
+            let completed_at = habit.last_completed_at;
+            if completed_today(completed_at) {
+                recap.completed.push(habit.habit_name.clone());
+                recap.xp_earned += habit.xp_on_complete;
+                if habit.streak > 1 {
+                    recap.streaks_extended.push(RecapStreak { habit_name: habit.habit_name.clone(), streak: habit.streak });
+                }
+            } else if habit.scheduled_in_period(now) && !habit.completed_in_period(completed_at, now) {
+                recap.remaining.push(habit.habit_name.clone());
+                let ends_tonight = habit
+                    .at_risk_until(completed_at, now)
+                    .is_some_and(|end| end.date_naive() <= tomorrow);
+                if ends_tonight {
+                    recap.streaks_at_risk.push(RecapStreak { habit_name: habit.habit_name.clone(), streak: habit.streak });
+                }
+            }
+        }
+        Some(recap)
+    }
+
+    pub fn notification_text(&self, locale: &str) -> (String, String) {
+        let streaks = |streaks: &[RecapStreak]| {
+            messages::list(
+                locale,
+                streaks.iter().map(|streak| format!("{} ({})", streak.habit_name, streak.streak)).collect(),
+            )
+        };
+
+        let total = self.completed.len() + self.remaining.len();
+        let mut lines = vec![messages::message(
+            locale,
+            "digest.evening_progress",
+            &[
+                ("completed", &self.completed.len().to_string()),
+                ("total", &total.to_string()),
+                ("xp", &self.xp_earned.to_string()),
+            ],
+        )];
+        if !self.streaks_extended.is_empty() {
+            lines.push(messages::message(locale, "digest.evening_extended", &[("streaks", &streaks(&self.streaks_extended))]));
+        }
+        if !self.streaks_at_risk.is_empty() {
+            lines.push(messages::message(locale, "digest.evening_at_risk", &[("streaks", &streaks(&self.streaks_at_risk))]));
+        } else if !self.remaining.is_empty() {
+            lines.push(messages::message(
+                locale,
+                "digest.evening_remaining",
+                &[("habits", &messages::list(locale, self.remaining.clone()))],
+            ));
+        }
+
+        (messages::message(locale, "digest.evening_title", &[]), lines.join("\n"))
+    }
+}
@@ -0,0 +1,767 @@
+/*!
+ * ================================================================================================
+ * ENCRYPTION
+ * ================================================================================================
+ *
+ * Optional encryption at rest. Once enabled, every file saved with `persistence::save_json`,
+ * every line of the event log and the database are sealed with XChaCha20-Poly1305 under a
+ * random data key. The data key is kept in `encryption.json` in the app data directory, sealed
+ * with a key derived from the user's passphrase by Argon2id, so a wrong passphrase fails to
+ * unseal it rather than producing garbage.
+ *
+ * Changing the passphrase also replaces the data key and re-encrypts every file, backups
+ * included. The old key stays in the key file until that's done, so a change interrupted by a
+ * crash is finished at the next unlock. Turning encryption on or off is recorded in the key file
+ * the same way, and finished at the next unlock too.
+ *
+ * The keys are held process-wide rather than in managed state, as every read and write of a
+ * data file goes through them, including those made without an `AppHandle`. While the data is
+ * locked nothing can be read or saved: the stores are emptied and the reminder scheduler holds
+ * its checks until the data is unlocked. Exports are written for the user to open, and stay
+ * readable. Weekly review reports would too, so none are written while encryption is enabled
+ * and those written before are deleted when it's turned on.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_data::AppDataStore;
+use crate::background_notifications::BackgroundNotificationService;
+use crate::backups::{self, BackupStore, DataDirs};
+use crate::notification_history::NotificationHistoryStore;
+use crate::persistence::{self, Schema};
+use crate::scheduler::ReminderScheduler;
+use crate::weekly_review;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Emitter, Manager};
+use zeroize::Zeroizing;
+
+/// The sealed data key and how to derive the key that unseals it, in the app data directory
+pub const KEY_FILE: &str = "encryption.json";
+
+/// Schema history of `encryption.json`
+pub const KEY_FILE_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+/// Event emitted with the new `EncryptionStatus` whenever it changes
+pub const STATUS_EVENT: &str = "encryption-status-changed";
+
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+const KEY_ID_LEN: usize = 9;
+
+/// Id of the key derived from the passphrase, which seals only the data keys
+const PASSPHRASE_KEY_ID: &str = "passphrase";
+
+// ================================================================================================
+// ERRORS
+// ================================================================================================
+
+/// Encrypted data was read or written while no key was unlocked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockedError;
+
+impl fmt::Display for LockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HabitQuest's data is encrypted and locked - unlock it with your passphrase first")
+    }
+}
+
+impl Error for LockedError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrongPassphraseError;
+
+impl fmt::Display for WrongPassphraseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Wrong passphrase")
+    }
+}
+
+impl Error for WrongPassphraseError {}
+
+/// Sealed data that fails authentication: damaged, or changed outside HabitQuest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecryptError;
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not be decrypted - it is damaged or was changed outside HabitQuest")
+    }
+}
+
+impl Error for DecryptError {}
+
+// ================================================================================================
+// KEYS AND SEALING
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionStatus {
+    Disabled,
+    Locked,
+    Unlocked,
+}
+
+/// A 256-bit key and the id that sealed data names it by
+pub struct DataKey {
+    id: String,
+    bytes: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl DataKey {
+    pub fn generate() -> Self {
+        let mut bytes = Zeroizing::new([0u8; KEY_LEN]);
+        OsRng.fill_bytes(bytes.as_mut());
+        Self { id: random_base64(KEY_ID_LEN), bytes }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(Key::from_slice(self.bytes.as_ref()))
+    }
+}
+
+impl fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+fn random_base64(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    BASE64.encode(bytes)
+}
+
+/// Data sealed with one key, whose id is also the associated data
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Sealed {
+    pub key_id: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// How sealed data is written to disk, distinct from any file's own JSON
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SealedFile {
+    encrypted: Sealed,
+}
+
+/// Encrypt `plaintext` with `key` under a fresh random nonce
+pub fn seal_with(key: &DataKey, plaintext: &[u8]) -> Sealed {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = key
+        .cipher()
+        .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad: key.id.as_bytes() })
+        .expect("XChaCha20-Poly1305 only fails on inputs larger than memory");
+
+    Sealed {
+        key_id: key.id.clone(),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    }
+}
+
+/// Decrypt and authenticate `sealed` with `key`
+pub fn open_with(key: &DataKey, sealed: &Sealed) -> Result<Zeroizing<Vec<u8>>, Box<dyn Error>> {
+    if sealed.key_id != key.id {
+        return Err(format!("sealed with key {}, not {}", sealed.key_id, key.id).into());
+    }
+    let nonce = BASE64.decode(&sealed.nonce).map_err(|_| DecryptError)?;
+    let ciphertext = BASE64.decode(&sealed.ciphertext).map_err(|_| DecryptError)?;
+    if nonce.len() != NONCE_LEN {
+        return Err(Box::new(DecryptError));
+    }
+
+    key.cipher()
+        .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: sealed.key_id.as_bytes() })
+        .map(Zeroizing::new)
+        .map_err(|_| Box::new(DecryptError) as Box<dyn Error>)
+}
+
+// ================================================================================================
+// KEY FILE
+// ================================================================================================
+
+/// Argon2id parameters and salt the passphrase key is derived with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KdfParams {
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /// The argon2 crate's recommended costs with a fresh salt
+    pub fn generate() -> Self {
+        Self::with_costs(Params::DEFAULT_M_COST, Params::DEFAULT_T_COST, Params::DEFAULT_P_COST)
+    }
+
+    pub fn with_costs(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        Self { salt: random_base64(SALT_LEN), memory_kib, iterations, parallelism }
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<DataKey, Box<dyn Error>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(KEY_LEN))
+            .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+        let salt = BASE64.decode(&self.salt)?;
+        let mut bytes = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, bytes.as_mut())
+            .map_err(|e| format!("Could not derive a key from the passphrase: {}", e))?;
+        Ok(DataKey { id: PASSPHRASE_KEY_ID.to_string(), bytes })
+    }
+}
+
+/// A data key sealed with the passphrase key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub id: String,
+    pub sealed: Sealed,
+}
+
+impl WrappedKey {
+    fn wrap(passphrase_key: &DataKey, key: &DataKey) -> Self {
+        Self { id: key.id.clone(), sealed: seal_with(passphrase_key, key.bytes.as_ref()) }
+    }
+
+    fn unwrap(&self, passphrase_key: &DataKey) -> Result<DataKey, Box<dyn Error>> {
+        let plaintext = open_with(passphrase_key, &self.sealed).map_err(|_| WrongPassphraseError)?;
+        let mut bytes = Zeroizing::new([0u8; KEY_LEN]);
+        if plaintext.len() != KEY_LEN {
+            return Err(format!("{} holds a damaged key", KEY_FILE).into());
+        }
+        bytes.copy_from_slice(&plaintext);
+        Ok(DataKey { id: self.id.clone(), bytes })
+    }
+}
+
+/// Contents of `encryption.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyFile {
+    pub kdf: KdfParams,
+    pub data_key: WrappedKey,
+    /// The data key before the passphrase was changed, until nothing is sealed with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_data_key: Option<WrappedKey>,
+    /// Set while files are being re-encrypted, so an interrupted run is finished on unlock
+    #[serde(default)]
+    pub resealing: bool,
+    /// Set while files are being decrypted to turn encryption off, so an interrupted run is
+    /// finished on unlock
+    #[serde(default)]
+    pub disabling: bool,
+}
+
+/// A change to the files that was cut short and is finished at the next unlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterruptedChange {
+    /// Re-encrypting them with the current key
+    Reseal,
+    /// Decrypting them to turn encryption off
+    Disable,
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), Box<dyn Error>> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!("The passphrase must be at least {} characters long", MIN_PASSPHRASE_CHARS).into());
+    }
+    Ok(())
+}
+
+impl KeyFile {
+    /// A key file for a new data key sealed with `passphrase`, returned with the key
+    pub fn create(passphrase: &str, kdf: KdfParams) -> Result<(Self, DataKey), Box<dyn Error>> {
+        check_passphrase(passphrase)?;
+        let data_key = DataKey::generate();
+        let key_file = Self {
+            data_key: WrappedKey::wrap(&kdf.derive(passphrase)?, &data_key),
+            kdf,
+            previous_data_key: None,
+            resealing: true,
+            disabling: false,
+        };
+        Ok((key_file, data_key))
+    }
+
+    /// A key file for a new data key sealed with `new_passphrase`, which keeps `current` until
+    /// every file is re-encrypted
+    pub fn rekey(current: &DataKey, new_passphrase: &str, kdf: KdfParams) -> Result<(Self, DataKey), Box<dyn Error>> {
+        let (mut key_file, data_key) = Self::create(new_passphrase, kdf)?;
+        let passphrase_key = key_file.kdf.derive(new_passphrase)?;
+        key_file.previous_data_key = Some(WrappedKey::wrap(&passphrase_key, current));
+        Ok((key_file, data_key))
+    }
+
+    /// The data keys, current first. `WrongPassphraseError` unless `passphrase` is the one
+    /// the file was sealed with.
+    pub fn unlock(&self, passphrase: &str) -> Result<Vec<DataKey>, Box<dyn Error>> {
+        let passphrase_key = self.kdf.derive(passphrase)?;
+        let mut keys = vec![self.data_key.unwrap(&passphrase_key)?];
+        if let Some(previous) = &self.previous_data_key {
+            keys.push(previous.unwrap(&passphrase_key)?);
+        }
+        Ok(keys)
+    }
+
+    /// `None` if encryption was never enabled in `data_dir`
+    pub fn load(data_dir: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        match fs::read_to_string(data_dir.join(KEY_FILE)) {
+            Ok(json) => Ok(Some(persistence::decode(&json, &KEY_FILE_SCHEMA)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Written without a `.bak`: a stale copy would unlock with a passphrase no longer in use
+    pub fn save(&self, data_dir: &Path) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(data_dir)?;
+        let json = persistence::encode(self, &KEY_FILE_SCHEMA)?;
+        persistence::write_atomic(&data_dir.join(KEY_FILE), json.as_bytes())?;
+        Ok(())
+    }
+}
+
+// ================================================================================================
+// KEYS IN USE
+// ================================================================================================
+
+enum State {
+    Disabled,
+    Locked,
+    /// `keys[0]` seals new data if `seal` is set; with it unset, as while encryption is being
+    /// turned off, data is written in plain text
+    Unlocked { keys: Vec<DataKey>, seal: bool },
+}
+
+static STATE: RwLock<State> = RwLock::new(State::Disabled);
+
+fn set_state(state: State) {
+    *STATE.write().unwrap() = state;
+}
+
+pub fn status() -> EncryptionStatus {
+    match &*STATE.read().unwrap() {
+        State::Disabled => EncryptionStatus::Disabled,
+        State::Locked => EncryptionStatus::Locked,
+        State::Unlocked { .. } => EncryptionStatus::Unlocked,
+    }
+}
+
+pub fn is_locked() -> bool {
+    status() == EncryptionStatus::Locked
+}
+
+pub fn is_enabled() -> bool {
+    status() != EncryptionStatus::Disabled
+}
+
+/// Whether data saved now is sealed rather than written in plain text
+pub fn is_sealing() -> bool {
+    matches!(*STATE.read().unwrap(), State::Unlocked { seal: true, .. })
+}
+
+/// `contents` sealed with the current key, or unchanged with encryption off
+pub fn seal(contents: &str) -> Result<String, Box<dyn Error>> {
+    match &*STATE.read().unwrap() {
+        State::Disabled | State::Unlocked { seal: false, .. } => Ok(contents.to_string()),
+        State::Locked => Err(Box::new(LockedError)),
+        State::Unlocked { keys, .. } => Ok(serde_json::to_string(&SealedFile { encrypted: seal_with(&keys[0], contents.as_bytes()) })?),
+    }
+}
+
+/// `bytes` sealed with the current key, for data that is only ever stored encrypted
+pub fn seal_bytes(bytes: &[u8]) -> Result<String, Box<dyn Error>> {
+    match &*STATE.read().unwrap() {
+        State::Unlocked { keys, seal: true } => Ok(serde_json::to_string(&SealedFile { encrypted: seal_with(&keys[0], bytes) })?),
+        State::Unlocked { .. } | State::Disabled => Err("Encryption is turned off".into()),
+        State::Locked => Err(Box::new(LockedError)),
+    }
+}
+
+/// Whether `contents` were written by `seal` or `seal_bytes` with encryption on
+pub fn is_sealed(contents: &str) -> bool {
+    serde_json::from_str::<SealedFile>(contents).is_ok()
+}
+
+/// Contents read from disk, decrypted if they were sealed
+pub fn unseal(contents: &str) -> Result<Cow<'_, str>, Box<dyn Error>> {
+    let Ok(SealedFile { encrypted }) = serde_json::from_str::<SealedFile>(contents) else {
+        return Ok(Cow::Borrowed(contents));
+    };
+    let plaintext = open_sealed(&encrypted)?;
+    Ok(Cow::Owned(String::from_utf8(plaintext.to_vec())?))
+}
+
+/// Contents written by `seal_bytes`, decrypted
+pub fn unseal_bytes(contents: &str) -> Result<Zeroizing<Vec<u8>>, Box<dyn Error>> {
+    let SealedFile { encrypted } = serde_json::from_str(contents).map_err(|_| "not encrypted data")?;
+    open_sealed(&encrypted)
+}
+
+fn open_sealed(sealed: &Sealed) -> Result<Zeroizing<Vec<u8>>, Box<dyn Error>> {
+    let state = STATE.read().unwrap();
+    let State::Unlocked { keys, .. } = &*state else {
+        return Err(Box::new(LockedError));
+    };
+    let key = keys
+        .iter()
+        .find(|key| key.id == sealed.key_id)
+        .ok_or_else(|| format!("encrypted with a key {} doesn't hold", KEY_FILE))?;
+    open_with(key, sealed)
+}
+
+// ================================================================================================
+// TURNING ENCRYPTION ON AND OFF
+// ================================================================================================
+
+/// Locked if `data_dir` has a key file, so nothing encrypted is read before the passphrase
+pub fn init(data_dir: &Path) -> EncryptionStatus {
+    set_state(if data_dir.join(KEY_FILE).exists() { State::Locked } else { State::Disabled });
+    status()
+}
+
+fn load_key_file(data_dir: &Path) -> Result<KeyFile, Box<dyn Error>> {
+    KeyFile::load(data_dir)?.ok_or_else(|| "Encryption is not enabled".into())
+}
+
+/// Create the key file for `passphrase` and seal everything saved from now on. The caller
+/// re-encrypts the existing files, then calls `finish_resealing`.
+pub fn enable(data_dir: &Path, passphrase: &str, kdf: KdfParams) -> Result<(), Box<dyn Error>> {
+    if is_enabled() || data_dir.join(KEY_FILE).exists() {
+        return Err("Encryption is already enabled".into());
+    }
+    let (key_file, key) = KeyFile::create(passphrase, kdf)?;
+    key_file.save(data_dir)?;
+    set_state(State::Unlocked { keys: vec![key], seal: true });
+    Ok(())
+}
+
+/// Unlock the data with `passphrase`. Returns the change to the files that was interrupted
+/// and has to be finished, if any; while encryption was being turned off, data is saved in
+/// plain text again straight away.
+pub fn unlock(data_dir: &Path, passphrase: &str) -> Result<Option<InterruptedChange>, Box<dyn Error>> {
+    let key_file = load_key_file(data_dir)?;
+    let keys = key_file.unlock(passphrase)?;
+    set_state(State::Unlocked { keys, seal: !key_file.disabling });
+    Ok(if key_file.disabling {
+        Some(InterruptedChange::Disable)
+    } else if key_file.resealing {
+        Some(InterruptedChange::Reseal)
+    } else {
+        None
+    })
+}
+
+/// Forget the keys; a no-op with encryption off
+pub fn lock() {
+    let mut state = STATE.write().unwrap();
+    if !matches!(*state, State::Disabled) {
+        *state = State::Locked;
+    }
+}
+
+/// Seal everything saved from now on with a new data key under `new_passphrase`. The caller
+/// re-encrypts the existing files, then calls `finish_resealing`.
+pub fn change_passphrase(data_dir: &Path, current: &str, new_passphrase: &str, kdf: KdfParams) -> Result<(), Box<dyn Error>> {
+    let key_file = load_key_file(data_dir)?;
+    let mut keys = key_file.unlock(current)?;
+    if key_file.previous_data_key.is_some() {
+        return Err("An earlier passphrase change is still being applied - unlock the data to finish it first".into());
+    }
+
+    let (new_key_file, new_key) = KeyFile::rekey(&keys[0], new_passphrase, kdf)?;
+    new_key_file.save(data_dir)?;
+    keys.insert(0, new_key);
+    set_state(State::Unlocked { keys, seal: true });
+    Ok(())
+}
+
+/// Record that every file is sealed with the current key, dropping any previous one
+pub fn finish_resealing(data_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let mut key_file = load_key_file(data_dir)?;
+    key_file.previous_data_key = None;
+    key_file.resealing = false;
+    key_file.save(data_dir)?;
+
+    if let State::Unlocked { keys, .. } = &mut *STATE.write().unwrap() {
+        keys.truncate(1);
+    }
+    Ok(())
+}
+
+/// Check `passphrase` and save everything in plain text from now on. The caller decrypts the
+/// existing files, then calls `finish_disabling`.
+pub fn begin_disabling(data_dir: &Path, passphrase: &str) -> Result<(), Box<dyn Error>> {
+    let mut key_file = load_key_file(data_dir)?;
+    let keys = key_file.unlock(passphrase)?;
+    key_file.disabling = true;
+    key_file.save(data_dir)?;
+    set_state(State::Unlocked { keys, seal: false });
+    Ok(())
+}
+
+/// Remove the key file once nothing is sealed any more
+pub fn finish_disabling(data_dir: &Path) -> Result<(), Box<dyn Error>> {
+    match fs::remove_file(data_dir.join(KEY_FILE)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    set_state(State::Disabled);
+    Ok(())
+}
+
+// ================================================================================================
+// STORE
+// ================================================================================================
+
+pub struct EncryptionStore {
+    /// Held through each change, so two never run at once
+    busy: Mutex<()>,
+    app_handle: AppHandle,
+}
+
+impl EncryptionStore {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            busy: Mutex::new(()),
+            app_handle,
+        }
+    }
+
+    fn data_dir(&self) -> Result<PathBuf, String> {
+        self.app_handle.path().app_data_dir().map_err(|e| format!("Could not resolve app data directory: {}", e))
+    }
+
+    /// Find out whether the data is encrypted. Runs in setup, before any store loads.
+    pub fn init(&self) {
+        match self.data_dir() {
+            Ok(data_dir) => {
+                if init(&data_dir) == EncryptionStatus::Locked {
+                    log::info!("App data is encrypted - locked until the passphrase is entered");
+                }
+            }
+            Err(e) => log::error!("{}", e),
+        }
+    }
+
+    pub fn enable(&self, passphrase: &str) -> Result<EncryptionStatus, String> {
+        let _busy = self.busy.lock().unwrap();
+        let dirs = DataDirs::of(&self.app_handle)?;
+        enable(&dirs.data, passphrase, KdfParams::generate()).map_err(|e| format!("Could not enable encryption: {}", e))?;
+        log::info!("Encryption enabled - encrypting the app data");
+
+        self.reseal(&dirs)?;
+        delete_weekly_reviews(&dirs.data);
+        Ok(self.announce())
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<EncryptionStatus, String> {
+        let _busy = self.busy.lock().unwrap();
+        let dirs = DataDirs::of(&self.app_handle)?;
+        let interrupted = unlock(&dirs.data, passphrase).map_err(|e| e.to_string())?;
+        log::info!("App data unlocked");
+
+        match interrupted {
+            Some(InterruptedChange::Reseal) => {
+                log::info!("Finishing an interrupted re-encryption of the app data");
+                self.reseal(&dirs)?;
+            }
+            Some(InterruptedChange::Disable) => {
+                log::info!("Finishing turning encryption off, which was interrupted");
+                self.decrypt(&dirs)?;
+            }
+            None => {
+                // Files couldn't be checked for older schemas while they were locked
+                if let Some(backups) = self.app_handle.try_state::<BackupStore>() {
+                    backups.backup_before_migration();
+                }
+                backups::reload_stores(&self.app_handle);
+            }
+        }
+        Ok(self.announce())
+    }
+
+    /// Forget the keys and the data loaded with them
+    pub fn lock(&self) -> Result<EncryptionStatus, String> {
+        let _busy = self.busy.lock().unwrap();
+        if !is_enabled() {
+            return Err("Encryption is not enabled".to_string());
+        }
+        lock();
+
+        if let Some(store) = self.app_handle.try_state::<AppDataStore>() {
+            store.close();
+        }
+        if let Some(service) = self.app_handle.try_state::<BackgroundNotificationService>() {
+            service.unload();
+        }
+        if let Some(history_store) = self.app_handle.try_state::<NotificationHistoryStore>() {
+            history_store.unload();
+        }
+        log::info!("App data locked");
+        Ok(self.announce())
+    }
+
+    pub fn change_passphrase(&self, current: &str, new_passphrase: &str) -> Result<EncryptionStatus, String> {
+        let _busy = self.busy.lock().unwrap();
+        let dirs = DataDirs::of(&self.app_handle)?;
+        change_passphrase(&dirs.data, current, new_passphrase, KdfParams::generate())
+            .map_err(|e| format!("Could not change the passphrase: {}", e))?;
+        log::info!("Passphrase changed - re-encrypting the app data");
+
+        self.reseal(&dirs)?;
+        Ok(self.announce())
+    }
+
+    pub fn disable(&self, passphrase: &str) -> Result<EncryptionStatus, String> {
+        let _busy = self.busy.lock().unwrap();
+        let dirs = DataDirs::of(&self.app_handle)?;
+        begin_disabling(&dirs.data, passphrase).map_err(|e| format!("Could not disable encryption: {}", e))?;
+        log::info!("Encryption disabled - decrypting the app data");
+
+        self.decrypt(&dirs)?;
+        Ok(self.announce())
+    }
+
+    /// Write every file back in plain text, then remove the key file
+    fn decrypt(&self, dirs: &DataDirs) -> Result<(), String> {
+        self.with_stores_paused(|| {
+            backups::reseal_files(dirs)?;
+            finish_disabling(&dirs.data)
+        })
+        .map_err(|e| format!("Could not decrypt the app data: {}", e))
+    }
+
+    /// Re-encrypt every file with the current key
+    fn reseal(&self, dirs: &DataDirs) -> Result<(), String> {
+        self.with_stores_paused(|| {
+            backups::reseal_files(dirs)?;
+            finish_resealing(&dirs.data)
+        })
+        .map_err(|e| format!("Could not re-encrypt the app data: {}", e))
+    }
+
+    /// Run `f` with the scheduler stopped and the database and event log closed, then reload
+    /// every store from the files
+    fn with_stores_paused(&self, f: impl FnOnce() -> Result<(), Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+        let scheduler_was_running = self
+            .app_handle
+            .try_state::<ReminderScheduler>()
+            .is_some_and(|scheduler| scheduler.stop());
+        if let Some(store) = self.app_handle.try_state::<AppDataStore>() {
+            store.close();
+        }
+
+        let result = f();
+        backups::reload_stores(&self.app_handle);
+        if scheduler_was_running {
+            if let Some(scheduler) = self.app_handle.try_state::<ReminderScheduler>() {
+                scheduler.start(self.app_handle.clone());
+            }
+        }
+        result
+    }
+
+    fn announce(&self) -> EncryptionStatus {
+        let status = status();
+        let _ = self.app_handle.emit(STATUS_EVENT, status);
+        status
+    }
+}
+
+/// Delete the weekly review reports in `data_dir`, plain text the passphrase doesn't protect
+fn delete_weekly_reviews(data_dir: &Path) {
+    match fs::remove_dir_all(data_dir.join(weekly_review::REVIEWS_DIR)) {
+        Ok(()) => log::info!("Deleted the weekly reviews written before encryption was enabled"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Could not delete the weekly reviews written before encryption was enabled: {}", e),
+    }
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn get_encryption_status() -> Result<EncryptionStatus, String> {
+    Ok(status())
+}
+
+/// Run `f` on the encryption store off the async runtime: deriving a key and re-encrypting the
+/// data take long enough to stall every other command
+async fn with_store_blocking(
+    app_handle: AppHandle,
+    f: impl FnOnce(&EncryptionStore) -> Result<EncryptionStatus, String> + Send + 'static,
+) -> Result<EncryptionStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Some(store) = app_handle.try_state::<EncryptionStore>() {
+            f(&store)
+        } else {
+            Err("Encryption not initialized".to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn enable_encryption(app_handle: AppHandle, passphrase: String) -> Result<EncryptionStatus, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    with_store_blocking(app_handle, move |store| store.enable(&passphrase)).await
+}
+
+#[tauri::command]
+pub async fn unlock_app_data(app_handle: AppHandle, passphrase: String) -> Result<EncryptionStatus, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    with_store_blocking(app_handle, move |store| store.unlock(&passphrase)).await
+}
+
+#[tauri::command]
+pub async fn lock_app_data(app_handle: AppHandle) -> Result<EncryptionStatus, String> {
+    if let Some(store) = app_handle.try_state::<EncryptionStore>() {
+        store.lock()
+    } else {
+        Err("Encryption not initialized".to_string())
+    }
+}
+
+/// Replace the passphrase and re-encrypt everything under a new key
+#[tauri::command]
+pub async fn change_encryption_passphrase(
+    app_handle: AppHandle,
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Result<EncryptionStatus, String> {
+    let current_passphrase = Zeroizing::new(current_passphrase);
+    let new_passphrase = Zeroizing::new(new_passphrase);
+    with_store_blocking(app_handle, move |store| store.change_passphrase(&current_passphrase, &new_passphrase)).await
+}
+
+#[tauri::command]
+pub async fn disable_encryption(app_handle: AppHandle, passphrase: String) -> Result<EncryptionStatus, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    with_store_blocking(app_handle, move |store| store.disable(&passphrase)).await
+}
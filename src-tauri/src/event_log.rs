@@ -0,0 +1,466 @@
+/*!
+ * ================================================================================================
+ * EVENT LOG
+ * ================================================================================================
+ *
+ * Append-only record of every change to the app data, one JSON event per line in
+ * `events.jsonl` in the app data directory. Events are never edited: the current data is the
+ * result of replaying them, starting from the latest snapshot, which records where in the file
+ * its events end so a long history is neither read nor replayed in full. Snapshots only skip
+ * replay: the log keeps every event, so the data as of any point can be replayed again, which
+ * is how a change is undone. The database is rebuilt from the replay whenever they disagree.
+ *
+ * The frontend saves whole fields at a time, so each save is turned into events by comparing
+ * the data before and after it. A save the events can't reproduce exactly is logged whole.
+ * With encryption enabled each line is sealed on its own, so appending stays a single write.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_data::{AppData, AppDataPatch, Completion, Habit, InventoryItem};
+use crate::app_settings::AppSettings;
+use crate::encryption::{self, LockedError};
+use crate::persistence::{self, Schema};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+pub const EVENT_LOG_FILE: &str = "events.jsonl";
+
+pub const SNAPSHOT_FILE: &str = "events_snapshot.json";
+
+/// Events appended between snapshots of the replayed data
+pub const SNAPSHOT_INTERVAL: u64 = 200;
+
+/// Schema history of `events_snapshot.json`
+pub const EVENT_SNAPSHOT_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+// ================================================================================================
+// EVENTS
+// ================================================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// All data replaced, by an import or by data stored before the log existed
+    DataReplaced { data: Box<AppData> },
+    HabitCreated { habit: Habit },
+    /// Everything about a habit but its completions, which change by `CompletionToggled`
+    HabitEdited { habit: Habit },
+    HabitDeleted { habit_id: String },
+    /// `completion` is `None` when the period was marked incomplete again
+    CompletionToggled {
+        habit_id: String,
+        period_key: String,
+        completion: Option<Completion>,
+    },
+    RewardRedeemed { item: InventoryItem },
+    /// Change in total XP and points
+    XpGranted { xp: i64, points: i64 },
+    SettingsChanged { settings: AppSettings },
+    /// Shop, goals, categories, inventory, achievements or stats changed other than by the above
+    DataPatched { patch: Box<AppDataPatch> },
+    /// The app was used
+    ActivityRecorded,
+    /// A completion reported through `record_habit_completion_backend`
+    HabitCompletionRecorded { habit_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoggedEvent {
+    /// Position in the log, from 1
+    pub seq: u64,
+    pub at: DateTime<Local>,
+    pub event: Event,
+}
+
+impl Event {
+    /// Apply this event to the data replayed so far; activity events leave it alone
+    pub fn apply(&self, data: &mut Option<AppData>) {
+        if let Event::DataReplaced { data: replacement } = self {
+            *data = Some((**replacement).clone());
+            return;
+        }
+        let Some(data) = data.as_mut() else {
+            return;
+        };
+
+        match self {
+            Event::HabitCreated { habit } => data.habits.push(habit.clone()),
+            Event::HabitEdited { habit } => {
+                if let Some(existing) = data.habits.iter_mut().find(|existing| existing.id == habit.id) {
+                    let completions = std::mem::take(&mut existing.completions);
+                    *existing = Habit { completions, ..habit.clone() };
+                }
+            }
+            Event::HabitDeleted { habit_id } => data.habits.retain(|habit| &habit.id != habit_id),
+            Event::CompletionToggled { habit_id, period_key, completion } => {
+                if let Some(habit) = data.habits.iter_mut().find(|habit| &habit.id == habit_id) {
+                    match completion {
+                        Some(completion) => habit.completions.insert(period_key.clone(), *completion),
+                        None => habit.completions.remove(period_key),
+                    };
+                }
+            }
+            Event::RewardRedeemed { item } => data.inventory.insert(0, item.clone()),
+            Event::XpGranted { xp, points } => {
+                data.total_xp = add_signed(data.total_xp, *xp);
+                data.points = add_signed(data.points, *points);
+            }
+            Event::SettingsChanged { settings } => data.settings = settings.clone(),
+            Event::DataPatched { patch } => (**patch).clone().apply(data),
+            Event::DataReplaced { .. } | Event::ActivityRecorded | Event::HabitCompletionRecorded { .. } => {}
+        }
+    }
+
+    /// Whether replaying this event changes the app data rather than the activity history
+    pub fn changes_app_data(&self) -> bool {
+        !matches!(self, Event::ActivityRecorded | Event::HabitCompletionRecorded { .. })
+    }
+}
+
+fn add_signed(value: u32, delta: i64) -> u32 {
+    u32::try_from((i64::from(value) + delta).max(0)).unwrap_or(u32::MAX)
+}
+
+fn without_completions(habit: &Habit) -> Habit {
+    Habit { completions: Default::default(), ..habit.clone() }
+}
+
+/// The events that turn `before` into `after`, empty if nothing changed
+pub fn events_between(before: Option<&AppData>, after: &AppData) -> Vec<Event> {
+    let Some(before) = before else {
+        return vec![Event::DataReplaced { data: Box::new(after.clone()) }];
+    };
+    let mut events = Vec::new();
+
+    for habit in &before.habits {
+        if !after.habits.iter().any(|kept| kept.id == habit.id) {
+            events.push(Event::HabitDeleted { habit_id: habit.id.clone() });
+        }
+    }
+    for habit in &after.habits {
+        let Some(previous) = before.habits.iter().find(|previous| previous.id == habit.id) else {
+            events.push(Event::HabitCreated { habit: habit.clone() });
+            continue;
+        };
+
+        for period_key in previous.completions.keys().filter(|key| !habit.completions.contains_key(*key)) {
+            events.push(Event::CompletionToggled {
+                habit_id: habit.id.clone(),
+                period_key: period_key.clone(),
+                completion: None,
+            });
+        }
+        for (period_key, completion) in &habit.completions {
+            if previous.completions.get(period_key) != Some(completion) {
+                events.push(Event::CompletionToggled {
+                    habit_id: habit.id.clone(),
+                    period_key: period_key.clone(),
+                    completion: Some(*completion),
+                });
+            }
+        }
+        let edited = without_completions(habit);
+        if edited != without_completions(previous) {
+            events.push(Event::HabitEdited { habit: edited });
+        }
+    }
+
+    let mut patch = AppDataPatch::default();
+    let redeemed = after.inventory.len().saturating_sub(before.inventory.len());
+    if after.inventory[redeemed..] == before.inventory[..] {
+        for item in after.inventory[..redeemed].iter().rev() {
+            events.push(Event::RewardRedeemed { item: item.clone() });
+        }
+    } else {
+        patch.inventory = Some(after.inventory.clone());
+    }
+
+    let xp = i64::from(after.total_xp) - i64::from(before.total_xp);
+    let points = i64::from(after.points) - i64::from(before.points);
+    if xp != 0 || points != 0 {
+        events.push(Event::XpGranted { xp, points });
+    }
+    if after.settings != before.settings {
+        events.push(Event::SettingsChanged { settings: after.settings.clone() });
+    }
+
+    if after.shop != before.shop {
+        patch.shop = Some(after.shop.clone());
+    }
+    if after.goals != before.goals {
+        patch.goals = Some(after.goals.clone());
+    }
+    if after.categories != before.categories {
+        patch.categories = Some(after.categories.clone());
+    }
+    if after.achievements != before.achievements {
+        patch.achievements = Some(after.achievements.clone());
+    }
+    if after.user_stats != before.user_stats {
+        patch.user_stats = Some(after.user_stats.clone());
+    }
+    if after.version != before.version {
+        patch.version = Some(after.version.clone());
+    }
+    if patch != AppDataPatch::default() {
+        events.push(Event::DataPatched { patch: Box::new(patch) });
+    }
+
+    // Habits reordered, say, which the events above can't express
+    let mut replayed = Some(before.clone());
+    for event in &events {
+        event.apply(&mut replayed);
+    }
+    if replayed.as_ref() != Some(after) {
+        return vec![Event::DataReplaced { data: Box::new(after.clone()) }];
+    }
+    events
+}
+
+// ================================================================================================
+// LOG FILE
+// ================================================================================================
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Last event reflected in `data`
+    seq: u64,
+    /// Length of the log up to event `seq`; 0 in snapshots taken before it was recorded
+    #[serde(default)]
+    offset: u64,
+    data: Option<AppData>,
+}
+
+pub struct EventLog {
+    path: PathBuf,
+    snapshot_path: PathBuf,
+    last_seq: u64,
+    snapshot_seq: u64,
+    /// Where the events after the snapshot start, if the log still matches it
+    snapshot_offset: Option<u64>,
+}
+
+impl EventLog {
+    /// Open the log in `dir`, dropping a last line left incomplete by a crash mid-append
+    pub fn open(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = dir.join(EVENT_LOG_FILE);
+        let snapshot_path = dir.join(SNAPSHOT_FILE);
+
+        let snapshot = load_snapshot(&snapshot_path);
+        let after_snapshot = match &snapshot {
+            Some(snapshot) => read_after_snapshot(&path, snapshot)?,
+            None => None,
+        };
+        let snapshot_seq = snapshot.as_ref().map_or(0, |snapshot| snapshot.seq);
+        let (last_seq, valid_len, snapshot_offset) = match after_snapshot {
+            Some((events, valid_len)) => {
+                let offset = snapshot.as_ref().map(|snapshot| snapshot.offset);
+                (events.last().map_or(snapshot_seq, |event| event.seq), valid_len, offset)
+            }
+            None => {
+                let (events, valid_len) = read_events(&path, 0)?;
+                (events.last().map_or(0, |event| event.seq), valid_len, None)
+            }
+        };
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > valid_len) {
+            log::warn!("Dropping an incomplete last entry from {}", EVENT_LOG_FILE);
+            OpenOptions::new().write(true).open(&path)?.set_len(valid_len)?;
+        }
+
+        Ok(Self {
+            path,
+            snapshot_path,
+            last_seq,
+            snapshot_seq,
+            snapshot_offset,
+        })
+    }
+
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    /// Append `events`, all recorded at `at`, and make them durable before returning them
+    pub fn append(&mut self, at: DateTime<Local>, events: Vec<Event>) -> Result<Vec<LoggedEvent>, Box<dyn std::error::Error>> {
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let logged: Vec<LoggedEvent> = events
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| LoggedEvent { seq: self.last_seq + 1 + i as u64, at, event })
+            .collect();
+        let mut lines = String::new();
+        for event in &logged {
+            lines.push_str(&encode_line(event)?);
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+        file.sync_all()?;
+
+        self.last_seq += logged.len() as u64;
+        Ok(logged)
+    }
+
+    /// Events after `seq`, oldest first. Those after the snapshot are read from where it ends.
+    pub fn events_after(&self, seq: u64) -> Result<Vec<LoggedEvent>, Box<dyn std::error::Error>> {
+        let offset = match self.snapshot_offset {
+            Some(offset) if seq >= self.snapshot_seq => offset,
+            _ => 0,
+        };
+        let (events, _) = read_events(&self.path, offset)?;
+        Ok(events.into_iter().filter(|event| event.seq > seq).collect())
+    }
+
+    /// The app data as of the last event: the latest snapshot with the events since replayed
+    pub fn replay(&self) -> Result<Option<AppData>, Box<dyn std::error::Error>> {
+        self.replay_until(self.last_seq)
+    }
+
+    /// The app data as of event `seq`, replayed from the latest snapshot at or before it
+    pub fn replay_until(&self, seq: u64) -> Result<Option<AppData>, Box<dyn std::error::Error>> {
+        if seq > self.last_seq {
+            return Err(format!("There is no event {} in the log, which ends at {}", seq, self.last_seq).into());
+        }
+        let snapshot = load_snapshot(&self.snapshot_path)
+            .filter(|snapshot| snapshot.seq <= seq)
+            .unwrap_or(Snapshot { seq: 0, offset: 0, data: None });
+
+        let events = self.events_after(snapshot.seq)?;
+        if let Some(first) = events.first().filter(|first| first.seq != snapshot.seq + 1) {
+            return Err(format!("{} starts at event {}, so event {} can't be replayed", EVENT_LOG_FILE, first.seq, seq).into());
+        }
+        let mut data = snapshot.data;
+        for logged in events.into_iter().take_while(|logged| logged.seq <= seq) {
+            logged.event.apply(&mut data);
+        }
+        Ok(data)
+    }
+
+    pub fn needs_snapshot(&self) -> bool {
+        self.last_seq >= self.snapshot_seq + SNAPSHOT_INTERVAL
+    }
+
+    /// Record `data` as the result of replaying the log so far, with where in the file the
+    /// events after it will start
+    pub fn save_snapshot(&mut self, data: Option<&AppData>) -> Result<(), Box<dyn std::error::Error>> {
+        let offset = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        persistence::save_json(&self.snapshot_path, &Snapshot { seq: self.last_seq, offset, data: data.cloned() }, &EVENT_SNAPSHOT_SCHEMA)?;
+        self.snapshot_seq = self.last_seq;
+        self.snapshot_offset = Some(offset);
+        Ok(())
+    }
+}
+
+/// Events read from the log, and the length of the file they end at
+type EventsRead = (Vec<LoggedEvent>, u64);
+
+fn load_snapshot(path: &Path) -> Option<Snapshot> {
+    persistence::load_json::<Snapshot>(path, &EVENT_SNAPSHOT_SCHEMA)
+        .unwrap_or_else(|e| {
+            log::warn!("Ignoring the event log snapshot and replaying in full: {}", e);
+            None
+        })
+        .map(|loaded| loaded.value)
+}
+
+/// The events after `snapshot` and the length of the file they end at, read from the
+/// snapshot's offset. `None` if the log no longer matches the snapshot there, as after a
+/// restore or a reseal, so it has to be read from the start.
+fn read_after_snapshot(path: &Path, snapshot: &Snapshot) -> Result<Option<EventsRead>, Box<dyn std::error::Error>> {
+    if snapshot.offset == 0 || fs::metadata(path).map_or(true, |metadata| metadata.len() < snapshot.offset) {
+        return Ok(None);
+    }
+    // The offset must fall just after a line
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(snapshot.offset - 1))?;
+    let mut last_byte = [0];
+    file.read_exact(&mut last_byte)?;
+    if last_byte[0] != b'\n' {
+        return Ok(None);
+    }
+
+    match read_events(path, snapshot.offset) {
+        Ok((events, valid_len)) if events.first().map_or(true, |event| event.seq == snapshot.seq + 1) => Ok(Some((events, valid_len))),
+        Ok(_) => Ok(None),
+        Err(e) if e.is::<LockedError>() => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Check that every line of the log at `path` is a complete event
+pub fn check_log(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let (events, valid_len) = read_events(path, 0)?;
+    if fs::metadata(path)?.len() > valid_len {
+        return Err(format!("{} ends with an incomplete entry after event {}", path.display(), events.len()).into());
+    }
+    Ok(())
+}
+
+/// Rewrite the log at `path` with every line as `append` now writes it: sealed with the current
+/// key, or in plain text with encryption off
+pub fn reseal_log(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let (events, _) = read_events(path, 0)?;
+    let mut lines = String::new();
+    for event in &events {
+        lines.push_str(&encode_line(event)?);
+    }
+    persistence::write_atomic(path, lines.as_bytes())?;
+    Ok(())
+}
+
+fn encode_line(event: &LoggedEvent) -> Result<String, Box<dyn std::error::Error>> {
+    let mut line = encryption::seal(&serde_json::to_string(event)?)?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn decode_line(line: &[u8]) -> Result<LoggedEvent, Box<dyn std::error::Error>> {
+    let line = std::str::from_utf8(line)?;
+    Ok(serde_json::from_str(&encryption::unseal(line)?)?)
+}
+
+/// The events in `path` from byte `offset` on, and the length of the file they end at. Only
+/// the last line may be unreadable, from a crash while it was appended; anywhere else the log
+/// is corrupt.
+fn read_events(path: &Path, offset: u64) -> Result<EventsRead, Box<dyn std::error::Error>> {
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e.into()),
+    };
+    let mut contents = Vec::new();
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_to_end(&mut contents)?;
+
+    let mut events = Vec::new();
+    let mut valid_len = offset;
+    let mut lines = contents.split_inclusive(|byte| *byte == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        match decode_line(line) {
+            Ok(event) if line.ends_with(b"\n") => {
+                events.push(event);
+                valid_len += line.len() as u64;
+            }
+            // Only the last line can be missing its newline
+            Ok(_) => break,
+            Err(e) if e.is::<LockedError>() => return Err(e),
+            Err(_) if lines.peek().is_none() => break,
+            Err(e) => return Err(format!("{} is corrupt after event {}: {}", path.display(), events.last().map_or(0, |event: &LoggedEvent| event.seq), e).into()),
+        }
+    }
+    Ok((events, valid_len))
+}
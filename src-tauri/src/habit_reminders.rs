@@ -0,0 +1,145 @@
+/*!
+ * ================================================================================================
+ * HABIT REMINDERS
+ * ================================================================================================
+ *
+ * Per-habit reminder schedules owned by the backend, so they keep firing while the window
+ * is hidden in the tray. Persisted as `habit_reminders.json` next to the notification config.
+ *
+ * @version 1.0.0
+ */
+
+use crate::background_notifications::BackgroundNotificationService;
+use crate::persistence::{self, Schema};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// Schema history of `habit_reminders.json`: v1 added the envelope
+pub const HABIT_REMINDERS_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HabitReminder {
+    /// Assigned by the backend when left empty on creation
+    #[serde(default)]
+    pub id: String,
+    pub habit_id: String,
+    /// Display name used in the notification text
+    #[serde(default)]
+    pub habit_name: Option<String>,
+    pub hour: u32,
+    pub minute: u32,
+    /// Days the reminder fires on. Empty means every day.
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    pub enabled: bool,
+}
+
+impl HabitReminder {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.habit_id.is_empty() {
+            return Err("Habit reminder needs a habit id".to_string());
+        }
+        if self.hour > 23 || self.minute > 59 {
+            return Err(format!("Invalid reminder time {:02}:{:02}", self.hour, self.minute));
+        }
+        Ok(())
+    }
+
+    /// Validate a reminder being created, giving it an id if it has none
+    pub fn prepare_new(mut self, now: DateTime<Local>) -> Result<Self, String> {
+        self.validate()?;
+        if self.id.is_empty() {
+            self.id = format!("reminder_{}_{}", self.habit_id, now.timestamp_millis());
+        }
+        Ok(self)
+    }
+
+    pub fn fires_on(&self, day: NaiveDate) -> bool {
+        self.enabled && (self.weekdays.is_empty() || self.weekdays.contains(&day.weekday()))
+    }
+
+    /// The local time this reminder fires on `day`, if it fires that day at all.
+    /// Times skipped by a DST transition are dropped for that day.
+    pub fn occurrence_on(&self, day: NaiveDate) -> Option<DateTime<Local>> {
+        if !self.fires_on(day) {
+            return None;
+        }
+        let time = NaiveTime::from_hms_opt(self.hour, self.minute, 0)?;
+        Local.from_local_datetime(&day.and_time(time)).earliest()
+    }
+}
+
+/// Insert a reminder, replacing any existing one with the same id
+pub fn upsert(reminders: &mut Vec<HabitReminder>, reminder: HabitReminder) {
+    match reminders.iter_mut().find(|existing| existing.id == reminder.id) {
+        Some(existing) => *existing = reminder,
+        None => reminders.push(reminder),
+    }
+}
+
+/// Returns false if no reminder with that id exists
+pub fn remove(reminders: &mut Vec<HabitReminder>, id: &str) -> bool {
+    let count_before = reminders.len();
+    reminders.retain(|reminder| reminder.id != id);
+    reminders.len() != count_before
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn list_habit_reminders(app_handle: AppHandle) -> Result<Vec<HabitReminder>, String> {
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        Ok(service.habit_reminders())
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn create_habit_reminder(
+    app_handle: AppHandle,
+    reminder: HabitReminder,
+) -> Result<HabitReminder, String> {
+    let reminder = reminder.prepare_new(Local::now())?;
+
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        service.upsert_habit_reminder(reminder.clone());
+        Ok(reminder)
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn update_habit_reminder(
+    app_handle: AppHandle,
+    reminder: HabitReminder,
+) -> Result<HabitReminder, String> {
+    reminder.validate()?;
+
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        if !service.habit_reminders().iter().any(|existing| existing.id == reminder.id) {
+            return Err(format!("Habit reminder {} not found", reminder.id));
+        }
+        service.upsert_habit_reminder(reminder.clone());
+        Ok(reminder)
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn delete_habit_reminder(app_handle: AppHandle, id: String) -> Result<(), String> {
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        if service.remove_habit_reminder(&id) {
+            Ok(())
+        } else {
+            Err(format!("Habit reminder {} not found", id))
+        }
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
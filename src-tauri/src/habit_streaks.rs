@@ -0,0 +1,256 @@
+/*!
+ * ================================================================================================
+ * HABIT STREAKS
+ * ================================================================================================
+ *
+ * Each habit's current streak, synced from the frontend, and the escalating warnings sent as
+ * the end of a habit's period approaches while its streak is still unprotected. Longer
+ * streaks are warned about earlier and more often. Persisted as `habit_streaks.json` next to
+ * the notification config.
+ *
+ * @version 1.0.0
+ */
+
+use crate::background_notifications::BackgroundNotificationService;
+use crate::persistence::{self, Schema};
+use crate::quiet_hours::QuietHours;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager};
+
+/// Warnings always sent for a streak at the threshold; each doubling of the threshold adds one
+const MIN_WARNING_STAGES: usize = 2;
+
+/// How far back a warning that lands in quiet hours is moved, per step
+const QUIET_HOURS_STEP_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HabitFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl HabitFrequency {
+    /// Start and end of the period containing `now`. Weeks start on Monday, as in the
+    /// frontend's period keys.
+    pub fn period_bounds(self, now: DateTime<Local>) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let today = now.date_naive();
+        let (start, end) = match self {
+            HabitFrequency::Daily => (today, today.succ_opt()?),
+            HabitFrequency::Weekly => {
+                let monday = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+                (monday, monday + Duration::days(7))
+            }
+            HabitFrequency::Monthly => {
+                let first = today.with_day(1)?;
+                let next = if first.month() == 12 {
+                    NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)?
+                };
+                (first, next)
+            }
+            HabitFrequency::Yearly => (
+                NaiveDate::from_ymd_opt(today.year(), 1, 1)?,
+                NaiveDate::from_ymd_opt(today.year() + 1, 1, 1)?,
+            ),
+        };
+        Some((start_of_day(start)?, start_of_day(end)?))
+    }
+
+    /// Key of the period containing `day` in a habit's `completions`, like the frontend's
+    /// `getPeriodKey`: `2025-06-09`, ISO week `2025-W24`, `2025-06` or `2025`
+    pub fn period_key(self, day: NaiveDate) -> String {
+        match self {
+            HabitFrequency::Daily => day.format("%Y-%m-%d").to_string(),
+            HabitFrequency::Weekly => {
+                let week = day.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            HabitFrequency::Monthly => day.format("%Y-%m").to_string(),
+            HabitFrequency::Yearly => day.format("%Y").to_string(),
+        }
+    }
+
+    /// First day of the period containing `day`
+    fn period_start(self, day: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            HabitFrequency::Daily => Some(day),
+            HabitFrequency::Weekly => Some(day - Duration::days(i64::from(day.weekday().num_days_from_monday()))),
+            HabitFrequency::Monthly => day.with_day(1),
+            HabitFrequency::Yearly => day.with_ordinal(1),
+        }
+    }
+
+    /// Hours before the end of the period at which warnings go out, least urgent first
+    fn warning_hours(self) -> &'static [i64] {
+        match self {
+            HabitFrequency::Daily => &[12, 8, 4, 2],
+            HabitFrequency::Weekly => &[96, 48, 24, 6],
+            HabitFrequency::Monthly => &[168, 72, 24, 12],
+            HabitFrequency::Yearly => &[720, 168, 72, 24],
+        }
+    }
+}
+
+/// First instant of `day`, which a DST change can move past midnight
+fn start_of_day(day: NaiveDate) -> Option<DateTime<Local>> {
+    (0..3).find_map(|hour| {
+        let time = NaiveTime::from_hms_opt(hour, 0, 0)?;
+        Local.from_local_datetime(&day.and_time(time)).earliest()
+    })
+}
+
+/// Schema history of `habit_streaks.json`: v1 added the envelope
+pub const HABIT_STREAKS_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HabitStreak {
+    pub habit_id: String,
+    /// Display name used in the notification text
+    pub habit_name: String,
+    pub frequency: HabitFrequency,
+    /// Consecutive periods completed, as counted by `Habit::complete` and the frontend
+    pub streak: u32,
+    #[serde(default)]
+    pub last_completed_at: Option<DateTime<Local>>,
+    #[serde(default = "default_is_recurring")]
+    pub is_recurring: bool,
+    /// Date a one-off habit is scheduled for; it only counts in the period containing it
+    #[serde(default)]
+    pub specific_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub xp_on_complete: u32,
+    #[serde(default)]
+    pub category: String,
+    /// Every recorded completion, one per period
+    #[serde(default)]
+    pub completions: Vec<DateTime<Local>>,
+}
+
+fn default_is_recurring() -> bool {
+    true
+}
+
+impl HabitStreak {
+    /// Whether the habit is scheduled in the period containing `now`, like the frontend's
+    /// `habitVisibleOnDate`
+    pub fn scheduled_in_period(&self, now: DateTime<Local>) -> bool {
+        match self.specific_date {
+            Some(date) if !self.is_recurring => {
+                let Some(date_start) = start_of_day(date) else {
+                    return false;
+                };
+                self.frequency.period_bounds(date_start) == self.frequency.period_bounds(now)
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the habit was completed in the period containing `now`
+    pub fn completed_in_period(&self, completed_at: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+        match (completed_at, self.frequency.period_bounds(now)) {
+            (Some(completed_at), Some((start, end))) => completed_at >= start && completed_at < end,
+            _ => false,
+        }
+    }
+
+    /// Streak as of `day`, counted from `completions` the way the frontend counts its day
+    /// streak: the run of completed periods up to the one containing `day`, which doesn't break
+    /// the run while it isn't completed
+    pub fn streak_on(&self, day: NaiveDate) -> u32 {
+        let completed: HashSet<String> = self
+            .completions
+            .iter()
+            .map(|at| at.date_naive())
+            .filter(|completed_on| *completed_on <= day)
+            .map(|completed_on| self.frequency.period_key(completed_on))
+            .collect();
+        let is_completed = |day: &NaiveDate| completed.contains(&self.frequency.period_key(*day));
+        let previous_period = |day: NaiveDate| self.frequency.period_start(day).and_then(|start| start.pred_opt());
+
+        let mut period = if is_completed(&day) { Some(day) } else { previous_period(day) };
+        let mut streak = 0;
+        while let Some(day) = period.filter(is_completed) {
+            streak += 1;
+            period = previous_period(day);
+        }
+        streak
+    }
+
+    /// Number of warnings sent for this streak in one period. Streaks below `threshold` get none.
+    pub fn warning_stages(&self, threshold: u32) -> usize {
+        let threshold = threshold.max(1);
+        if self.streak < threshold {
+            return 0;
+        }
+
+        let ladder_len = self.frequency.warning_hours().len();
+        let mut stages = MIN_WARNING_STAGES;
+        let mut level = threshold.saturating_mul(2);
+        while self.streak >= level && stages < ladder_len {
+            stages += 1;
+            level = level.saturating_mul(2);
+        }
+        stages.min(ladder_len)
+    }
+
+    /// End of the current period if the streak is still alive but hasn't been extended in it,
+    /// i.e. the latest completion (`completed_at`) falls in the previous period
+    pub fn at_risk_until(&self, completed_at: Option<DateTime<Local>>, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        if self.streak == 0 || !self.scheduled_in_period(now) {
+            return None;
+        }
+        let (start, end) = self.frequency.period_bounds(now)?;
+        let (previous_start, _) = self.frequency.period_bounds(start - Duration::seconds(1))?;
+        let completed_at = completed_at?;
+        (completed_at >= previous_start && completed_at < start).then_some(end)
+    }
+
+    /// Times of this period's warnings, least urgent first. A warning that would land in quiet
+    /// hours goes out just before they start instead; warnings that end up at the same time
+    /// are merged.
+    pub fn warning_times(&self, threshold: u32, quiet_hours: &QuietHours, period_end: DateTime<Local>) -> Vec<DateTime<Local>> {
+        let ladder = self.frequency.warning_hours();
+        let mut times: Vec<DateTime<Local>> = ladder[ladder.len() - self.warning_stages(threshold)..]
+            .iter()
+            .filter_map(|hours| before_quiet_hours(period_end - Duration::hours(*hours), quiet_hours))
+            .collect();
+        times.dedup();
+        times
+    }
+}
+
+/// `at`, or the latest time before it outside quiet hours, looking back at most a day
+fn before_quiet_hours(at: DateTime<Local>, quiet_hours: &QuietHours) -> Option<DateTime<Local>> {
+    let steps = 24 * 60 / QUIET_HOURS_STEP_MINUTES;
+    (0..=steps)
+        .map(|step| at - Duration::minutes(step * QUIET_HOURS_STEP_MINUTES))
+        .find(|time| !quiet_hours.is_quiet(*time))
+}
+
+/// Warnings already sent for a habit's current period
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StreakWarningsSent {
+    pub period_end: DateTime<Local>,
+    pub stages: usize,
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+/// Superseded by the app data commands, which share the habits themselves; kept for older frontends
+#[tauri::command]
+pub async fn sync_habit_streaks(app_handle: AppHandle, habits: Vec<HabitStreak>) -> Result<(), String> {
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        service.set_habit_streaks(habits);
+        Ok(())
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
@@ -1,18 +1,94 @@
-mod background_notifications;
+pub mod activity_insights;
+pub mod app_data;
+pub mod app_settings;
+pub mod background_notifications;
+pub mod backups;
+pub mod clock;
+pub mod digests;
+pub mod encryption;
+pub mod event_log;
+pub mod habit_reminders;
+pub mod habit_streaks;
+pub mod messages;
+pub mod notification_actions;
+pub mod notification_budget;
+pub mod notification_dispatcher;
+pub mod notification_history;
+pub mod notification_sink;
+pub mod persistence;
+pub mod quiet_hours;
+pub mod random_reminders;
+mod scheduler;
+pub mod startup_summary;
+pub mod storage;
+pub mod weekly_review;
 
+use app_data::*;
+use app_settings::*;
 use background_notifications::*;
+use backups::*;
+use clock::*;
+use encryption::*;
+use habit_reminders::*;
+use habit_streaks::*;
+use notification_actions::*;
+use notification_budget::BudgetCategory;
+use notification_dispatcher::*;
+use notification_history::*;
+use notification_sink::*;
+use persistence::{take_storage_warnings, StorageWarnings};
+use scheduler::*;
+use weekly_review::*;
 use tauri::{Manager, Emitter};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
-use tauri_plugin_notification::NotificationExt;
-use chrono::Timelike;
 use std::env;
 
+// Clock for all reminder logic. Debug builds accept --simulate-now for manual QA.
+fn app_clock() -> SharedClock {
+  #[cfg(debug_assertions)]
+  match simulated_clock_from_args(env::args()) {
+    Ok(Some(clock)) => {
+      log::warn!("Running with a simulated clock - current time is {}", clock.now().format("%Y-%m-%d %H:%M"));
+      return clock;
+    }
+    Ok(None) => {}
+    Err(e) => log::error!("Ignoring {}: {}", SIMULATE_NOW_FLAG, e),
+  }
+
+  std::sync::Arc::new(SystemClock)
+}
+
 // Check if app was launched on system startup
 fn is_startup_launch() -> bool {
     // Only check for explicit autostart arguments
     env::args().any(|arg| arg == "--autostart" || arg == "--startup")
 }
 
+/// Notify the user of today's due habits and the streaks at stake
+fn send_startup_summary(app_handle: &tauri::AppHandle) {
+  if encryption::is_locked() {
+    log::info!("App data is locked - no startup summary");
+    return;
+  }
+  let Some(service) = app_handle.try_state::<BackgroundNotificationService>() else {
+    log::error!("Background notification service not initialized - no startup summary");
+    return;
+  };
+
+  let summary = service.startup_summary(service.now());
+  let (title, body) = summary.notification_text(current_locale(app_handle));
+  let notification = OutgoingNotification::new(
+    NotificationKind::Startup,
+    format!("App launched on system startup with {} habits due", summary.due.len()),
+    title,
+    body,
+  );
+  match dispatch_notification(app_handle, &notification) {
+    Ok(result) => log::info!("Startup notification: {:?}", result),
+    Err(e) => log::error!("Failed to send startup notification: {}", e),
+  }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
@@ -30,25 +106,67 @@ pub fn run() {
       let handle = app.handle().clone();
       let is_startup = is_startup_launch();
 
+      let clock = app_clock();
+      app.manage(clock.clone());
+
+      // Before anything is loaded, so files restored from a backup can be reported
+      app.manage(StorageWarnings::default());
+
+      // Encrypted data stays locked, and unread, until the passphrase is entered
+      let encryption_store = EncryptionStore::new(handle.clone());
+      encryption_store.init();
+      app.manage(encryption_store);
+
+      // Copy the data aside before any of it is migrated to this version's schemas
+      let backup_store = BackupStore::new(handle.clone());
+      backup_store.backup_before_migration();
+      app.manage(backup_store);
+
+      // Register the notification service up front so background checks can read persisted activity
+      let notification_service = BackgroundNotificationService::new(handle.clone(), clock);
+      if let Err(e) = notification_service.load_from_files() {
+        log::warn!("Could not load notification data: {}", e);
+      }
+      app.manage(notification_service);
+
+      let history_store = NotificationHistoryStore::new(handle.clone());
+      if let Err(e) = history_store.load_from_file() {
+        log::warn!("Could not load notification history: {}", e);
+      }
+      app.manage(history_store);
+      app.manage(NotificationDispatcher::new(DesktopNotificationSink::new(handle.clone())));
+
+      let settings_store = AppSettingsStore::new(handle.clone());
+      if let Err(e) = settings_store.load_from_file() {
+        log::warn!("Could not load app settings: {}", e);
+      }
+      app.manage(settings_store);
+
+      // Canonical habit data; opened last so it can refresh the copies the services above keep
+      let app_data_store = AppDataStore::new(handle.clone());
+      if let Err(e) = app_data_store.open() {
+        log::warn!("Could not load app data: {}", e);
+      }
+      app.manage(app_data_store);
+
       // Handle startup behavior
-      if is_startup {
-        log::info!("App launched on startup - showing notification only");
+      let startup_behavior = handle.state::<AppSettingsStore>().settings().startup_behavior;
+      if is_startup && startup_behavior != StartupBehavior::OpenWindow {
+        log::info!("App launched on startup - staying in the tray ({:?})", startup_behavior);
         
         // Hide the main window immediately if launched on startup
         if let Some(window) = handle.get_webview_window("main") {
           let _ = window.hide();
         }
 
-        // Send startup notification after brief delay
-        let notification_handle = handle.clone();
-        tauri::async_runtime::spawn(async move {
-          tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-          let _ = notification_handle.notification().builder()
-            .title("🎯 HabitQuest is Ready")
-            .body("Your habit tracker is running in the background. Click to open!")
-            .show();
-          log::info!("Startup notification sent");
-        });
+        // Send the summary of what's due after brief delay
+        if startup_behavior == StartupBehavior::Summary {
+          let notification_handle = handle.clone();
+          tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            send_startup_summary(&notification_handle);
+          });
+        }
       } else {
         log::info!("Normal app launch - showing window");
         // Normal launch - show window immediately
@@ -60,8 +178,8 @@ pub fn run() {
 
       // Create system tray icon for background operation
       let handle_for_tray = handle.clone();
-      TrayIconBuilder::new()
-        .tooltip("HabitQuest - Habit Tracker (Click to restore)")
+      TrayIconBuilder::with_id(TRAY_ICON_ID)
+        .tooltip(localized(&handle, "tray.tooltip", &[]))
         .icon(app.default_window_icon().unwrap().clone())
         .on_tray_icon_event(move |_tray, event| {
           if let TrayIconEvent::Click { .. } = event {
@@ -86,11 +204,10 @@ pub fn run() {
         let _ = handle.autolaunch().enable();
       }
 
-      // Initialize background notification service with enhanced scheduling
-      let app_handle = app.handle().clone();
-      tauri::async_runtime::spawn(async move {
-        start_enhanced_background_service(app_handle).await;
-      });
+      // Start the reminder scheduler that drives all background notifications
+      let scheduler = ReminderScheduler::default();
+      scheduler.start(handle.clone());
+      app.manage(scheduler);
 
       Ok(())
     })
@@ -131,6 +248,38 @@ pub fn run() {
       record_habit_completion_backend,
       is_background_service_running,
       get_background_service_status,
+      start_reminder_scheduler,
+      stop_reminder_scheduler,
+      restart_reminder_scheduler,
+      list_habit_reminders,
+      create_habit_reminder,
+      update_habit_reminder,
+      delete_habit_reminder,
+      sync_habit_streaks,
+      sync_progress,
+      generate_weekly_review,
+      handle_notification_action,
+      get_notification_history,
+      take_storage_warnings,
+      load_app_data,
+      save_app_data,
+      patch_app_data,
+      import_app_data,
+      export_app_data,
+      query_completions,
+      get_xp_ledger,
+      get_event_log,
+      revert_app_data,
+      list_backups,
+      restore_backup,
+      delete_backup,
+      get_encryption_status,
+      enable_encryption,
+      unlock_app_data,
+      lock_app_data,
+      change_encryption_passphrase,
+      disable_encryption,
+      sync_app_settings,
       show_main_window,
       init_notifications_and_send_test,
       minimize_to_tray,
@@ -140,57 +289,6 @@ pub fn run() {
     .expect("error while running tauri application");
 }
 
-// ================================================================================================
-// ENHANCED BACKGROUND SERVICE
-// ================================================================================================
-
-async fn start_enhanced_background_service(app_handle: tauri::AppHandle) {
-  use std::time::Duration;
-  
-  log::info!("Starting enhanced background notification service...");
-  
-  loop {
-    // Check every hour for activity-based notifications
-    tokio::time::sleep(Duration::from_secs(3600)).await;
-    
-    // Send notification only after 20 hours of inactivity to preserve daily streaks
-    if should_send_streak_protection_reminder(&app_handle).await {
-      let _ = app_handle.notification().builder()
-        .title("🎯 HabitQuest - Daily Streak Protection")
-        .body("It's been 20 hours since your last activity! Don't lose your streak - check in now! 🔥")
-        .show();
-      
-      log::info!("20-hour inactivity streak protection notification sent");
-    }
-  }
-}
-
-async fn should_send_streak_protection_reminder(app_handle: &tauri::AppHandle) -> bool {
-  use chrono::Local;
-  
-  // Check if 20 hours have passed since last activity
-  let now = Local::now();
-  let hour = now.hour();
-  
-  // Only send during reasonable hours (8 AM to 10 PM) to avoid night notifications
-  if hour < 8 || hour > 22 {
-    return false;
-  }
-  
-  // TODO: In production, implement actual activity tracking
-  // This would check when the user last interacted with habits in your app
-  // For now, we'll check once per day maximum during afternoon hours
-  
-  // Send streak protection reminder once per day around 6 PM (18:00)
-  // In a real implementation, you'd track actual user activity via app_handle state
-  if hour == 18 && now.minute() < 5 {
-    log::info!("Checking for 20-hour inactivity period via app handle: {}", app_handle.package_info().name);
-    true
-  } else {
-    false
-  }
-}
-
 // ================================================================================================
 // NOTIFICATION COMMANDS
 // ================================================================================================
@@ -208,15 +306,19 @@ fn show_main_window(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 async fn init_notifications_and_send_test(app_handle: tauri::AppHandle) -> Result<bool, String> {
-  use tauri_plugin_notification::NotificationExt;
-  
   log::info!("Initializing notifications and sending test notification");
   
   // Send the initial notification that will register the app in Windows
-  match app_handle.notification().builder()
-    .title("🎯 HabitQuest")
-    .body("Notifications enabled! You'll now receive habit reminders. ✅")
-    .show() {
+  // The user just asked for it, so it is shown even during quiet hours
+  let notification = OutgoingNotification::new(
+    NotificationKind::Test,
+    "Notifications enabled from settings",
+    localized(&app_handle, "test.title", &[]),
+    localized(&app_handle, "test.body", &[]),
+  )
+  .ignoring_quiet_hours();
+  
+  match dispatch_notification(&app_handle, &notification) {
     Ok(_) => {
       log::info!("Initial notification sent successfully - app should now appear in Windows notification settings");
       Ok(true)
@@ -248,22 +350,22 @@ fn tauri_request_notification_permission() -> bool {
 }
 
 #[tauri::command]
-fn tauri_send_notification(app_handle: tauri::AppHandle, title: String, body: String, icon: Option<String>) -> Result<(), String> {
-  use tauri_plugin_notification::NotificationExt;
-  
+fn tauri_send_notification(app_handle: tauri::AppHandle, title: String, body: String, icon: Option<String>, habit_id: Option<String>, budget: Option<BudgetCategory>) -> Result<(), String> {
   log::info!("Attempting to send notification: {} - {}", title, body);
   
-  let mut builder = app_handle.notification().builder()
-    .title(&title)
-    .body(&body);
-    
-  if let Some(icon_name) = icon {
-    builder = builder.icon(&icon_name);
+  // Keep the content as a reminder so the notification's actions can snooze or complete it
+  let reminder = Reminder::Custom { title: title.clone(), body: body.clone(), habit_id };
+  let mut notification = OutgoingNotification::new(NotificationKind::Custom, reminder.trigger_reason(), title, body)
+    .with_reminder(reminder)
+    .with_budget(budget);
+  if let Some(icon) = icon {
+    notification = notification.with_icon(icon);
   }
   
-  match builder.show() {
-    Ok(_) => {
-      log::info!("Notification sent successfully");
+  // A suppressed notification still reports success so the frontend doesn't fall back to a web notification
+  match dispatch_notification(&app_handle, &notification) {
+    Ok(result) => {
+      log::info!("Notification dispatched: {:?}", result);
       Ok(())
     },
     Err(e) => {
@@ -275,17 +377,17 @@ fn tauri_send_notification(app_handle: tauri::AppHandle, title: String, body: St
 }
 
 #[tauri::command]
-fn is_background_service_running() -> bool {
-  // For now, we'll assume the service is running if the app is running
-  // TODO: Add proper background service tracking
-  true
+fn is_background_service_running(app_handle: tauri::AppHandle) -> bool {
+  app_handle.state::<ReminderScheduler>().is_running()
 }
 
 #[tauri::command]
-fn get_background_service_status() -> String {
-  // Return status of background notification service
-  // TODO: Add proper status checking
-  "running".to_string()
+fn get_background_service_status(app_handle: tauri::AppHandle) -> String {
+  if app_handle.state::<ReminderScheduler>().is_running() {
+    "running".to_string()
+  } else {
+    "stopped".to_string()
+  }
 }
 
 // New function to minimize to tray (optional behavior)
@@ -0,0 +1,265 @@
+/*!
+ * ================================================================================================
+ * MESSAGE CATALOG
+ * ================================================================================================
+ *
+ * User-facing notification, action button and tray strings, per locale. Templates use `{name}`
+ * placeholders such as `{streak}`, `{habit}` and `{hours_inactive}`. The locale comes from
+ * `AppSettings.language`; missing locales or keys fall back to English.
+ *
+ * @version 1.0.0
+ */
+
+pub const FALLBACK_LOCALE: &str = "en";
+
+/// Locales with a catalog, matched against the primary subtag of `AppSettings.language`
+pub const SUPPORTED_LOCALES: [&str; 4] = ["en", "es", "fr", "de"];
+
+/// Map a language tag like "es-MX" or "fr_CA" to a supported locale
+pub fn resolve_locale(language: &str) -> &'static str {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    SUPPORTED_LOCALES
+        .iter()
+        .copied()
+        .find(|locale| *locale == primary)
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+fn english(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "streak_protection.title" => "🎯 HabitQuest - Daily Streak Protection",
+        "streak_protection.body" => "It's been {hours_inactive} hours since your last activity! Don't lose your streak - check in now! 🔥",
+        "habit_reminder.title" => "⏰ HabitQuest - Habit Reminder",
+        "habit_reminder.body" => "Time for \"{habit}\"! Keep your streak going. ✅",
+        "habit_reminder.body_unnamed" => "Time for one of your habits! Keep your streak going. ✅",
+        "streak_at_risk.title" => "🔥 HabitQuest - Streak at Risk",
+        "streak_at_risk.title_final" => "🚨 HabitQuest - Last Chance for Your Streak",
+        "streak_at_risk.body" => "Your {streak}-{unit} \"{habit}\" streak ends in {hours_left} hours!",
+        "streak_at_risk.body_days" => "Your {streak}-{unit} \"{habit}\" streak ends in {days_left} days. Don't let it slip!",
+        "streak_at_risk.body_soon" => "Your {streak}-{unit} \"{habit}\" streak ends in less than an hour - complete it now!",
+        "streak_at_risk.unit.daily" => "day",
+        "streak_at_risk.unit.weekly" => "week",
+        "streak_at_risk.unit.monthly" => "month",
+        "streak_at_risk.unit.yearly" => "year",
+        "weekly_review.title" => "📊 HabitQuest - Your Weekly Review",
+        "weekly_review.body" => "Your review of the week of {week_start} is ready: {file} in the HabitQuest data folder.",
+        "catch_up.title" => "⏰ HabitQuest - While You Were Away",
+        "catch_up.body" => "You missed {count} reminders. Open HabitQuest to catch up on your habits!",
+        "digest.morning_title" => "☀️ HabitQuest - Good Morning",
+        "digest.morning_body" => "{count} habits today, worth {xp} XP: {habits}",
+        "digest.evening_title" => "🌙 HabitQuest - Today's Recap",
+        "digest.evening_progress" => "{completed} of {total} habits done, {xp} XP earned",
+        "digest.evening_extended" => "🔥 Streaks extended: {streaks}",
+        "digest.evening_at_risk" => "⚠️ Streaks ending tonight: {streaks}",
+        "digest.evening_remaining" => "Still open: {habits}",
+        "random.0.title" => "🌟 HabitQuest Check-in",
+        "random.0.body" => "A quick check-in keeps your quest on track. How are your habits going today?",
+        "random.1.title" => "⚡ Time for a Habit Break",
+        "random.1.body" => "Got a minute? Knock out a habit and grab some XP!",
+        "random.2.title" => "🎮 Your Quest Awaits",
+        "random.2.body" => "Your habits are waiting for you. Every check-in counts!",
+        "startup.summary_title" => "🎯 HabitQuest - Today's Quests",
+        "startup.summary_due" => "{count} habits due: {habits}",
+        "startup.summary_streaks" => "🔥 Streaks at stake: {streaks}",
+        "list.more" => "+{count} more",
+        "startup.summary_all_done" => "Everything is done for now - nice work! 🎉",
+        "test.title" => "🎯 HabitQuest",
+        "test.body" => "Notifications enabled! You'll now receive habit reminders. ✅",
+        "tray.tooltip" => "HabitQuest - Habit Tracker (Click to restore)",
+        "action.mark_done" => "Mark done",
+        "action.snooze" => "Snooze 1h",
+        "action.open" => "Open",
+        _ => return None,
+    })
+}
+
+fn spanish(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "streak_protection.title" => "🎯 HabitQuest - Protege tu racha diaria",
+        "streak_protection.body" => "¡Han pasado {hours_inactive} horas desde tu última actividad! No pierdas tu racha: ¡registra tu progreso ahora! 🔥",
+        "habit_reminder.title" => "⏰ HabitQuest - Recordatorio de hábito",
+        "habit_reminder.body" => "¡Es hora de \"{habit}\"! Mantén viva tu racha. ✅",
+        "habit_reminder.body_unnamed" => "¡Es hora de uno de tus hábitos! Mantén viva tu racha. ✅",
+        "streak_at_risk.title" => "🔥 HabitQuest - Racha en peligro",
+        "streak_at_risk.title_final" => "🚨 HabitQuest - Última oportunidad para tu racha",
+        "streak_at_risk.body" => "¡Tu racha de {streak} {unit} de \"{habit}\" termina en {hours_left} horas!",
+        "streak_at_risk.body_days" => "Tu racha de {streak} {unit} de \"{habit}\" termina en {days_left} días. ¡No la dejes escapar!",
+        "streak_at_risk.body_soon" => "¡Tu racha de {streak} {unit} de \"{habit}\" termina en menos de una hora: complétala ahora!",
+        "streak_at_risk.unit.daily" => "días",
+        "streak_at_risk.unit.weekly" => "semanas",
+        "streak_at_risk.unit.monthly" => "meses",
+        "streak_at_risk.unit.yearly" => "años",
+        "weekly_review.title" => "📊 HabitQuest - Tu resumen semanal",
+        "weekly_review.body" => "Tu resumen de la semana del {week_start} está listo: {file} en la carpeta de datos de HabitQuest.",
+        "catch_up.title" => "⏰ HabitQuest - Mientras no estabas",
+        "catch_up.body" => "Te perdiste {count} recordatorios. ¡Abre HabitQuest para ponerte al día con tus hábitos!",
+        "digest.morning_title" => "☀️ HabitQuest - Buenos días",
+        "digest.morning_body" => "{count} hábitos hoy, con {xp} XP en juego: {habits}",
+        "digest.evening_title" => "🌙 HabitQuest - Resumen del día",
+        "digest.evening_progress" => "{completed} de {total} hábitos completados, {xp} XP ganados",
+        "digest.evening_extended" => "🔥 Rachas ampliadas: {streaks}",
+        "digest.evening_at_risk" => "⚠️ Rachas que terminan esta noche: {streaks}",
+        "digest.evening_remaining" => "Pendientes: {habits}",
+        "random.0.title" => "🌟 Momento de HabitQuest",
+        "random.0.body" => "Un repaso rápido mantiene tu aventura en marcha. ¿Cómo van tus hábitos hoy?",
+        "random.1.title" => "⚡ Pausa para un hábito",
+        "random.1.body" => "¿Tienes un minuto? ¡Completa un hábito y gana XP!",
+        "random.2.title" => "🎮 Tu aventura te espera",
+        "random.2.body" => "Tus hábitos te están esperando. ¡Cada registro cuenta!",
+        "startup.summary_title" => "🎯 HabitQuest - Misiones de hoy",
+        "startup.summary_due" => "{count} hábitos pendientes: {habits}",
+        "startup.summary_streaks" => "🔥 Rachas en juego: {streaks}",
+        "list.more" => "+{count} más",
+        "startup.summary_all_done" => "¡Todo hecho por ahora, buen trabajo! 🎉",
+        "test.title" => "🎯 HabitQuest",
+        "test.body" => "¡Notificaciones activadas! Ahora recibirás recordatorios de tus hábitos. ✅",
+        "tray.tooltip" => "HabitQuest - Registro de hábitos (haz clic para restaurar)",
+        "action.mark_done" => "Marcar como hecho",
+        "action.snooze" => "Posponer 1 h",
+        "action.open" => "Abrir",
+        _ => return None,
+    })
+}
+
+fn french(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "streak_protection.title" => "🎯 HabitQuest - Protection de la série",
+        "streak_protection.body" => "Cela fait {hours_inactive} heures depuis ta dernière activité ! Ne perds pas ta série, fais le point maintenant ! 🔥",
+        "habit_reminder.title" => "⏰ HabitQuest - Rappel d'habitude",
+        "habit_reminder.body" => "C'est l'heure de « {habit} » ! Garde ta série en vie. ✅",
+        "habit_reminder.body_unnamed" => "C'est l'heure d'une de tes habitudes ! Garde ta série en vie. ✅",
+        "streak_at_risk.title" => "🔥 HabitQuest - Série en danger",
+        "streak_at_risk.title_final" => "🚨 HabitQuest - Dernière chance pour ta série",
+        "streak_at_risk.body" => "Ta série de {streak} {unit} pour « {habit} » se termine dans {hours_left} heures !",
+        "streak_at_risk.body_days" => "Ta série de {streak} {unit} pour « {habit} » se termine dans {days_left} jours. Ne la laisse pas filer !",
+        "streak_at_risk.body_soon" => "Ta série de {streak} {unit} pour « {habit} » se termine dans moins d'une heure, valide-la maintenant !",
+        "streak_at_risk.unit.daily" => "jours",
+        "streak_at_risk.unit.weekly" => "semaines",
+        "streak_at_risk.unit.monthly" => "mois",
+        "streak_at_risk.unit.yearly" => "ans",
+        "weekly_review.title" => "📊 HabitQuest - Ton bilan de la semaine",
+        "weekly_review.body" => "Ton bilan de la semaine du {week_start} est prêt : {file} dans le dossier de données de HabitQuest.",
+        "catch_up.title" => "⏰ HabitQuest - Pendant ton absence",
+        "catch_up.body" => "Tu as manqué {count} rappels. Ouvre HabitQuest pour rattraper tes habitudes !",
+        "digest.morning_title" => "☀️ HabitQuest - Bonjour",
+        "digest.morning_body" => "{count} habitudes aujourd'hui, pour {xp} XP : {habits}",
+        "digest.evening_title" => "🌙 HabitQuest - Bilan du jour",
+        "digest.evening_progress" => "{completed} habitudes sur {total} faites, {xp} XP gagnés",
+        "digest.evening_extended" => "🔥 Séries prolongées : {streaks}",
+        "digest.evening_at_risk" => "⚠️ Séries qui se terminent ce soir : {streaks}",
+        "digest.evening_remaining" => "Encore à faire : {habits}",
+        "random.0.title" => "🌟 Petit point HabitQuest",
+        "random.0.body" => "Un petit point garde ta quête sur la bonne voie. Où en sont tes habitudes aujourd'hui ?",
+        "random.1.title" => "⚡ Pause habitude",
+        "random.1.body" => "Tu as une minute ? Valide une habitude et gagne de l'XP !",
+        "random.2.title" => "🎮 Ta quête t'attend",
+        "random.2.body" => "Tes habitudes t'attendent. Chaque point compte !",
+        "startup.summary_title" => "🎯 HabitQuest - Quêtes du jour",
+        "startup.summary_due" => "{count} habitudes à faire : {habits}",
+        "startup.summary_streaks" => "🔥 Séries en jeu : {streaks}",
+        "list.more" => "+{count} autres",
+        "startup.summary_all_done" => "Tout est fait pour le moment, bravo ! 🎉",
+        "test.title" => "🎯 HabitQuest",
+        "test.body" => "Notifications activées ! Tu recevras désormais des rappels d'habitudes. ✅",
+        "tray.tooltip" => "HabitQuest - Suivi d'habitudes (cliquer pour restaurer)",
+        "action.mark_done" => "Marquer comme fait",
+        "action.snooze" => "Rappeler dans 1 h",
+        "action.open" => "Ouvrir",
+        _ => return None,
+    })
+}
+
+fn german(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "streak_protection.title" => "🎯 HabitQuest - Serienschutz",
+        "streak_protection.body" => "Seit deiner letzten Aktivität sind {hours_inactive} Stunden vergangen! Verliere deine Serie nicht - schau jetzt rein! 🔥",
+        "habit_reminder.title" => "⏰ HabitQuest - Gewohnheitserinnerung",
+        "habit_reminder.body" => "Zeit für „{habit}“! Halte deine Serie am Leben. ✅",
+        "habit_reminder.body_unnamed" => "Zeit für eine deiner Gewohnheiten! Halte deine Serie am Leben. ✅",
+        "streak_at_risk.title" => "🔥 HabitQuest - Serie in Gefahr",
+        "streak_at_risk.title_final" => "🚨 HabitQuest - Letzte Chance für deine Serie",
+        "streak_at_risk.body" => "Deine {streak}-{unit}-Serie „{habit}“ endet in {hours_left} Stunden!",
+        "streak_at_risk.body_days" => "Deine {streak}-{unit}-Serie „{habit}“ endet in {days_left} Tagen. Lass sie nicht abreißen!",
+        "streak_at_risk.body_soon" => "Deine {streak}-{unit}-Serie „{habit}“ endet in weniger als einer Stunde - erledige sie jetzt!",
+        "streak_at_risk.unit.daily" => "Tage",
+        "streak_at_risk.unit.weekly" => "Wochen",
+        "streak_at_risk.unit.monthly" => "Monate",
+        "streak_at_risk.unit.yearly" => "Jahre",
+        "weekly_review.title" => "📊 HabitQuest - Dein Wochenrückblick",
+        "weekly_review.body" => "Dein Rückblick auf die Woche vom {week_start} ist fertig: {file} im HabitQuest-Datenordner.",
+        "catch_up.title" => "⏰ HabitQuest - Während du weg warst",
+        "catch_up.body" => "Du hast {count} Erinnerungen verpasst. Öffne HabitQuest, um deine Gewohnheiten nachzuholen!",
+        "digest.morning_title" => "☀️ HabitQuest - Guten Morgen",
+        "digest.morning_body" => "{count} Gewohnheiten heute, {xp} XP zu holen: {habits}",
+        "digest.evening_title" => "🌙 HabitQuest - Tagesrückblick",
+        "digest.evening_progress" => "{completed} von {total} Gewohnheiten erledigt, {xp} XP verdient",
+        "digest.evening_extended" => "🔥 Verlängerte Serien: {streaks}",
+        "digest.evening_at_risk" => "⚠️ Serien, die heute Nacht enden: {streaks}",
+        "digest.evening_remaining" => "Noch offen: {habits}",
+        "random.0.title" => "🌟 HabitQuest Check-in",
+        "random.0.body" => "Ein kurzer Check-in hält deine Quest auf Kurs. Wie laufen deine Gewohnheiten heute?",
+        "random.1.title" => "⚡ Zeit für eine Gewohnheit",
+        "random.1.body" => "Hast du eine Minute? Erledige eine Gewohnheit und sammle XP!",
+        "random.2.title" => "🎮 Deine Quest wartet",
+        "random.2.body" => "Deine Gewohnheiten warten auf dich. Jeder Check-in zählt!",
+        "startup.summary_title" => "🎯 HabitQuest - Heutige Quests",
+        "startup.summary_due" => "{count} Gewohnheiten fällig: {habits}",
+        "startup.summary_streaks" => "🔥 Serien in Gefahr: {streaks}",
+        "list.more" => "+{count} weitere",
+        "startup.summary_all_done" => "Alles erledigt für den Moment - gut gemacht! 🎉",
+        "test.title" => "🎯 HabitQuest",
+        "test.body" => "Benachrichtigungen aktiviert! Du erhältst jetzt Gewohnheitserinnerungen. ✅",
+        "tray.tooltip" => "HabitQuest - Gewohnheitstracker (zum Wiederherstellen klicken)",
+        "action.mark_done" => "Erledigt",
+        "action.snooze" => "In 1 Std. erinnern",
+        "action.open" => "Öffnen",
+        _ => return None,
+    })
+}
+
+/// Raw template for `key` in `locale`, falling back to English and finally to the app name
+pub fn template(locale: &str, key: &str) -> &'static str {
+    let localized = match resolve_locale(locale) {
+        "es" => spanish(key),
+        "fr" => french(key),
+        "de" => german(key),
+        _ => None,
+    };
+
+    localized.or_else(|| english(key)).unwrap_or_else(|| {
+        log::warn!("Missing message catalog entry: {}", key);
+        "HabitQuest"
+    })
+}
+
+/// Replace `{name}` placeholders with the matching parameter. Unknown placeholders are kept.
+pub fn render(template: &str, params: &[(&str, &str)]) -> String {
+    let mut rendered = template.to_string();
+    for (name, value) in params {
+        rendered = rendered.replace(&format!("{{{}}}", name), value);
+    }
+    rendered
+}
+
+/// Localized message for `key` with its placeholders filled in
+pub fn message(locale: &str, key: &str, params: &[(&str, &str)]) -> String {
+    render(template(locale, key), params)
+}
+
+/// Items named in a notification before the rest are counted as "+N more"
+const MAX_LISTED_ITEMS: usize = 3;
+
+/// The first few `items` joined with commas, with the rest counted
+pub fn list(locale: &str, mut items: Vec<String>) -> String {
+    let hidden = items.len().saturating_sub(MAX_LISTED_ITEMS);
+    items.truncate(MAX_LISTED_ITEMS);
+    if hidden > 0 {
+        items.push(message(locale, "list.more", &[("count", &hidden.to_string())]));
+    }
+    items.join(", ")
+}
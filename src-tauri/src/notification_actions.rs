@@ -0,0 +1,278 @@
+/*!
+ * ================================================================================================
+ * NOTIFICATION ACTIONS
+ * ================================================================================================
+ *
+ * "Mark done", "Snooze 1h" and "Open" buttons on reminder notifications. Routing an action is
+ * done by `handle_action` against the `NotificationActionHandler` trait, so the policy can be
+ * exercised without a desktop notification daemon. On Linux/BSD the buttons are shown through
+ * notify-rust, which is listened to until the notification closes or `ACTION_TIMEOUT` passes.
+ * Elsewhere the desktop plugin has no action support, so the webview is sent the buttons as
+ * `ACTION_PROMPT_EVENT` and shows them in the app, forwarding clicks through
+ * `handle_notification_action`.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_settings::current_locale;
+use crate::background_notifications::{BackgroundNotificationService, Reminder};
+use crate::clock::app_now;
+use crate::messages;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// How far "Snooze" pushes a reminder back
+pub const SNOOZE_MINUTES: i64 = 60;
+
+/// How long a notification's buttons are listened for before it's closed
+pub const ACTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// Event sending the webview an `ActionPrompt` where notifications can't show buttons
+pub const ACTION_PROMPT_EVENT: &str = "notification-action-prompt";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationAction {
+    MarkDone,
+    Snooze,
+    Open,
+}
+
+impl NotificationAction {
+    pub fn id(&self) -> &'static str {
+        match self {
+            NotificationAction::MarkDone => "mark_done",
+            NotificationAction::Snooze => "snooze",
+            NotificationAction::Open => "open",
+        }
+    }
+
+    /// Button text in `locale`
+    pub fn label(&self, locale: &str) -> String {
+        messages::message(locale, &format!("action.{}", self.id()), &[])
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "mark_done" => Some(NotificationAction::MarkDone),
+            "snooze" => Some(NotificationAction::Snooze),
+            "open" => Some(NotificationAction::Open),
+            _ => None,
+        }
+    }
+
+    /// Buttons offered on a reminder. "Mark done" only makes sense when it targets a habit.
+    pub fn for_reminder(reminder: &Reminder) -> Vec<Self> {
+        let mut actions = Vec::new();
+        if reminder.habit_id().is_some() {
+            actions.push(NotificationAction::MarkDone);
+        }
+        actions.push(NotificationAction::Snooze);
+        actions.push(NotificationAction::Open);
+        actions
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ActionButton {
+    pub action: NotificationAction,
+    pub label: String,
+}
+
+/// A reminder's notification and its buttons, for the webview to offer
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ActionPrompt {
+    pub title: String,
+    pub body: String,
+    pub reminder: Reminder,
+    pub actions: Vec<ActionButton>,
+}
+
+impl ActionPrompt {
+    pub fn new(title: &str, body: &str, reminder: &Reminder, locale: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            body: body.to_string(),
+            reminder: reminder.clone(),
+            actions: NotificationAction::for_reminder(reminder)
+                .into_iter()
+                .map(|action| ActionButton { action, label: action.label(locale) })
+                .collect(),
+        }
+    }
+}
+
+/// Side effects an action can have. Implemented against the running app by `AppActionHandler`.
+pub trait NotificationActionHandler {
+    fn complete_habit(&self, habit_id: &str) -> Result<(), String>;
+    fn snooze(&self, reminder: Reminder, until: DateTime<Local>) -> Result<(), String>;
+    fn open_main_window(&self) -> Result<(), String>;
+}
+
+/// Apply `action` taken on the notification for `reminder`
+pub fn handle_action(
+    handler: &impl NotificationActionHandler,
+    action: NotificationAction,
+    reminder: &Reminder,
+    now: DateTime<Local>,
+) -> Result<(), String> {
+    match action {
+        NotificationAction::MarkDone => match reminder.habit_id() {
+            Some(habit_id) => handler.complete_habit(habit_id),
+            None => Err("This notification is not tied to a habit".to_string()),
+        },
+        NotificationAction::Snooze => handler.snooze(reminder.clone(), now + Duration::minutes(SNOOZE_MINUTES)),
+        NotificationAction::Open => handler.open_main_window(),
+    }
+}
+
+pub struct AppActionHandler {
+    app_handle: AppHandle,
+}
+
+impl AppActionHandler {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+
+    fn service(&self) -> Result<tauri::State<'_, BackgroundNotificationService>, String> {
+        self.app_handle
+            .try_state::<BackgroundNotificationService>()
+            .ok_or_else(|| "Background notification service not initialized".to_string())
+    }
+}
+
+impl NotificationActionHandler for AppActionHandler {
+    fn complete_habit(&self, habit_id: &str) -> Result<(), String> {
+        self.service()?.record_habit_completion(habit_id)?;
+        log::info!("Habit {} marked done from notification", habit_id);
+        Ok(())
+    }
+
+    fn snooze(&self, reminder: Reminder, until: DateTime<Local>) -> Result<(), String> {
+        self.service()?.snooze_reminder(reminder, until);
+        log::info!("Reminder snoozed until {}", until.format("%H:%M"));
+        Ok(())
+    }
+
+    fn open_main_window(&self) -> Result<(), String> {
+        let window = self
+            .app_handle
+            .get_webview_window("main")
+            .ok_or_else(|| "Main window not found".to_string())?;
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// Show a notification for `reminder` with its action buttons where the platform supports them
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn show_actionable_notification(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    icon: Option<&str>,
+    reminder: &Reminder,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(title).body(body);
+    match icon {
+        Some(icon) => notification.icon(icon),
+        None => notification.auto_icon(),
+    };
+    let locale = current_locale(app_handle);
+    for action in NotificationAction::for_reminder(reminder) {
+        notification.action(action.id(), &action.label(locale));
+    }
+    let timeout_ms = u32::try_from(ACTION_TIMEOUT.as_millis()).unwrap_or(u32::MAX);
+    notification.timeout(notify_rust::Timeout::Milliseconds(timeout_ms));
+    let handle = notification.show()?;
+
+    // Servers may keep the notification past its timeout, so it's closed then; that ends the wait
+    let id = handle.id();
+    let answered = Arc::new(AtomicBool::new(false));
+    tauri::async_runtime::spawn({
+        let answered = answered.clone();
+        async move {
+            tokio::time::sleep(ACTION_TIMEOUT).await;
+            if !answered.load(Ordering::SeqCst) {
+                tauri::async_runtime::spawn_blocking(move || close_notification(notification, id));
+            }
+        }
+    });
+
+    let app_handle = app_handle.clone();
+    let reminder = reminder.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        handle.wait_for_action(|action_id| {
+            answered.store(true, Ordering::SeqCst);
+            // "__closed" and the default click carry no button id
+            let Some(action) = NotificationAction::from_id(action_id) else {
+                return;
+            };
+            let handler = AppActionHandler::new(app_handle.clone());
+            if let Err(e) = handle_action(&handler, action, &reminder, app_now(&app_handle)) {
+                log::error!("Failed to handle notification action {}: {}", action_id, e);
+            }
+        });
+    });
+
+    Ok(())
+}
+
+/// Close the notification shown as `id`, through a handle to the same notification replacing it
+#[cfg(all(unix, not(target_os = "macos")))]
+fn close_notification(mut notification: notify_rust::Notification, id: u32) {
+    match notification.id(id).show() {
+        Ok(handle) => handle.close(),
+        Err(e) => log::warn!("Could not close notification {}: {}", id, e),
+    }
+}
+
+/// Show a notification for `reminder` with its action buttons where the platform supports them
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn show_actionable_notification(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    icon: Option<&str>,
+    reminder: &Reminder,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri::Emitter;
+    use tauri_plugin_notification::NotificationExt;
+
+    let mut builder = app_handle.notification().builder().title(title).body(body);
+    if let Some(icon) = icon {
+        builder = builder.icon(icon);
+    }
+    builder.show()?;
+
+    // The plugin's notifications have no buttons, so the app offers them instead
+    let prompt = ActionPrompt::new(title, body, reminder, current_locale(app_handle));
+    if let Err(e) = app_handle.emit(ACTION_PROMPT_EVENT, &prompt) {
+        log::warn!("Could not send the notification's actions to the webview: {}", e);
+    }
+
+    Ok(())
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn handle_notification_action(
+    app_handle: AppHandle,
+    action: NotificationAction,
+    reminder: Reminder,
+) -> Result<(), String> {
+    let now = app_now(&app_handle);
+    let handler = AppActionHandler::new(app_handle);
+    handle_action(&handler, action, &reminder, now)
+}
@@ -0,0 +1,108 @@
+/*!
+ * ================================================================================================
+ * NOTIFICATION BUDGETS
+ * ================================================================================================
+ *
+ * Per-category limits on how many notifications may be shown in any rolling 24-hour window.
+ * Each category keeps the send times that are still inside the window, so the budget frees up
+ * gradually instead of all at once at midnight, and survives restarts as part of
+ * `ActivityData`.
+ *
+ * @version 1.0.0
+ */
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Length of the rolling window budgets are counted over
+pub const BUDGET_WINDOW_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetCategory {
+    StreakProtection,
+    /// Random check-ins
+    Random,
+    HabitSpecific,
+    Digest,
+    Achievement,
+}
+
+/// Maximum notifications per category in any rolling window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BudgetLimits {
+    pub streak_protection: u32,
+    pub random: u32,
+    pub habit_specific: u32,
+    pub digest: u32,
+    pub achievement: u32,
+}
+
+impl Default for BudgetLimits {
+    fn default() -> Self {
+        Self::from_daily_cap(2)
+    }
+}
+
+impl BudgetLimits {
+    /// Limits for configs that only set the old shared `max_reminders_per_day` cap. The cap
+    /// applies to the reminder kinds it used to cover; the rest get their own defaults.
+    pub fn from_daily_cap(max_reminders_per_day: u32) -> Self {
+        Self {
+            streak_protection: max_reminders_per_day,
+            random: max_reminders_per_day,
+            habit_specific: 10,
+            digest: 2,
+            achievement: 5,
+        }
+    }
+
+    pub fn limit(&self, category: BudgetCategory) -> u32 {
+        match category {
+            BudgetCategory::StreakProtection => self.streak_protection,
+            BudgetCategory::Random => self.random,
+            BudgetCategory::HabitSpecific => self.habit_specific,
+            BudgetCategory::Digest => self.digest,
+            BudgetCategory::Achievement => self.achievement,
+        }
+    }
+}
+
+/// Send times per category within the rolling window
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotificationBudgets {
+    pub sent: BTreeMap<BudgetCategory, Vec<DateTime<Local>>>,
+}
+
+impl NotificationBudgets {
+    fn window_start(now: DateTime<Local>) -> DateTime<Local> {
+        now - Duration::hours(BUDGET_WINDOW_HOURS)
+    }
+
+    /// Notifications of `category` sent in the window ending at `now`
+    pub fn sent_in_window(&self, category: BudgetCategory, now: DateTime<Local>) -> u32 {
+        let start = Self::window_start(now);
+        self.sent
+            .get(&category)
+            .map_or(0, |times| times.iter().filter(|sent| **sent > start && **sent <= now).count() as u32)
+    }
+
+    pub fn remaining(&self, category: BudgetCategory, limits: &BudgetLimits, now: DateTime<Local>) -> u32 {
+        limits.limit(category).saturating_sub(self.sent_in_window(category, now))
+    }
+
+    pub fn has_room(&self, category: BudgetCategory, limits: &BudgetLimits, now: DateTime<Local>) -> bool {
+        self.remaining(category, limits, now) > 0
+    }
+
+    /// Count a notification of `category` sent at `now`, dropping sends that left the window
+    pub fn record(&mut self, category: BudgetCategory, now: DateTime<Local>) {
+        let start = Self::window_start(now);
+        for times in self.sent.values_mut() {
+            times.retain(|sent| *sent > start);
+        }
+        self.sent.entry(category).or_default().push(now);
+        self.sent.retain(|_, times| !times.is_empty());
+    }
+}
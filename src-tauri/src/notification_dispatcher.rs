@@ -0,0 +1,129 @@
+/*!
+ * ================================================================================================
+ * NOTIFICATION DISPATCHER
+ * ================================================================================================
+ *
+ * The single path every notification takes: quiet hours, then its category budget, then the
+ * sink, with the outcome logged to the notification history. The checks go through the
+ * `DispatchPolicy` trait so they can be exercised without a running app.
+ *
+ * @version 1.0.0
+ */
+
+use crate::background_notifications::BackgroundNotificationService;
+use crate::notification_budget::BudgetCategory;
+use crate::notification_history::{DeliveryResult, NotificationHistoryStore};
+use crate::notification_sink::{NotificationSink, OutgoingNotification};
+use crate::clock::app_now;
+use chrono::{DateTime, Local};
+use tauri::{AppHandle, Manager};
+
+/// Policy state the dispatcher consults and updates. Implemented against the running app by
+/// `AppDispatchPolicy`.
+pub trait DispatchPolicy {
+    fn is_quiet_time(&self, now: DateTime<Local>) -> bool;
+    fn budget_available(&self, category: BudgetCategory, now: DateTime<Local>) -> bool;
+    /// Count a delivered notification against its category's budget
+    fn consume_budget(&self, category: BudgetCategory, now: DateTime<Local>);
+    fn record(&self, notification: &OutgoingNotification, result: DeliveryResult, error: Option<String>);
+}
+
+/// Deliver `notification` through `sink` unless policy holds it back. Suppressed
+/// notifications are not errors; a failed delivery is returned as one after being logged.
+pub fn dispatch(
+    sink: &dyn NotificationSink,
+    policy: &impl DispatchPolicy,
+    notification: &OutgoingNotification,
+    now: DateTime<Local>,
+) -> Result<DeliveryResult, String> {
+    if !notification.ignores_quiet_hours && policy.is_quiet_time(now) {
+        log::info!("Notification \"{}\" suppressed during quiet hours", notification.title);
+        policy.record(notification, DeliveryResult::Suppressed, None);
+        return Ok(DeliveryResult::Suppressed);
+    }
+
+    if let Some(category) = notification.budget {
+        if !policy.budget_available(category, now) {
+            log::info!("Notification \"{}\" suppressed - {:?} budget used up", notification.title, category);
+            policy.record(notification, DeliveryResult::Suppressed, None);
+            return Ok(DeliveryResult::Suppressed);
+        }
+    }
+
+    match sink.deliver(notification) {
+        Ok(()) => {
+            if let Some(category) = notification.budget {
+                policy.consume_budget(category, now);
+            }
+            policy.record(notification, DeliveryResult::Delivered, None);
+            Ok(DeliveryResult::Delivered)
+        }
+        Err(e) => {
+            policy.record(notification, DeliveryResult::Failed, Some(e.clone()));
+            Err(e)
+        }
+    }
+}
+
+pub struct AppDispatchPolicy {
+    app_handle: AppHandle,
+}
+
+impl AppDispatchPolicy {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl DispatchPolicy for AppDispatchPolicy {
+    fn is_quiet_time(&self, now: DateTime<Local>) -> bool {
+        self.app_handle
+            .try_state::<BackgroundNotificationService>()
+            .is_some_and(|service| service.is_quiet_time(now))
+    }
+
+    fn budget_available(&self, category: BudgetCategory, now: DateTime<Local>) -> bool {
+        self.app_handle
+            .try_state::<BackgroundNotificationService>()
+            .map_or(true, |service| service.budget_available(category, now))
+    }
+
+    fn consume_budget(&self, category: BudgetCategory, now: DateTime<Local>) {
+        if let Some(service) = self.app_handle.try_state::<BackgroundNotificationService>() {
+            service.consume_budget(category, now);
+        }
+    }
+
+    fn record(&self, notification: &OutgoingNotification, result: DeliveryResult, error: Option<String>) {
+        if let Some(store) = self.app_handle.try_state::<NotificationHistoryStore>() {
+            store.record(
+                notification.kind,
+                notification.trigger.as_str(),
+                notification.title.as_str(),
+                result,
+                error,
+            );
+        }
+    }
+}
+
+/// Managed dispatcher holding the sink notifications are delivered to
+pub struct NotificationDispatcher {
+    sink: Box<dyn NotificationSink>,
+}
+
+impl NotificationDispatcher {
+    pub fn new(sink: impl NotificationSink + 'static) -> Self {
+        Self { sink: Box::new(sink) }
+    }
+}
+
+/// Send `notification` through the app's dispatcher
+pub fn dispatch_notification(app_handle: &AppHandle, notification: &OutgoingNotification) -> Result<DeliveryResult, String> {
+    let dispatcher = app_handle
+        .try_state::<NotificationDispatcher>()
+        .ok_or_else(|| "Notification dispatcher not initialized".to_string())?;
+
+    let policy = AppDispatchPolicy::new(app_handle.clone());
+    dispatch(dispatcher.sink.as_ref(), &policy, notification, app_now(app_handle))
+}
@@ -0,0 +1,259 @@
+/*!
+ * ================================================================================================
+ * NOTIFICATION HISTORY
+ * ================================================================================================
+ *
+ * Bounded, persisted log of every notification the app tried to show: what kind it was, why
+ * it was triggered and whether delivery worked. Answers "why did I get pinged at 6 PM?".
+ * Stored as `notification_history.json` next to the notification config, and copied to the
+ * app data database, which keeps entries past the file's limit.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_data::AppDataStore;
+use crate::background_notifications::Reminder;
+use crate::clock::app_now;
+use crate::persistence::{self, Schema};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Oldest entries are dropped beyond this
+pub const MAX_HISTORY_ENTRIES: usize = 500;
+
+/// Page size used when a query doesn't specify one
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    StreakProtection,
+    Random,
+    HabitSpecific,
+    StreakAtRisk,
+    MorningDigest,
+    EveningRecap,
+    WeeklyReview,
+    CatchUp,
+    /// Requested by the frontend through `tauri_send_notification`
+    Custom,
+    Startup,
+    Test,
+}
+
+impl From<&Reminder> for NotificationKind {
+    fn from(reminder: &Reminder) -> Self {
+        match reminder {
+            Reminder::StreakProtection { .. } => NotificationKind::StreakProtection,
+            Reminder::Random { .. } => NotificationKind::Random,
+            Reminder::HabitSpecific { .. } => NotificationKind::HabitSpecific,
+            Reminder::StreakAtRisk { .. } => NotificationKind::StreakAtRisk,
+            Reminder::MorningDigest { .. } => NotificationKind::MorningDigest,
+            Reminder::EveningRecap { .. } => NotificationKind::EveningRecap,
+            Reminder::WeeklyReview { .. } => NotificationKind::WeeklyReview,
+            Reminder::CatchUp { .. } => NotificationKind::CatchUp,
+            Reminder::Custom { .. } => NotificationKind::Custom,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryResult {
+    Delivered,
+    /// Deliberately not shown, e.g. during quiet hours
+    Suppressed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub sent_at: DateTime<Local>,
+    pub kind: NotificationKind,
+    pub trigger: String,
+    pub title: String,
+    pub result: DeliveryResult,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    pub kind: Option<NotificationKind>,
+    pub result: Option<DeliveryResult>,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    /// Newest first
+    pub entries: Vec<HistoryEntry>,
+    /// Number of entries matching the filters, before paging
+    pub total: usize,
+}
+
+/// Schema history of `notification_history.json`: v1 added the envelope
+pub const NOTIFICATION_HISTORY_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotificationHistory {
+    next_id: u64,
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl NotificationHistory {
+    pub fn record(
+        &mut self,
+        sent_at: DateTime<Local>,
+        kind: NotificationKind,
+        trigger: impl Into<String>,
+        title: impl Into<String>,
+        result: DeliveryResult,
+        error: Option<String>,
+    ) -> HistoryEntry {
+        let entry = HistoryEntry {
+            id: self.next_id,
+            sent_at,
+            kind,
+            trigger: trigger.into(),
+            title: title.into(),
+            result,
+            error,
+        };
+        self.entries.push_back(entry.clone());
+        self.next_id += 1;
+
+        while self.entries.len() > MAX_HISTORY_ENTRIES {
+            self.entries.pop_front();
+        }
+        entry
+    }
+
+    /// Oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    pub fn query(&self, query: &HistoryQuery) -> HistoryPage {
+        let matching: Vec<&HistoryEntry> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| query.kind.map_or(true, |kind| entry.kind == kind))
+            .filter(|entry| query.result.map_or(true, |result| entry.result == result))
+            .filter(|entry| query.since.map_or(true, |since| entry.sent_at >= since))
+            .filter(|entry| query.until.map_or(true, |until| entry.sent_at < until))
+            .collect();
+
+        HistoryPage {
+            total: matching.len(),
+            entries: matching
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit.unwrap_or(DEFAULT_PAGE_SIZE))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Managed state wrapping the history with its on-disk location
+pub struct NotificationHistoryStore {
+    history: Mutex<NotificationHistory>,
+    app_handle: AppHandle,
+}
+
+impl NotificationHistoryStore {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            history: Mutex::new(NotificationHistory::default()),
+            app_handle,
+        }
+    }
+
+    pub fn record(
+        &self,
+        kind: NotificationKind,
+        trigger: impl Into<String>,
+        title: impl Into<String>,
+        result: DeliveryResult,
+        error: Option<String>,
+    ) {
+        let mut history = self.history.lock().unwrap();
+        let entry = history.record(app_now(&self.app_handle), kind, trigger, title, result, error);
+
+        if let Err(e) = self.save_to_file(&history) {
+            log::warn!("Could not save notification history: {}", e);
+        }
+        drop(history);
+
+        // The database keeps every notification, past the JSON file's limit
+        if let Some(app_data) = self.app_handle.try_state::<AppDataStore>() {
+            app_data.record_notification(&entry);
+        }
+    }
+
+    pub fn query(&self, query: &HistoryQuery) -> HistoryPage {
+        self.history.lock().unwrap().query(query)
+    }
+
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.history.lock().unwrap().entries().cloned().collect()
+    }
+
+    /// Forget the loaded history without saving, such as when the data is locked
+    pub fn unload(&self) {
+        *self.history.lock().unwrap() = NotificationHistory::default();
+    }
+
+    fn save_to_file(&self, history: &NotificationHistory) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+
+        persistence::save_json(&config_dir.join("notification_history.json"), history, &NOTIFICATION_HISTORY_SCHEMA)
+    }
+
+    pub fn load_from_file(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = self.app_handle.path().app_config_dir()
+            .map_err(|e| format!("Could not resolve app config directory: {}", e))?;
+
+        let history_path = config_dir.join("notification_history.json");
+        if let Some(loaded) = persistence::load_json::<NotificationHistory>(&history_path, &NOTIFICATION_HISTORY_SCHEMA)? {
+            let history = persistence::report_recovery(&self.app_handle, loaded);
+            *self.history.lock().unwrap() = history;
+        }
+
+        Ok(())
+    }
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn get_notification_history(
+    app_handle: AppHandle,
+    query: Option<HistoryQuery>,
+) -> Result<HistoryPage, String> {
+    let query = query.unwrap_or_default();
+    if let Some(page) = app_handle
+        .try_state::<AppDataStore>()
+        .and_then(|app_data| app_data.with_storage(|storage| storage.notifications(&query)).ok())
+    {
+        return Ok(page);
+    }
+
+    if let Some(store) = app_handle.try_state::<NotificationHistoryStore>() {
+        Ok(store.query(&query))
+    } else {
+        Err("Notification history not initialized".to_string())
+    }
+}
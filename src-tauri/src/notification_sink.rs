@@ -0,0 +1,157 @@
+/*!
+ * ================================================================================================
+ * NOTIFICATION SINKS
+ * ================================================================================================
+ *
+ * Where notifications end up once the dispatcher has decided to show them. The desktop sink
+ * shows them through the OS (with action buttons where supported); the memory sink only
+ * records them, so reminder policy can be tested and other channels added later.
+ *
+ * @version 1.0.0
+ */
+
+use crate::background_notifications::Reminder;
+use crate::notification_actions::show_actionable_notification;
+use crate::notification_budget::BudgetCategory;
+use crate::notification_history::NotificationKind;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// Icon used for reminders raised by the backend
+const REMINDER_ICON: &str = "habitquest-icon";
+
+/// A notification on its way to a sink, with what the dispatcher needs to gate and log it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutgoingNotification {
+    pub kind: NotificationKind,
+    /// Why it is being sent, as recorded in the notification history
+    pub trigger: String,
+    pub title: String,
+    pub body: String,
+    pub icon: Option<String>,
+    /// Reminder behind the notification, which its action buttons act on
+    pub reminder: Option<Reminder>,
+    /// Budget the notification is charged to, if any
+    pub budget: Option<BudgetCategory>,
+    /// Shown even during quiet hours, for notifications the user asked for directly
+    pub ignores_quiet_hours: bool,
+}
+
+impl OutgoingNotification {
+    pub fn new(kind: NotificationKind, trigger: impl Into<String>, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            kind,
+            trigger: trigger.into(),
+            title: title.into(),
+            body: body.into(),
+            icon: None,
+            reminder: None,
+            budget: None,
+            ignores_quiet_hours: false,
+        }
+    }
+
+    /// Notification for a backend reminder, with its text in `locale`
+    pub fn for_reminder(reminder: &Reminder, locale: &str) -> Self {
+        let (title, body) = reminder.notification_text(locale);
+        Self::new(NotificationKind::from(reminder), reminder.trigger_reason(), title, body)
+            .with_icon(REMINDER_ICON)
+            .with_reminder(reminder.clone())
+    }
+
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    pub fn with_reminder(mut self, reminder: Reminder) -> Self {
+        self.reminder = Some(reminder);
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Option<BudgetCategory>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn ignoring_quiet_hours(mut self) -> Self {
+        self.ignores_quiet_hours = true;
+        self
+    }
+}
+
+/// A delivery channel for notifications
+pub trait NotificationSink: Send + Sync {
+    fn deliver(&self, notification: &OutgoingNotification) -> Result<(), String>;
+}
+
+/// Shows notifications on the desktop
+pub struct DesktopNotificationSink {
+    app_handle: AppHandle,
+}
+
+impl DesktopNotificationSink {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl NotificationSink for DesktopNotificationSink {
+    fn deliver(&self, notification: &OutgoingNotification) -> Result<(), String> {
+        use tauri_plugin_notification::NotificationExt;
+
+        if let Some(reminder) = &notification.reminder {
+            return show_actionable_notification(
+                &self.app_handle,
+                &notification.title,
+                &notification.body,
+                notification.icon.as_deref(),
+                reminder,
+            )
+            .map_err(|e| e.to_string());
+        }
+
+        let mut builder = self
+            .app_handle
+            .notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.body);
+        if let Some(icon) = &notification.icon {
+            builder = builder.icon(icon);
+        }
+        builder.show().map_err(|e| e.to_string())
+    }
+}
+
+/// Keeps delivered notifications in memory instead of showing them
+#[derive(Default)]
+pub struct MemoryNotificationSink {
+    delivered: Mutex<Vec<OutgoingNotification>>,
+    failure: Option<String>,
+}
+
+impl MemoryNotificationSink {
+    /// A sink whose deliveries all fail with `error`
+    pub fn failing(error: impl Into<String>) -> Self {
+        Self {
+            delivered: Mutex::new(Vec::new()),
+            failure: Some(error.into()),
+        }
+    }
+
+    pub fn delivered(&self) -> Vec<OutgoingNotification> {
+        self.delivered.lock().unwrap().clone()
+    }
+}
+
+impl NotificationSink for MemoryNotificationSink {
+    fn deliver(&self, notification: &OutgoingNotification) -> Result<(), String> {
+        if let Some(error) = &self.failure {
+            return Err(error.clone());
+        }
+        self.delivered.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
@@ -0,0 +1,311 @@
+/*!
+ * ================================================================================================
+ * PERSISTENCE
+ * ================================================================================================
+ *
+ * Crash-safe reads and writes for the backend's JSON files. A save goes to a temp file that is
+ * fsynced and renamed over the original, so a crash leaves either the old or the new contents,
+ * never a truncated file. The previous contents are kept as `<file>.bak` whenever they still
+ * parse, and a load falls back to that last-known-good copy when the primary is missing or
+ * corrupt, reporting the recovery so the frontend can warn the user.
+ *
+ * Every file is wrapped in `{ "schema_version": N, "data": ... }`. Files from before the
+ * envelope are version 0. Loading runs the file's chain of migrations from its version up to
+ * the current one; a file written by a newer build is rejected, and never overwritten.
+ *
+ * With encryption enabled the envelope is sealed before it's written, and unsealed on load;
+ * see `encryption`. Files from before encryption was enabled still load.
+ *
+ * @version 1.0.0
+ */
+
+use crate::encryption::{self, LockedError};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted when a file had to be loaded from its backup
+pub const RECOVERY_EVENT: &str = "storage-recovered-from-backup";
+
+/// `<file>.bak` next to `path`
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+/// Replace `path` with `contents` atomically: write a temp file next to it, fsync it, then
+/// rename it into place and fsync the directory so the rename itself is durable.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = sibling(path, "tmp");
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp_path, path)?;
+    sync_parent_dir(path)
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => File::open(dir)?.sync_all(),
+        _ => Ok(()),
+    }
+}
+
+/// Directories can't be opened for syncing on Windows; `MoveFileEx` is durable enough there
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+// ================================================================================================
+// SCHEMAS
+// ================================================================================================
+
+/// Upgrades a file's data from one schema version to the next
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// The migrations of one kind of file, `migrations[n]` upgrading version `n` to `n + 1`. The
+/// current version is the number of migrations, so adding one is all a schema change takes.
+pub struct Schema {
+    pub migrations: &'static [Migration],
+}
+
+impl Schema {
+    pub const fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+}
+
+/// Migration for a version whose data didn't change shape, such as the move into the envelope
+pub fn unchanged(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+#[derive(Serialize)]
+struct Envelope<'a, T: ?Sized> {
+    schema_version: u32,
+    data: &'a T,
+}
+
+/// A file written by a newer version of the app than this one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewerSchemaError {
+    pub found: u64,
+    pub supported: u32,
+}
+
+impl fmt::Display for NewerSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "written by a newer version of HabitQuest (schema version {}, this version supports up to {}) - update HabitQuest to use this data",
+            self.found, self.supported
+        )
+    }
+}
+
+impl std::error::Error for NewerSchemaError {}
+
+/// Schema version and data of a parsed file; files without the envelope are version 0
+fn unwrap_envelope(value: Value) -> Result<(u64, Value), String> {
+    match value {
+        Value::Object(mut map) if map.contains_key(SCHEMA_VERSION_KEY) => {
+            let version = map
+                .get(SCHEMA_VERSION_KEY)
+                .and_then(Value::as_u64)
+                .ok_or("schema_version is not a number")?;
+            let data = map.remove("data").ok_or("data missing from the schema envelope")?;
+            Ok((version, data))
+        }
+        legacy => Ok((0, legacy)),
+    }
+}
+
+/// Parse a file's contents and migrate them to the current version of `schema`
+pub fn decode<T: DeserializeOwned>(json: &str, schema: &Schema) -> Result<T, Box<dyn std::error::Error>> {
+    let (version, mut data) = unwrap_envelope(serde_json::from_str(json)?)?;
+    if version > u64::from(schema.version()) {
+        return Err(Box::new(NewerSchemaError { found: version, supported: schema.version() }));
+    }
+
+    for (from, migration) in schema.migrations.iter().enumerate().skip(version as usize) {
+        data = migration(data).map_err(|e| format!("migration from schema version {} failed: {}", from, e))?;
+    }
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Schema version of a file's contents, without migrating them
+pub fn stored_version(json: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    Ok(unwrap_envelope(serde_json::from_str(json)?)?.0)
+}
+
+/// `value` wrapped in the envelope for the current version of `schema`
+pub fn encode<T: Serialize + ?Sized>(value: &T, schema: &Schema) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&Envelope { schema_version: schema.version(), data: value })
+}
+
+// ================================================================================================
+// FILES
+// ================================================================================================
+
+/// Save `value` in its schema envelope to `path` with `write_atomic`, creating the directory
+/// if needed. The current contents become the `.bak` if they're valid JSON, so a truncated
+/// file never replaces a good backup. A file from a newer version is left alone. Sealed when
+/// encryption is on, and refused while it's locked.
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T, schema: &Schema) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = encryption::seal(&encode(value, schema)?)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    if let Ok(current) = fs::read_to_string(path) {
+        let current_value = encryption::unseal(&current)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Value>(&json).ok());
+        if let Some(current_value) = current_value {
+            if let Ok((version, _)) = unwrap_envelope(current_value) {
+                if version > u64::from(schema.version()) {
+                    return Err(format!(
+                        "Not saving {}: {}",
+                        path.display(),
+                        NewerSchemaError { found: version, supported: schema.version() }
+                    )
+                    .into());
+                }
+            }
+            write_atomic(&backup_path(path), current.as_bytes())?;
+        }
+    }
+
+    write_atomic(path, contents.as_bytes())?;
+    Ok(())
+}
+
+/// Contents of the JSON file at `path`, decrypted if it was saved encrypted
+pub fn read_json(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(encryption::unseal(&contents)?.into_owned())
+}
+
+/// Rewrite `path` and its `.bak` as `save_json` now would: sealed with the current key, or in
+/// plain text with encryption off. Missing files are skipped.
+pub fn reseal_file(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    for path in [path.to_path_buf(), backup_path(path)] {
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let resealed = encryption::seal(&encryption::unseal(&contents)?)
+            .map_err(|e| format!("Could not re-encrypt {}: {}", path.display(), e))?;
+        write_atomic(&path, resealed.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// A file that couldn't be read and was loaded from its backup instead
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageRecovery {
+    pub file: String,
+    /// Why the primary file couldn't be used
+    pub error: String,
+}
+
+#[derive(Debug)]
+pub struct Loaded<T> {
+    pub value: T,
+    /// Set when `value` came from the backup
+    pub recovery: Option<StorageRecovery>,
+}
+
+/// Load and migrate `path`, falling back to its `.bak` if the primary is missing or doesn't
+/// parse. `None` if neither exists; an error if the primary is unusable and there's no usable
+/// backup, if it comes from a newer version of the app, or if it's encrypted and locked.
+pub fn load_json<T: DeserializeOwned>(path: &Path, schema: &Schema) -> Result<Option<Loaded<T>>, Box<dyn std::error::Error>> {
+    let primary_error = match fs::read_to_string(path) {
+        Ok(contents) => match encryption::unseal(&contents).and_then(|json| decode(&json, schema)) {
+            Ok(value) => return Ok(Some(Loaded { value, recovery: None })),
+            Err(e) if e.is::<LockedError>() => return Err(e),
+            Err(e) if e.is::<NewerSchemaError>() => return Err(format!("Could not load {}: {}", path.display(), e).into()),
+            Err(e) => Some(format!("{}", e)),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => Some(format!("{}", e)),
+    };
+
+    let backup = match fs::read_to_string(backup_path(path)) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return match primary_error {
+                Some(error) => Err(format!("Could not load {}: {}", path.display(), error).into()),
+                None => Ok(None),
+            };
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let value = encryption::unseal(&backup).and_then(|json| decode(&json, schema)).map_err(|e| {
+        format!(
+            "Could not load {} ({}) or its backup ({})",
+            path.display(),
+            primary_error.as_deref().unwrap_or("missing"),
+            e
+        )
+    })?;
+
+    let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let error = primary_error.unwrap_or_else(|| "file missing".to_string());
+    Ok(Some(Loaded { value, recovery: Some(StorageRecovery { file, error }) }))
+}
+
+// ================================================================================================
+// RECOVERY WARNINGS
+// ================================================================================================
+
+/// Recoveries not yet seen by the frontend. Files are loaded during setup, before the webview
+/// listens for events, so they're also kept here until taken.
+#[derive(Default)]
+pub struct StorageWarnings {
+    pending: Mutex<Vec<StorageRecovery>>,
+}
+
+/// Unwrap a load result, warning the frontend if it came from a backup
+pub fn report_recovery<T>(app_handle: &AppHandle, loaded: Loaded<T>) -> T {
+    if let Some(recovery) = loaded.recovery {
+        log::warn!("{} could not be read ({}) - restored the last good backup", recovery.file, recovery.error);
+        let _ = app_handle.emit(RECOVERY_EVENT, &recovery);
+        if let Some(warnings) = app_handle.try_state::<StorageWarnings>() {
+            warnings.pending.lock().unwrap().push(recovery);
+        }
+    }
+    loaded.value
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn take_storage_warnings(app_handle: AppHandle) -> Result<Vec<StorageRecovery>, String> {
+    if let Some(warnings) = app_handle.try_state::<StorageWarnings>() {
+        Ok(std::mem::take(&mut *warnings.pending.lock().unwrap()))
+    } else {
+        Err("Storage warnings not initialized".to_string())
+    }
+}
@@ -0,0 +1,139 @@
+/*!
+ * ================================================================================================
+ * QUIET HOURS
+ * ================================================================================================
+ *
+ * Minute-resolution quiet periods with separate weekday and weekend profiles. A period whose
+ * end is earlier than its start wraps past midnight ("22:00 to 07:00") and belongs to the
+ * profile of the day it starts on. Every notification path checks `QuietHours::is_quiet`.
+ *
+ * @version 1.0.0
+ */
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Half-open range `[start, end)` of local time. `start == end` is an empty period.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietPeriod {
+    pub start_hour: u32,
+    pub start_minute: u32,
+    pub end_hour: u32,
+    pub end_minute: u32,
+}
+
+impl QuietPeriod {
+    pub fn new(start_hour: u32, start_minute: u32, end_hour: u32, end_minute: u32) -> Self {
+        Self { start_hour, start_minute, end_hour, end_minute }
+    }
+
+    fn start(&self) -> u32 {
+        (self.start_hour * 60 + self.start_minute).min(MINUTES_PER_DAY)
+    }
+
+    fn end(&self) -> u32 {
+        (self.end_hour * 60 + self.end_minute).min(MINUTES_PER_DAY)
+    }
+
+    pub fn wraps_midnight(&self) -> bool {
+        self.end() < self.start()
+    }
+
+    /// Whether `minute_of_day` is quiet on the day this period starts
+    fn covers_same_day(&self, minute_of_day: u32) -> bool {
+        if self.wraps_midnight() {
+            minute_of_day >= self.start()
+        } else {
+            minute_of_day >= self.start() && minute_of_day < self.end()
+        }
+    }
+
+    /// Whether `minute_of_day` is quiet on the day after this period starts
+    fn covers_next_day(&self, minute_of_day: u32) -> bool {
+        self.wraps_midnight() && minute_of_day < self.end()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_hour > 24 || self.end_hour > 24 || self.start_minute > 59 || self.end_minute > 59 {
+            return Err(format!(
+                "Invalid quiet period {:02}:{:02}-{:02}:{:02}",
+                self.start_hour, self.start_minute, self.end_hour, self.end_minute
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietHoursProfile {
+    pub periods: Vec<QuietPeriod>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// Applies to periods starting Monday through Friday
+    pub weekday: QuietHoursProfile,
+    /// Applies to periods starting Saturday or Sunday
+    pub weekend: QuietHoursProfile,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self::every_day(QuietPeriod::new(22, 0, 8, 0))
+    }
+}
+
+impl QuietHours {
+    fn every_day(period: QuietPeriod) -> Self {
+        let profile = QuietHoursProfile { periods: vec![period] };
+        Self {
+            enabled: true,
+            weekday: profile.clone(),
+            weekend: profile,
+        }
+    }
+
+    /// Quiet hours equivalent to an "active between start_hour and end_hour" window, on every day.
+    /// A window that starts where it ends is rejected: read as a period it would never be quiet,
+    /// though it was meant to leave no active hours at all.
+    pub fn from_active_window(start_hour: u32, end_hour: u32) -> Result<Self, String> {
+        let (start_hour, end_hour) = (start_hour.min(24), end_hour.min(24));
+        if start_hour == end_hour {
+            return Err(format!(
+                "Reminders can't be active from {start_hour:02}:00 to {end_hour:02}:00 - the window must not start where it ends"
+            ));
+        }
+        Ok(Self::every_day(QuietPeriod::new(end_hour, 0, start_hour, 0)))
+    }
+
+    pub fn profile_for(&self, day: NaiveDate) -> &QuietHoursProfile {
+        match day.weekday() {
+            Weekday::Sat | Weekday::Sun => &self.weekend,
+            _ => &self.weekday,
+        }
+    }
+
+    pub fn is_quiet(&self, now: DateTime<Local>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let minute_of_day = now.hour() * 60 + now.minute();
+        let today = now.date_naive();
+        let yesterday = today - Duration::days(1);
+
+        self.profile_for(today).periods.iter().any(|period| period.covers_same_day(minute_of_day))
+            || self.profile_for(yesterday).periods.iter().any(|period| period.covers_next_day(minute_of_day))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.weekday
+            .periods
+            .iter()
+            .chain(self.weekend.periods.iter())
+            .try_for_each(QuietPeriod::validate)
+    }
+}
@@ -0,0 +1,63 @@
+/*!
+ * ================================================================================================
+ * RANDOM REMINDERS
+ * ================================================================================================
+ *
+ * Picks the day's randomized reminder times for the `random_reminders` mode. Times fall inside
+ * the allowed window, keep a minimum spacing and are derived from a seed and the date only,
+ * so a given (date, seed) pair always produces the same schedule.
+ *
+ * @version 1.0.0
+ */
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Parameters for one day's random reminder schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomReminderPlan {
+    pub count: u32,
+    pub window_start: NaiveTime,
+    /// Exclusive
+    pub window_end: NaiveTime,
+    pub min_spacing_minutes: u32,
+}
+
+/// Mix the date into the seed so every day gets its own sequence
+fn day_seed(seed: u64, date: NaiveDate) -> u64 {
+    seed ^ (date.num_days_from_ce() as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Random reminder times for `date`, in ascending order.
+///
+/// Draws `count` uniform offsets in the window's slack (its length minus the spacing the
+/// reminders need), sorts them and pushes the i-th one back by i spacings. This keeps every
+/// gap at least `min_spacing_minutes`. If the window can't hold `count` reminders at that
+/// spacing, as many as fit are returned.
+pub fn random_reminder_times(date: NaiveDate, seed: u64, plan: &RandomReminderPlan) -> Vec<NaiveTime> {
+    let start = plan.window_start.hour() * 60 + plan.window_start.minute();
+    let end = plan.window_end.hour() * 60 + plan.window_end.minute();
+    if end <= start || plan.count == 0 {
+        return Vec::new();
+    }
+
+    let window = end - start;
+    let spacing = plan.min_spacing_minutes;
+    let fitting = (window - 1).checked_div(spacing).map_or(plan.count, |gaps| gaps + 1);
+    let count = plan.count.min(fitting);
+    let slack = window - 1 - (count - 1) * spacing;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(day_seed(seed, date));
+    let mut offsets: Vec<u32> = (0..count).map(|_| rng.gen_range(0..=slack)).collect();
+    offsets.sort_unstable();
+
+    offsets
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, offset)| {
+            let minute_of_day = start + offset + index as u32 * spacing;
+            NaiveTime::from_hms_opt(minute_of_day / 60, minute_of_day % 60, 0)
+        })
+        .collect()
+}
@@ -0,0 +1,227 @@
+/*!
+ * ================================================================================================
+ * REMINDER SCHEDULER
+ * ================================================================================================
+ *
+ * The one background loop that drives reminder notifications. Runs on the async runtime,
+ * sleeps until the next deadline computed from the notification config, then asks
+ * `BackgroundNotificationService` which reminders are due and delivers them. Each wake also
+ * takes the daily backup when it's due. While encrypted data is locked there's nothing to
+ * decide from, so checks wait until it's unlocked and then catch up on what came due meanwhile.
+ * Can be started, stopped and restarted through Tauri commands.
+ *
+ * Timers run on monotonic time, which stops during suspend, so the loop never sleeps longer
+ * than `DRIFT_CHECK_INTERVAL`. Each wake evaluates everything that came due since the previous
+ * one, and drift between wall-clock and monotonic time (suspend/resume, clock changes) is
+ * logged.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_settings::current_locale;
+use crate::background_notifications::{BackgroundNotificationService, Reminder};
+use crate::backups::BackupStore;
+use crate::clock::{ClockDrift, DriftDetector};
+use crate::encryption;
+use crate::notification_dispatcher::dispatch_notification;
+use crate::notification_sink::OutgoingNotification;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::watch;
+
+/// Longest single sleep, so a suspend or clock change is noticed soon after it happens
+const DRIFT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Handle to the running scheduler task, managed as Tauri state. Holding the cancellation
+/// sender is what keeps the loop alive; sending `true` (or dropping it) ends the loop.
+#[derive(Default)]
+pub struct ReminderScheduler {
+    cancel: Mutex<Option<watch::Sender<bool>>>,
+}
+
+impl ReminderScheduler {
+    /// Spawn the scheduler loop. Returns false if it was already running.
+    pub fn start(&self, app_handle: AppHandle) -> bool {
+        let mut cancel = self.cancel.lock().unwrap();
+        if cancel.is_some() {
+            return false;
+        }
+
+        let (sender, cancelled) = watch::channel(false);
+        tauri::async_runtime::spawn(run_scheduler(app_handle, cancelled));
+        *cancel = Some(sender);
+
+        log::info!("Reminder scheduler started");
+        true
+    }
+
+    /// Signal the scheduler loop to exit. Returns false if it was not running.
+    pub fn stop(&self) -> bool {
+        match self.cancel.lock().unwrap().take() {
+            Some(sender) => {
+                let _ = sender.send(true);
+                log::info!("Reminder scheduler stopped");
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn restart(&self, app_handle: AppHandle) {
+        self.stop();
+        self.start(app_handle);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.cancel.lock().unwrap().is_some()
+    }
+}
+
+async fn run_scheduler(app_handle: AppHandle, mut cancelled: watch::Receiver<bool>) {
+    let Some(service) = app_handle.try_state::<BackgroundNotificationService>() else {
+        log::error!("Background notification service not initialized - reminder scheduler not running");
+        return;
+    };
+    let mut schedule_changed = service.subscribe_schedule_changes();
+
+    // Everything due after this has yet to be evaluated. Never moves backwards, so a clock set
+    // back (or a frozen simulated clock) doesn't re-run reminders that were already sent.
+    let mut last_checked = service.now();
+    let mut drift = DriftDetector::new(chrono::Local::now(), Instant::now());
+    let mut logged_deadline = None;
+    let mut logged_locked = false;
+
+    loop {
+        let now = service.now();
+        let deadline = service.next_deadline(now);
+        let sleep_for = deadline
+            .map(|deadline| (deadline - now).to_std().unwrap_or_default())
+            .unwrap_or(DRIFT_CHECK_INTERVAL)
+            .min(DRIFT_CHECK_INTERVAL);
+
+        if logged_deadline != Some(deadline) {
+            match deadline {
+                Some(deadline) => log::info!("Next reminder check at {}", deadline.format("%Y-%m-%d %H:%M")),
+                None => log::info!("No reminders scheduled - idling"),
+            }
+            logged_deadline = Some(deadline);
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(sleep_for) => {}
+            _ = schedule_changed.changed() => {
+                log::info!("Reminder schedule changed - recomputing next deadline");
+                continue;
+            }
+            _ = cancelled.changed() => break,
+        }
+
+        // Drift is measured on the system clock, independent of any simulated one
+        match drift.check(chrono::Local::now(), Instant::now()) {
+            Some(ClockDrift::Forward(by)) => log::warn!(
+                "Wall clock jumped {} minutes ahead of monotonic time (suspend/resume or clock change) - catching up on missed reminders",
+                by.num_minutes()
+            ),
+            Some(ClockDrift::Backward(by)) => log::warn!(
+                "Wall clock set back {} minutes - reminders already checked won't be repeated",
+                by.num_minutes()
+            ),
+            None => {}
+        }
+
+        let now = service.now();
+        if now <= last_checked {
+            continue;
+        }
+
+        // Reminders that come due while locked are sent as a catch-up once it's unlocked
+        if encryption::is_locked() {
+            if !logged_locked {
+                log::info!("App data is locked - holding reminder checks until it's unlocked");
+                logged_locked = true;
+            }
+            continue;
+        }
+        logged_locked = false;
+        let since = std::mem::replace(&mut last_checked, now);
+
+        // Copying the data can take a while, so it's done off the async runtime
+        let backup_app_handle = app_handle.clone();
+        let backed_up = tauri::async_runtime::spawn_blocking(move || {
+            if let Some(backups) = backup_app_handle.try_state::<BackupStore>() {
+                backups.backup_if_due(now);
+            }
+        });
+        if let Err(e) = backed_up.await {
+            log::error!("Scheduled backup failed: {}", e);
+        }
+
+        for due in service.due_reminders(since, now) {
+            if !write_weekly_reviews(&service, &due.reminder) {
+                continue;
+            }
+            let notification = OutgoingNotification::for_reminder(&due.reminder, current_locale(&app_handle))
+                .with_budget(due.budget);
+            match dispatch_notification(&app_handle, &notification) {
+                Ok(result) => log::info!("Reminder {:?}: {:?}", due.reminder, result),
+                Err(e) => log::error!("Failed to send reminder {:?}: {}", due.reminder, e),
+            }
+        }
+    }
+
+    log::info!("Reminder scheduler loop exited");
+}
+
+/// Write the reports that weekly review reminders point to, including any in a catch-up.
+/// Returns false if a weekly review couldn't be written, so it isn't announced.
+fn write_weekly_reviews(service: &BackgroundNotificationService, reminder: &Reminder) -> bool {
+    match reminder {
+        // Plain-text reports would leak the encrypted data
+        Reminder::WeeklyReview { week_start } if encryption::is_enabled() => {
+            log::info!("Skipping the weekly review of the week of {} while encryption is enabled", week_start);
+            false
+        }
+        Reminder::WeeklyReview { week_start } => match service.write_weekly_review(*week_start) {
+            Ok(path) => {
+                log::info!("Weekly review written to {}", path.display());
+                true
+            }
+            Err(e) => {
+                log::error!("Failed to write weekly review of the week of {}: {}", week_start, e);
+                false
+            }
+        },
+        Reminder::CatchUp { missed } => {
+            for reminder in missed {
+                write_weekly_reviews(service, reminder);
+            }
+            true
+        }
+        _ => true,
+    }
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+#[tauri::command]
+pub async fn start_reminder_scheduler(app_handle: AppHandle) -> Result<(), String> {
+    let scheduler = app_handle.state::<ReminderScheduler>();
+    scheduler.start(app_handle.clone());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_reminder_scheduler(app_handle: AppHandle) -> Result<(), String> {
+    app_handle.state::<ReminderScheduler>().stop();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn restart_reminder_scheduler(app_handle: AppHandle) -> Result<(), String> {
+    let scheduler = app_handle.state::<ReminderScheduler>();
+    scheduler.restart(app_handle.clone());
+    Ok(())
+}
@@ -0,0 +1,86 @@
+/*!
+ * ================================================================================================
+ * STARTUP SUMMARY
+ * ================================================================================================
+ *
+ * What a launch at system startup reports: the habits still due in their current period and
+ * which of them have a streak at stake, computed from the habit streaks of the app data.
+ *
+ * @version 1.0.0
+ */
+
+use crate::habit_streaks::HabitStreak;
+use crate::messages;
+use chrono::{DateTime, Local};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueHabit {
+    pub habit_id: String,
+    pub habit_name: String,
+    pub streak: u32,
+    /// The streak ends with the current period unless the habit is completed
+    pub streak_at_stake: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartupSummary {
+    /// Streaks at stake first, longest first
+    pub due: Vec<DueHabit>,
+}
+
+impl StartupSummary {
+    /// Habits scheduled but not yet completed in their period containing `now`
+    pub fn build(habits: &[HabitStreak], now: DateTime<Local>) -> Self {
+        let mut due: Vec<DueHabit> = habits
+            .iter()
+            .filter(|habit| habit.scheduled_in_period(now))
+            .filter_map(|habit| {
+                let completed_at = habit.last_completed_at;
+                if habit.completed_in_period(completed_at, now) {
+                    return None;
+                }
+                Some(DueHabit {
+                    habit_id: habit.habit_id.clone(),
+                    habit_name: habit.habit_name.clone(),
+                    streak: habit.streak,
+                    streak_at_stake: habit.at_risk_until(completed_at, now).is_some(),
+                })
+            })
+            .collect();
+        due.sort_by(|a, b| {
+            b.streak_at_stake
+                .cmp(&a.streak_at_stake)
+                .then_with(|| if a.streak_at_stake { b.streak.cmp(&a.streak) } else { std::cmp::Ordering::Equal })
+        });
+        Self { due }
+    }
+
+    pub fn streaks_at_stake(&self) -> impl Iterator<Item = &DueHabit> {
+        self.due.iter().filter(|habit| habit.streak_at_stake)
+    }
+
+    /// Title and body of the startup notification, in `locale`
+    pub fn notification_text(&self, locale: &str) -> (String, String) {
+        let title = messages::message(locale, "startup.summary_title", &[]);
+        if self.due.is_empty() {
+            return (title, messages::message(locale, "startup.summary_all_done", &[]));
+        }
+
+        let habits = messages::list(locale, self.due.iter().map(|habit| habit.habit_name.clone()).collect());
+        let mut body = messages::message(
+            locale,
+            "startup.summary_due",
+            &[("count", &self.due.len().to_string()), ("habits", &habits)],
+        );
+
+        let streaks: Vec<String> = self
+            .streaks_at_stake()
+            .map(|habit| format!("{} ({})", habit.habit_name, habit.streak))
+            .collect();
+        if !streaks.is_empty() {
+            body.push('\n');
+            body.push_str(&messages::message(locale, "startup.summary_streaks", &[("streaks", &messages::list(locale, streaks))]));
+        }
+        (title, body)
+    }
+}
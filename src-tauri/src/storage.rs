@@ -0,0 +1,772 @@
+/*!
+ * ================================================================================================
+ * STORAGE
+ * ================================================================================================
+ *
+ * The `Storage` trait the app data store keeps its data behind, and its SQLite implementation.
+ * Habits, completions, the XP ledger, rewards, redemptions and sent notifications each get a
+ * table, with completions, XP and notifications indexed by day so date-range questions don't
+ * load the whole history. Every save runs in one transaction, and the schema is upgraded by a
+ * numbered list of SQL migrations tracked in `PRAGMA user_version`. The JSON files remain the
+ * import and export format.
+ *
+ * Instants are stored as UTC RFC 3339 with fixed precision, so text order is time order; days
+ * are the local `YYYY-MM-DD` the user saw.
+ *
+ * With encryption enabled the database is kept in memory and written whole to a sealed file
+ * after every change, as SQLite can't encrypt its own pages.
+ *
+ * @version 1.0.0
+ */
+
+use crate::app_data::{AppData, Completion, Habit, InventoryItem, Reward};
+use crate::encryption;
+use crate::notification_history::{HistoryEntry, HistoryPage, HistoryQuery};
+use crate::persistence::{self, NewerSchemaError};
+use chrono::{DateTime, Datelike, Local, NaiveDate, SecondsFormat, Utc, Weekday};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::serialize::OwnedData;
+use rusqlite::{params, params_from_iter, Connection, DatabaseName, OpenFlags, OptionalExtension, Row, Transaction};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+
+pub type StorageResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Page size used when a notification query doesn't specify one, as in the JSON history
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+// ================================================================================================
+// STORAGE TRAIT
+// ================================================================================================
+
+/// Completions with a timestamp between two local days, inclusive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub habit_id: Option<String>,
+    pub weekday: Option<Weekday>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompletionRecord {
+    pub habit_id: String,
+    pub period_key: String,
+    pub completed_at: DateTime<Local>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum XpReason {
+    /// XP already earned when the data was first stored
+    Opening,
+    Completion,
+    /// A completion taken back
+    Undo,
+    /// A change to the total that no completion explains
+    Adjustment,
+}
+
+/// A change to the user's total XP
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct XpEntry {
+    pub id: i64,
+    pub recorded_at: DateTime<Local>,
+    pub habit_id: Option<String>,
+    pub xp: i64,
+    pub reason: XpReason,
+}
+
+pub trait Storage: Send {
+    /// Everything stored, or `None` if nothing has been saved yet
+    fn load_app_data(&self) -> StorageResult<Option<AppData>>;
+
+    /// Replace the stored data with `data`, recording the XP it gained or lost since the last
+    /// save in the ledger
+    fn save_app_data(&mut self, data: &AppData, at: DateTime<Local>) -> StorageResult<()>;
+
+    /// Timestamped completions matching `query`, oldest first
+    fn completions(&self, query: &CompletionQuery) -> StorageResult<Vec<CompletionRecord>>;
+
+    /// Ledger entries recorded between two local days, inclusive, oldest first
+    fn xp_ledger(&self, from: NaiveDate, to: NaiveDate) -> StorageResult<Vec<XpEntry>>;
+
+    /// Add notifications to the log, skipping any already there
+    fn record_notifications(&mut self, entries: &[HistoryEntry]) -> StorageResult<()>;
+
+    fn notifications(&self, query: &HistoryQuery) -> StorageResult<HistoryPage>;
+}
+
+// ================================================================================================
+// MIGRATIONS
+// ================================================================================================
+
+/// `MIGRATIONS[n]` upgrades the database from version `n` to `n + 1`
+pub const MIGRATIONS: &[&str] = &[r#"
+    CREATE TABLE habits (
+        id TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        description TEXT,
+        frequency TEXT NOT NULL,
+        category TEXT NOT NULL,
+        xp_on_complete INTEGER NOT NULL,
+        streak INTEGER NOT NULL,
+        best_streak INTEGER NOT NULL,
+        last_completed_at TEXT,
+        is_recurring INTEGER NOT NULL,
+        specific_date TEXT,
+        color TEXT,
+        icon TEXT
+    );
+
+    -- Completions from before they were timestamped have no completed_at
+    CREATE TABLE completions (
+        habit_id TEXT NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+        period_key TEXT NOT NULL,
+        completed_at TEXT,
+        completed_on TEXT,
+        weekday INTEGER,
+        PRIMARY KEY (habit_id, period_key)
+    );
+    CREATE INDEX completions_by_day ON completions(completed_on, weekday);
+
+    CREATE TABLE xp_ledger (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        recorded_at TEXT NOT NULL,
+        recorded_on TEXT NOT NULL,
+        habit_id TEXT,
+        xp INTEGER NOT NULL,
+        reason TEXT NOT NULL
+    );
+    CREATE INDEX xp_ledger_by_day ON xp_ledger(recorded_on);
+
+    CREATE TABLE rewards (
+        position INTEGER PRIMARY KEY,
+        id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        cost INTEGER NOT NULL,
+        description TEXT,
+        icon TEXT,
+        rarity TEXT
+    );
+
+    CREATE TABLE redemptions (
+        position INTEGER PRIMARY KEY,
+        reward_id TEXT,
+        name TEXT NOT NULL,
+        cost INTEGER NOT NULL,
+        redeemed_at TEXT NOT NULL,
+        redeemed_on TEXT NOT NULL,
+        description TEXT,
+        icon TEXT,
+        rarity TEXT
+    );
+    CREATE INDEX redemptions_by_day ON redemptions(redeemed_on);
+
+    CREATE TABLE notifications (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        sent_at TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        triggered_by TEXT NOT NULL,
+        title TEXT NOT NULL,
+        result TEXT NOT NULL,
+        error TEXT,
+        UNIQUE (sent_at, kind, triggered_by)
+    );
+
+    -- Points, goals, categories, achievements, stats and settings, as JSON
+    CREATE TABLE app_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+"#, r#"
+    -- Periods the frontend marked `false`, kept so the data loads back as it was saved
+    ALTER TABLE completions ADD COLUMN completed INTEGER NOT NULL DEFAULT 1;
+"#];
+
+/// Bring the database up to the latest of `migrations`, each in its own transaction. A
+/// database from a newer version of the app is left untouched.
+pub fn run_migrations(conn: &mut Connection, migrations: &[&str]) -> StorageResult<()> {
+    let supported = migrations.len() as u32;
+    let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > supported {
+        return Err(Box::new(NewerSchemaError { found: u64::from(version), supported }));
+    }
+
+    for (from, sql) in migrations.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql).map_err(|e| format!("migration from schema version {} failed: {}", from, e))?;
+        tx.pragma_update(None, "user_version", from as u32 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Whether the file at `path` was written by a sealed `SqliteStorage` rather than by SQLite,
+/// whose files start with a binary header
+fn is_sealed_database(path: &Path) -> StorageResult<bool> {
+    use std::io::Read;
+    let mut first = [0u8; 1];
+    let read = fs::File::open(path)?.read(&mut first)?;
+    Ok(read == 1 && first[0] == b'{')
+}
+
+/// An in-memory database holding a copy of `bytes`, as serialized by SQLite
+fn database_from_bytes(bytes: &[u8]) -> StorageResult<Connection> {
+    if bytes.is_empty() {
+        return Err("the database is empty".into());
+    }
+    // SAFETY: the buffer comes from sqlite3_malloc64 and is only handed to SQLite, which frees it
+    let data = unsafe {
+        let ptr = NonNull::new(rusqlite::ffi::sqlite3_malloc64(bytes.len() as u64).cast::<u8>()).ok_or("out of memory")?;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr.as_ptr(), bytes.len());
+        OwnedData::from_raw_nonnull(ptr, bytes.len())
+    };
+    let mut conn = Connection::open_in_memory()?;
+    conn.deserialize(DatabaseName::Main, data, false)?;
+    Ok(conn)
+}
+
+/// The database at `path` for reading; a sealed one is decrypted into memory
+fn open_read_only(path: &Path) -> StorageResult<Connection> {
+    if is_sealed_database(path)? {
+        return database_from_bytes(&encryption::unseal_bytes(&fs::read_to_string(path)?)?);
+    }
+    Ok(Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?)
+}
+
+/// Schema version of the database at `path`, without migrating it
+pub fn database_version(path: &Path) -> StorageResult<u32> {
+    Ok(open_read_only(path)?.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+/// Check that the database at `path` is intact and can be opened by this version of the app
+pub fn check_database(path: &Path) -> StorageResult<()> {
+    let conn = open_read_only(path)?;
+    let result: String = conn.pragma_query_value(None, "integrity_check", |row| row.get(0))?;
+    if result != "ok" {
+        return Err(format!("{} is damaged: {}", path.display(), result).into());
+    }
+
+    let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let supported = MIGRATIONS.len() as u32;
+    if version > supported {
+        return Err(Box::new(NewerSchemaError { found: u64::from(version), supported }));
+    }
+    Ok(())
+}
+
+/// Write a consistent copy of the database at `path` to `to`, even while it's open elsewhere.
+/// A sealed database is replaced whole on every write, so it's copied as it is.
+pub fn copy_database(path: &Path, to: &Path) -> StorageResult<()> {
+    if is_sealed_database(path)? {
+        fs::copy(path, to)?;
+        return Ok(());
+    }
+    open_read_only(path)?.execute("VACUUM INTO ?1", [to.to_string_lossy()])?;
+    Ok(())
+}
+
+fn remove_database(path: &Path) -> StorageResult<()> {
+    let journal = path.with_file_name(format!("{}-journal", path.file_name().unwrap_or_default().to_string_lossy()));
+    for path in [path, journal.as_path()] {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Store the database at `plain` or `sealed` the way the current encryption mode saves it:
+/// sealed with the current key at `sealed`, or as a SQLite file at `plain`. The other file is
+/// removed once the new one is written.
+pub fn reseal_database(plain: &Path, sealed: &Path) -> StorageResult<()> {
+    if encryption::is_sealing() {
+        let source = if sealed.exists() { sealed } else { plain };
+        if !source.exists() {
+            return Ok(());
+        }
+        let contents = encryption::seal_bytes(&open_read_only(source)?.serialize(DatabaseName::Main)?)?;
+        persistence::write_atomic(sealed, contents.as_bytes())?;
+        remove_database(plain)
+    } else if sealed.exists() {
+        let conn = open_read_only(sealed)?;
+        remove_database(plain)?;
+        conn.execute("VACUUM INTO ?1", [plain.to_string_lossy()])?;
+        drop(conn);
+        fs::remove_file(sealed)?;
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+// ================================================================================================
+// SQLITE STORAGE
+// ================================================================================================
+
+pub struct SqliteStorage {
+    conn: Connection,
+    /// File the in-memory database is written to, sealed, after every change
+    sealed_path: Option<PathBuf>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> StorageResult<Self> {
+        Self::prepare(Connection::open(path)?)
+            .map_err(|e| format!("Could not open {}: {}", path.display(), e).into())
+    }
+
+    pub fn open_in_memory() -> StorageResult<Self> {
+        Self::prepare(Connection::open_in_memory()?)
+    }
+
+    /// Decrypt the database at `path` into memory, or start an empty one if there is none.
+    /// Every change is written back to `path`, sealed with the current key.
+    pub fn open_sealed(path: &Path) -> StorageResult<Self> {
+        let open = || -> StorageResult<Self> {
+            let conn = if path.exists() { open_read_only(path)? } else { Connection::open_in_memory()? };
+            let mut storage = Self::prepare(conn)?;
+            storage.sealed_path = Some(path.to_path_buf());
+            storage.persist()?;
+            Ok(storage)
+        };
+        open().map_err(|e| format!("Could not open {}: {}", path.display(), e).into())
+    }
+
+    fn prepare(mut conn: Connection) -> StorageResult<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        run_migrations(&mut conn, MIGRATIONS)?;
+        Ok(Self { conn, sealed_path: None })
+    }
+
+    /// Write the database to its sealed file, if it's kept in one
+    fn persist(&self) -> StorageResult<()> {
+        if let Some(path) = &self.sealed_path {
+            let contents = encryption::seal_bytes(&self.conn.serialize(DatabaseName::Main)?)?;
+            persistence::write_atomic(path, contents.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn habits(&self) -> StorageResult<Vec<Habit>> {
+        let mut completions: HashMap<String, Vec<(String, Completion)>> = HashMap::new();
+        let mut statement = self.conn.prepare("SELECT habit_id, period_key, completed_at, completed FROM completions")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let completion = match row.get::<_, Option<String>>(2)? {
+                Some(at) => Completion::At(parse_instant(&at)?),
+                None => Completion::Legacy(row.get(3)?),
+            };
+            completions.entry(row.get(0)?).or_default().push((row.get(1)?, completion));
+        }
+
+        let mut statement = self.conn.prepare(
+            "SELECT id, title, description, frequency, category, xp_on_complete, streak, best_streak,
+                    last_completed_at, is_recurring, specific_date, color, icon
+             FROM habits ORDER BY position",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut habits = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            habits.push(Habit {
+                completions: completions.remove(&id).unwrap_or_default().into_iter().collect(),
+                id,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                frequency: from_text(&row.get::<_, String>(3)?)?,
+                category: row.get(4)?,
+                xp_on_complete: row.get(5)?,
+                streak: row.get(6)?,
+                best_streak: row.get(7)?,
+                last_completed_at: row.get::<_, Option<String>>(8)?.map(|at| parse_instant(&at)).transpose()?,
+                is_recurring: row.get(9)?,
+                specific_date: row.get::<_, Option<String>>(10)?.map(|day| day.parse()).transpose()?,
+                color: row.get(11)?,
+                icon: row.get(12)?,
+            });
+        }
+        Ok(habits)
+    }
+
+    fn shop(&self) -> StorageResult<Vec<Reward>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT id, name, cost, description, icon, rarity FROM rewards ORDER BY position")?;
+        let mut rows = statement.query([])?;
+        let mut shop = Vec::new();
+        while let Some(row) = rows.next()? {
+            shop.push(Reward {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                cost: row.get(2)?,
+                description: row.get(3)?,
+                icon: row.get(4)?,
+                rarity: row.get::<_, Option<String>>(5)?.map(|rarity| from_text(&rarity)).transpose()?,
+            });
+        }
+        Ok(shop)
+    }
+
+    fn inventory(&self) -> StorageResult<Vec<InventoryItem>> {
+        let mut statement = self.conn.prepare(
+            "SELECT reward_id, name, cost, redeemed_at, description, icon, rarity FROM redemptions ORDER BY position",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut inventory = Vec::new();
+        while let Some(row) = rows.next()? {
+            inventory.push(InventoryItem {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                cost: row.get(2)?,
+                redeemed_at: parse_instant(&row.get::<_, String>(3)?)?,
+                description: row.get(4)?,
+                icon: row.get(5)?,
+                rarity: row.get::<_, Option<String>>(6)?.map(|rarity| from_text(&rarity)).transpose()?,
+            });
+        }
+        Ok(inventory)
+    }
+}
+
+/// The `app_state` row holding `key`, parsed
+fn state_value<T: DeserializeOwned>(conn: &Connection, key: &str) -> StorageResult<Option<T>> {
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM app_state WHERE key = ?1", [key], |row| row.get(0))
+        .optional()?;
+    Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+}
+
+fn set_state_value<T: Serialize + ?Sized>(tx: &Transaction, key: &str, value: &T) -> StorageResult<()> {
+    tx.execute(
+        "INSERT INTO app_state (key, value) VALUES (?1, ?2)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        params![key, serde_json::to_string(value)?],
+    )?;
+    Ok(())
+}
+
+/// Ledger entries explaining the change from the stored completions and XP total to `data`'s
+fn xp_changes(tx: &Transaction, data: &AppData) -> StorageResult<Vec<(Option<String>, i64, XpReason)>> {
+    let previous_total: Option<u32> = state_value(tx, "totalXP")?;
+    let Some(previous_total) = previous_total else {
+        return Ok(if data.total_xp > 0 { vec![(None, i64::from(data.total_xp), XpReason::Opening)] } else { Vec::new() });
+    };
+
+    let mut previous = HashSet::new();
+    let mut statement = tx.prepare("SELECT habit_id, period_key FROM completions WHERE completed")?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        previous.insert((row.get::<_, String>(0)?, row.get::<_, String>(1)?));
+    }
+
+    let mut changes = Vec::new();
+    for habit in &data.habits {
+        let xp = i64::from(habit.xp_on_complete);
+        for (period_key, completion) in &habit.completions {
+            if *completion == Completion::Legacy(false) {
+                continue;
+            }
+            let key = (habit.id.clone(), period_key.clone());
+            if !previous.remove(&key) {
+                changes.push((Some(habit.id.clone()), xp, XpReason::Completion));
+            }
+        }
+    }
+    // Completions left over were taken back, unless their habit is gone with its XP kept
+    let habits: HashMap<&str, &Habit> = data.habits.iter().map(|habit| (habit.id.as_str(), habit)).collect();
+    for (habit_id, _) in previous {
+        if let Some(habit) = habits.get(habit_id.as_str()) {
+            changes.push((Some(habit_id), -i64::from(habit.xp_on_complete), XpReason::Undo));
+        }
+    }
+
+    let explained: i64 = changes.iter().map(|(_, xp, _)| xp).sum();
+    let residual = i64::from(data.total_xp) - i64::from(previous_total) - explained;
+    if residual != 0 {
+        changes.push((None, residual, XpReason::Adjustment));
+    }
+    Ok(changes)
+}
+
+impl Storage for SqliteStorage {
+    fn load_app_data(&self) -> StorageResult<Option<AppData>> {
+        let Some(total_xp) = state_value(&self.conn, "totalXP")? else {
+            return Ok(None);
+        };
+
+        Ok(Some(AppData {
+            habits: self.habits()?,
+            points: state_value(&self.conn, "points")?.unwrap_or_default(),
+            total_xp,
+            goals: state_value(&self.conn, "goals")?.unwrap_or_default(),
+            inventory: self.inventory()?,
+            shop: self.shop()?,
+            categories: state_value(&self.conn, "categories")?.unwrap_or_default(),
+            achievements: state_value(&self.conn, "achievements")?.unwrap_or_default(),
+            user_stats: state_value(&self.conn, "userStats")?.unwrap_or_default(),
+            settings: state_value(&self.conn, "settings")?.unwrap_or_default(),
+            version: state_value(&self.conn, "version")?.unwrap_or_default(),
+        }))
+    }
+
+    fn save_app_data(&mut self, data: &AppData, at: DateTime<Local>) -> StorageResult<()> {
+        let tx = self.conn.transaction()?;
+
+        for (habit_id, xp, reason) in xp_changes(&tx, data)? {
+            tx.execute(
+                "INSERT INTO xp_ledger (recorded_at, recorded_on, habit_id, xp, reason) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![instant_text(at), day_text(at), habit_id, xp, to_text(&reason)?],
+            )?;
+        }
+
+        tx.execute_batch("DELETE FROM completions; DELETE FROM habits; DELETE FROM rewards; DELETE FROM redemptions;")?;
+        for (position, habit) in data.habits.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO habits (id, position, title, description, frequency, category, xp_on_complete, streak,
+                                     best_streak, last_completed_at, is_recurring, specific_date, color, icon)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    habit.id,
+                    position as i64,
+                    habit.title,
+                    habit.description,
+                    to_text(&habit.frequency)?,
+                    habit.category,
+                    habit.xp_on_complete,
+                    habit.streak,
+                    habit.best_streak,
+                    habit.last_completed_at.map(instant_text),
+                    habit.is_recurring,
+                    habit.specific_date.map(|day| day.to_string()),
+                    habit.color,
+                    habit.icon,
+                ],
+            )?;
+            for (period_key, completion) in &habit.completions {
+                // `false` marks a period that isn't complete, stored so it loads back
+                let at = completion.at();
+                tx.execute(
+                    "INSERT INTO completions (habit_id, period_key, completed_at, completed_on, weekday, completed)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        habit.id,
+                        period_key,
+                        at.map(instant_text),
+                        at.map(day_text),
+                        at.map(|at| at.weekday().num_days_from_monday()),
+                        *completion != Completion::Legacy(false),
+                    ],
+                )?;
+            }
+        }
+
+        for (position, reward) in data.shop.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO rewards (position, id, name, cost, description, icon, rarity) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    position as i64,
+                    reward.id,
+                    reward.name,
+                    reward.cost,
+                    reward.description,
+                    reward.icon,
+                    reward.rarity.as_ref().map(to_text).transpose()?,
+                ],
+            )?;
+        }
+        for (position, item) in data.inventory.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO redemptions (position, reward_id, name, cost, redeemed_at, redeemed_on, description, icon, rarity)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    position as i64,
+                    item.id,
+                    item.name,
+                    item.cost,
+                    instant_text(item.redeemed_at),
+                    day_text(item.redeemed_at),
+                    item.description,
+                    item.icon,
+                    item.rarity.as_ref().map(to_text).transpose()?,
+                ],
+            )?;
+        }
+
+        set_state_value(&tx, "points", &data.points)?;
+        set_state_value(&tx, "totalXP", &data.total_xp)?;
+        set_state_value(&tx, "goals", &data.goals)?;
+        set_state_value(&tx, "categories", &data.categories)?;
+        set_state_value(&tx, "achievements", &data.achievements)?;
+        set_state_value(&tx, "userStats", &data.user_stats)?;
+        set_state_value(&tx, "settings", &data.settings)?;
+        set_state_value(&tx, "version", &data.version)?;
+
+        tx.commit()?;
+        self.persist()
+    }
+
+    fn completions(&self, query: &CompletionQuery) -> StorageResult<Vec<CompletionRecord>> {
+        let mut sql = "SELECT habit_id, period_key, completed_at FROM completions
+                       WHERE completed_on BETWEEN ?1 AND ?2"
+            .to_string();
+        let mut values = vec![SqlValue::Text(query.from.to_string()), SqlValue::Text(query.to.to_string())];
+        if let Some(habit_id) = &query.habit_id {
+            values.push(SqlValue::Text(habit_id.clone()));
+            sql.push_str(&format!(" AND habit_id = ?{}", values.len()));
+        }
+        if let Some(weekday) = query.weekday {
+            values.push(SqlValue::Integer(i64::from(weekday.num_days_from_monday())));
+            sql.push_str(&format!(" AND weekday = ?{}", values.len()));
+        }
+        sql.push_str(" ORDER BY completed_at");
+
+        let mut statement = self.conn.prepare(&sql)?;
+        let mut rows = statement.query(params_from_iter(values))?;
+        let mut records = Vec::new();
+        while let Some(row) = rows.next()? {
+            records.push(CompletionRecord {
+                habit_id: row.get(0)?,
+                period_key: row.get(1)?,
+                completed_at: parse_instant(&row.get::<_, String>(2)?)?,
+            });
+        }
+        Ok(records)
+    }
+
+    fn xp_ledger(&self, from: NaiveDate, to: NaiveDate) -> StorageResult<Vec<XpEntry>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, recorded_at, habit_id, xp, reason FROM xp_ledger
+             WHERE recorded_on BETWEEN ?1 AND ?2 ORDER BY id",
+        )?;
+        let mut rows = statement.query(params![from.to_string(), to.to_string()])?;
+        let mut entries = Vec::new();
+        while let Some(row) = rows.next()? {
+            entries.push(XpEntry {
+                id: row.get(0)?,
+                recorded_at: parse_instant(&row.get::<_, String>(1)?)?,
+                habit_id: row.get(2)?,
+                xp: row.get(3)?,
+                reason: from_text(&row.get::<_, String>(4)?)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn record_notifications(&mut self, entries: &[HistoryEntry]) -> StorageResult<()> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        for entry in entries {
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO notifications (sent_at, kind, triggered_by, title, result, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    instant_text(entry.sent_at),
+                    to_text(&entry.kind)?,
+                    entry.trigger,
+                    entry.title,
+                    to_text(&entry.result)?,
+                    entry.error,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        if inserted > 0 {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    fn notifications(&self, query: &HistoryQuery) -> StorageResult<HistoryPage> {
+        let mut filters = Vec::new();
+        let mut values = Vec::new();
+        if let Some(kind) = &query.kind {
+            values.push(SqlValue::Text(to_text(kind)?));
+            filters.push(format!("kind = ?{}", values.len()));
+        }
+        if let Some(result) = &query.result {
+            values.push(SqlValue::Text(to_text(result)?));
+            filters.push(format!("result = ?{}", values.len()));
+        }
+        if let Some(since) = query.since {
+            values.push(SqlValue::Text(instant_text(since)));
+            filters.push(format!("sent_at >= ?{}", values.len()));
+        }
+        if let Some(until) = query.until {
+            values.push(SqlValue::Text(instant_text(until)));
+            filters.push(format!("sent_at < ?{}", values.len()));
+        }
+        let filter = if filters.is_empty() { String::new() } else { format!(" WHERE {}", filters.join(" AND ")) };
+
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM notifications{}", filter),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        values.push(SqlValue::Integer(query.limit.unwrap_or(DEFAULT_PAGE_SIZE) as i64));
+        values.push(SqlValue::Integer(query.offset as i64));
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT id, sent_at, kind, triggered_by, title, result, error FROM notifications{}
+             ORDER BY sent_at DESC, id DESC LIMIT ?{} OFFSET ?{}",
+            filter,
+            values.len() - 1,
+            values.len()
+        ))?;
+        let mut rows = statement.query(params_from_iter(values))?;
+        let mut entries = Vec::new();
+        while let Some(row) = rows.next()? {
+            entries.push(history_entry(row)?);
+        }
+        Ok(HistoryPage { entries, total: total as usize })
+    }
+}
+
+fn history_entry(row: &Row) -> StorageResult<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get::<_, i64>(0)? as u64,
+        sent_at: parse_instant(&row.get::<_, String>(1)?)?,
+        kind: from_text(&row.get::<_, String>(2)?)?,
+        trigger: row.get(3)?,
+        title: row.get(4)?,
+        result: from_text(&row.get::<_, String>(5)?)?,
+        error: row.get(6)?,
+    })
+}
+
+// ================================================================================================
+// COLUMN ENCODING
+// ================================================================================================
+
+/// Fixed width in UTC, so the text sorts in time order, and to the nanosecond, so instants
+/// taken from the clock load back equal to the ones in the event log
+fn instant_text(at: DateTime<Local>) -> String {
+    at.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn parse_instant(text: &str) -> StorageResult<DateTime<Local>> {
+    Ok(DateTime::parse_from_rfc3339(text)?.with_timezone(&Local))
+}
+
+fn day_text(at: DateTime<Local>) -> String {
+    at.date_naive().to_string()
+}
+
+/// The serde name of a unit enum variant, as the JSON files spell it
+fn to_text<T: Serialize>(value: &T) -> StorageResult<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Err(format!("{} is not a name", other).into()),
+    }
+}
+
+fn from_text<T: DeserializeOwned>(text: &str) -> StorageResult<T> {
+    Ok(serde_json::from_value(serde_json::Value::String(text.to_string()))?)
+}
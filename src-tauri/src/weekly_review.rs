@@ -0,0 +1,403 @@
+/*!
+ * ================================================================================================
+ * WEEKLY REVIEW
+ * ================================================================================================
+ *
+ * A review of the past Monday-to-Sunday week, written as Markdown and HTML to `reviews/` in the
+ * app data directory so it can be kept in a journal: per-habit completion rate and streak
+ * change, XP per category against the monthly goals, and rewards redeemed. The reports are
+ * built from the synced habit streaks and progress (goals and redeemed rewards).
+ *
+ * @version 1.0.0
+ */
+
+use crate::background_notifications::BackgroundNotificationService;
+use crate::habit_streaks::{HabitFrequency, HabitStreak};
+use crate::persistence::{self, Schema};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app data directory the reports are written to
+pub const REVIEWS_DIR: &str = "reviews";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WeeklyReviewConfig {
+    pub enabled: bool,
+    /// Day the review of the previous week is written
+    pub weekday: Weekday,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl Default for WeeklyReviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            weekday: Weekday::Mon,
+            hour: 9,
+            minute: 0,
+        }
+    }
+}
+
+impl WeeklyReviewConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.hour > 23 || self.minute > 59 {
+            return Err(format!("Invalid weekly review time {:02}:{:02}", self.hour, self.minute));
+        }
+        Ok(())
+    }
+
+    /// When the review goes out on `day`, if it does that day
+    pub fn occurrence_on(&self, day: NaiveDate) -> Option<DateTime<Local>> {
+        if !self.enabled || day.weekday() != self.weekday {
+            return None;
+        }
+        let time = NaiveTime::from_hms_opt(self.hour, self.minute, 0)?;
+        Local.from_local_datetime(&day.and_time(time)).earliest()
+    }
+}
+
+/// Monday of the last full week before `day`
+pub fn previous_week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(i64::from(day.weekday().num_days_from_monday()) + 7)
+}
+
+/// File name of the review of the week starting `week_start`, without extension
+pub fn report_name(week_start: NaiveDate) -> String {
+    format!("weekly-review-{}", week_start.format("%Y-%m-%d"))
+}
+
+// ================================================================================================
+// PROGRESS DATA
+// ================================================================================================
+
+/// Mirrors an entry of the frontend's `goals`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Goal {
+    #[serde(rename = "monthlyTargetXP")]
+    pub monthly_target_xp: u32,
+}
+
+/// Mirrors an entry of the frontend's `inventory`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Redemption {
+    pub name: String,
+    pub cost: u32,
+    pub redeemed_at: DateTime<Local>,
+}
+
+/// Schema history of `progress.json`: v1 added the envelope
+pub const PROGRESS_SCHEMA: Schema = Schema { migrations: &[persistence::unchanged] };
+
+/// Goals per category and redeemed rewards, synced from the frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProgressData {
+    #[serde(default)]
+    pub goals: BTreeMap<String, Goal>,
+    #[serde(default)]
+    pub inventory: Vec<Redemption>,
+}
+
+// ================================================================================================
+// REPORT
+// ================================================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HabitReview {
+    pub habit_name: String,
+    pub category: String,
+    pub completed: u32,
+    /// Completions the frequency asks for in a week; none for monthly and yearly habits
+    pub expected: Option<u32>,
+    /// Streak as of the last day of the week
+    pub streak: u32,
+    /// Change since the end of the previous week; none for a habit first completed this week
+    pub streak_delta: Option<i64>,
+}
+
+impl HabitReview {
+    /// Share of the expected completions done, in percent
+    pub fn completion_rate(&self) -> Option<u32> {
+        self.expected
+            .filter(|expected| *expected > 0)
+            .map(|expected| self.completed.min(expected) * 100 / expected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryReview {
+    pub category: String,
+    pub week_xp: u32,
+    /// XP from the first of the month the week ends in, through the end of the week
+    pub month_xp: u32,
+    pub monthly_target_xp: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklyReview {
+    pub week_start: NaiveDate,
+    pub habits: Vec<HabitReview>,
+    pub categories: Vec<CategoryReview>,
+    pub redeemed: Vec<Redemption>,
+}
+
+impl WeeklyReview {
+    /// Review of the week starting on Monday `week_start`. Streaks are counted from the
+    /// completions, so a review of a past week shows the streaks as they were then.
+    pub fn build(week_start: NaiveDate, habits: &[HabitStreak], progress: &ProgressData) -> Self {
+        let week_end = week_start + Duration::days(7);
+        let month_start = (week_end - Duration::days(1)).with_day(1).unwrap_or(week_start);
+        let in_range = |at: &DateTime<Local>, start: NaiveDate, end: NaiveDate| {
+            at.date_naive() >= start && at.date_naive() < end
+        };
+
+        let last_day = week_end - Duration::days(1);
+        let previous_last_day = week_start - Duration::days(1);
+        let habit_reviews = habits
+            .iter()
+            .map(|habit| HabitReview {
+                habit_name: habit.habit_name.clone(),
+                category: habit.category.clone(),
+                completed: habit.completions.iter().filter(|at| in_range(at, week_start, week_end)).count() as u32,
+                expected: match habit.frequency {
+                    HabitFrequency::Daily => Some(7),
+                    HabitFrequency::Weekly => Some(1),
+                    HabitFrequency::Monthly | HabitFrequency::Yearly => None,
+                },
+                streak: habit.streak_on(last_day),
+                streak_delta: habit
+                    .completions
+                    .iter()
+                    .any(|at| at.date_naive() < week_start)
+                    .then(|| i64::from(habit.streak_on(last_day)) - i64::from(habit.streak_on(previous_last_day))),
+            })
+            .collect();
+
+        let mut xp: BTreeMap<String, (u32, u32)> = progress
+            .goals
+            .keys()
+            .map(|category| (category.clone(), (0, 0)))
+            .collect();
+        for habit in habits {
+            let (week_xp, month_xp) = xp.entry(habit.category.clone()).or_default();
+            for completed_at in &habit.completions {
+                if in_range(completed_at, week_start, week_end) {
+                    *week_xp += habit.xp_on_complete;
+                }
+                if in_range(completed_at, month_start, week_end) {
+                    *month_xp += habit.xp_on_complete;
+                }
+            }
+        }
+        let categories = xp
+            .into_iter()
+            .map(|(category, (week_xp, month_xp))| CategoryReview {
+                monthly_target_xp: progress.goals.get(&category).map(|goal| goal.monthly_target_xp),
+                category,
+                week_xp,
+                month_xp,
+            })
+            .collect();
+
+        let redeemed = progress
+            .inventory
+            .iter()
+            .filter(|redemption| in_range(&redemption.redeemed_at, week_start, week_end))
+            .cloned()
+            .collect();
+
+        Self { week_start, habits: habit_reviews, categories, redeemed }
+    }
+
+    pub fn week_end(&self) -> NaiveDate {
+        self.week_start + Duration::days(6)
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "HabitQuest Weekly Review: {} to {}",
+            self.week_start.format("%Y-%m-%d"),
+            self.week_end().format("%Y-%m-%d")
+        )
+    }
+
+    /// Table cells shared by the Markdown and HTML reports
+    fn habit_rows(&self) -> Vec<[String; 5]> {
+        self.habits
+            .iter()
+            .map(|habit| {
+                [
+                    habit.habit_name.clone(),
+                    habit.category.clone(),
+                    match (habit.expected, habit.completion_rate()) {
+                        (Some(expected), Some(rate)) => format!("{}/{} ({}%)", habit.completed, expected, rate),
+                        _ => habit.completed.to_string(),
+                    },
+                    habit.streak.to_string(),
+                    habit.streak_delta.map_or_else(|| "new".to_string(), |delta| format!("{:+}", delta)),
+                ]
+            })
+            .collect()
+    }
+
+    fn category_rows(&self) -> Vec<[String; 4]> {
+        self.categories
+            .iter()
+            .map(|category| {
+                [
+                    category.category.clone(),
+                    category.week_xp.to_string(),
+                    match category.monthly_target_xp {
+                        Some(target) if target > 0 => {
+                            format!("{} / {} ({}%)", category.month_xp, target, category.month_xp * 100 / target)
+                        }
+                        _ => category.month_xp.to_string(),
+                    },
+                    category.monthly_target_xp.map_or_else(|| "-".to_string(), |target| target.to_string()),
+                ]
+            })
+            .collect()
+    }
+
+    fn redemption_lines(&self) -> Vec<String> {
+        self.redeemed
+            .iter()
+            .map(|redemption| {
+                format!("{} ({} points) on {}", redemption.name, redemption.cost, redemption.redeemed_at.format("%Y-%m-%d"))
+            })
+            .collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n## Habits\n\n", self.title());
+        out.push_str("| Habit | Category | Completed | Streak | Change |\n|---|---|---|---|---|\n");
+        for row in self.habit_rows() {
+            out.push_str(&format!("| {} |\n", row.map(|cell| markdown_cell(&cell)).join(" | ")));
+        }
+
+        out.push_str("\n## XP by Category\n\n");
+        out.push_str("| Category | This week | This month | Monthly target |\n|---|---|---|---|\n");
+        for row in self.category_rows() {
+            out.push_str(&format!("| {} |\n", row.map(|cell| markdown_cell(&cell)).join(" | ")));
+        }
+
+        out.push_str("\n## Rewards Redeemed\n\n");
+        let redemptions = self.redemption_lines();
+        if redemptions.is_empty() {
+            out.push_str("_None this week_\n");
+        }
+        for line in redemptions {
+            out.push_str(&format!("- {}\n", single_line(&line)));
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let table = |headers: &[&str], rows: Vec<Vec<String>>| {
+            let head: String = headers.iter().map(|header| format!("<th>{}</th>", escape_html(header))).collect();
+            let body: String = rows
+                .into_iter()
+                .map(|row| {
+                    let cells: String = row.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+                    format!("<tr>{}</tr>\n", cells)
+                })
+                .collect();
+            format!("<table>\n<thead><tr>{}</tr></thead>\n<tbody>\n{}</tbody>\n</table>\n", head, body)
+        };
+
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+             <style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+             th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}</style>\n\
+             </head>\n<body>\n<h1>{0}</h1>\n",
+            title
+        );
+        out.push_str("<h2>Habits</h2>\n");
+        out.push_str(&table(
+            &["Habit", "Category", "Completed", "Streak", "Change"],
+            self.habit_rows().into_iter().map(Vec::from).collect(),
+        ));
+        out.push_str("<h2>XP by Category</h2>\n");
+        out.push_str(&table(
+            &["Category", "This week", "This month", "Monthly target"],
+            self.category_rows().into_iter().map(Vec::from).collect(),
+        ));
+        out.push_str("<h2>Rewards Redeemed</h2>\n");
+        let redemptions = self.redemption_lines();
+        if redemptions.is_empty() {
+            out.push_str("<p><em>None this week</em></p>\n");
+        } else {
+            out.push_str("<ul>\n");
+            for line in redemptions {
+                out.push_str(&format!("<li>{}</li>\n", escape_html(&line)));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Write the Markdown and HTML reports to `reviews_dir`, creating it if needed, and return
+    /// the Markdown report's path. Regenerating a week overwrites its reports.
+    pub fn write_reports(&self, reviews_dir: &Path) -> std::io::Result<PathBuf> {
+        fs::create_dir_all(reviews_dir)?;
+        let name = report_name(self.week_start);
+        let markdown_path = reviews_dir.join(format!("{}.md", name));
+        persistence::write_atomic(&markdown_path, self.to_markdown().as_bytes())?;
+        persistence::write_atomic(&reviews_dir.join(format!("{}.html", name)), self.to_html().as_bytes())?;
+        Ok(markdown_path)
+    }
+}
+
+/// `text` on one line, so a name with a line break can't end a table row or list item early
+fn single_line(text: &str) -> String {
+    text.replace("\r\n", " ").replace(['\r', '\n'], " ")
+}
+
+/// `text` as the content of a Markdown table cell
+fn markdown_cell(text: &str) -> String {
+    single_line(text).replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ================================================================================================
+// TAURI COMMANDS
+// ================================================================================================
+
+/// Superseded by the app data commands, which share the habits themselves; kept for older frontends
+#[tauri::command]
+pub async fn sync_progress(app_handle: AppHandle, progress: ProgressData) -> Result<(), String> {
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        service.set_progress(progress);
+        Ok(())
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
+
+/// Write the review of the last full week now, returning the Markdown report's path
+#[tauri::command]
+pub async fn generate_weekly_review(app_handle: AppHandle) -> Result<String, String> {
+    if let Some(service) = app_handle.try_state::<BackgroundNotificationService>() {
+        let week_start = previous_week_start(service.now().date_naive());
+        service
+            .write_weekly_review(week_start)
+            .map(|path| path.display().to_string())
+            .map_err(|e| format!("Could not write weekly review: {}", e))
+    } else {
+        Err("Background notification service not initialized".to_string())
+    }
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use chrono::Duration;
+use common::at;
+use habitquest_lib::activity_insights::ActivityInsights;
+
+/// A user who checks in every evening around 19:00 for two weeks
+fn evening_user() -> ActivityInsights {
+    let mut insights = ActivityInsights::default();
+    for day in 1..=14 {
+        insights.record_session(at(2025, 3, day, 19, 5));
+        insights.record_completion(at(2025, 3, day, 19, 10));
+    }
+    insights
+}
+
+#[test]
+fn thin_history_keeps_configured_hour() {
+    let mut insights = ActivityInsights::default();
+    insights.record_session(at(2025, 3, 1, 19, 0));
+
+    assert_eq!(insights.preferred_hour_near(18), 18);
+}
+
+#[test]
+fn slot_moves_to_usual_activity_hour() {
+    let insights = evening_user();
+
+    assert_eq!(insights.preferred_hour_near(18), 19);
+    assert_eq!(insights.preferred_hour_near(20), 19);
+}
+
+#[test]
+fn slot_only_moves_within_search_radius() {
+    let insights = evening_user();
+
+    // 19:00 is three hours from noon, so noon has no better candidate
+    assert_eq!(insights.preferred_hour_near(12), 12);
+}
+
+#[test]
+fn ties_prefer_the_closest_then_earliest_hour() {
+    let mut insights = ActivityInsights::default();
+    for day in 1..=10 {
+        insights.record_session(at(2025, 3, day, 17, 0));
+        insights.record_session(at(2025, 3, day, 19, 0));
+    }
+
+    assert_eq!(insights.preferred_hour_near(18), 17);
+    assert_eq!(insights.preferred_hour_near(19), 19);
+}
+
+#[test]
+fn history_older_than_retention_is_dropped() {
+    let mut insights = evening_user();
+    insights.record_session(at(2025, 3, 14, 9, 0) + Duration::days(40));
+
+    assert_eq!(insights.days.len(), 1);
+    assert_eq!(insights.preferred_hour_near(18), 18);
+}
+
+#[test]
+fn answered_reminders_never_back_off() {
+    let mut insights = ActivityInsights::default();
+    for day in 1..=6 {
+        insights.record_reminder_sent("streak_protection", at(2025, 3, day, 18, 0));
+        insights.record_session(at(2025, 3, day, 18, 30));
+    }
+
+    assert!(!insights.is_backed_off("streak_protection", at(2025, 3, 7, 18, 0)));
+    assert_eq!(insights.engagement["streak_protection"].engaged, 6);
+}
+
+#[test]
+fn ignored_reminders_back_off_exponentially() {
+    let mut insights = ActivityInsights::default();
+    for day in 1..=3 {
+        insights.record_reminder_sent("streak_protection", at(2025, 3, day, 18, 0));
+    }
+
+    // Third ignored reminder resolves once its window closes: wait two days
+    assert!(insights.is_backed_off("streak_protection", at(2025, 3, 4, 18, 0)));
+    assert!(!insights.is_backed_off("streak_protection", at(2025, 3, 5, 18, 0)));
+
+    insights.record_reminder_sent("streak_protection", at(2025, 3, 5, 18, 0));
+    // Fourth ignored: wait four days
+    assert!(insights.is_backed_off("streak_protection", at(2025, 3, 8, 18, 0)));
+    assert!(!insights.is_backed_off("streak_protection", at(2025, 3, 9, 18, 0)));
+}
+
+#[test]
+fn activity_after_window_does_not_count_as_engagement() {
+    let mut insights = ActivityInsights::default();
+    insights.record_reminder_sent("streak_protection", at(2025, 3, 1, 18, 0));
+    insights.record_session(at(2025, 3, 1, 21, 0));
+
+    let engagement = &insights.engagement["streak_protection"];
+    assert_eq!(engagement.engaged, 0);
+    assert_eq!(engagement.consecutive_ignored, 1);
+}
+
+#[test]
+fn engagement_resets_backoff() {
+    let mut insights = ActivityInsights::default();
+    for day in 1..=4 {
+        insights.record_reminder_sent("streak_protection", at(2025, 3, day, 18, 0));
+    }
+    insights.record_reminder_sent("streak_protection", at(2025, 3, 9, 18, 0));
+    insights.record_completion(at(2025, 3, 9, 18, 45));
+
+    assert!(!insights.is_backed_off("streak_protection", at(2025, 3, 10, 18, 0)));
+}
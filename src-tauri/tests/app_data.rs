@@ -0,0 +1,132 @@
+use chrono::{Local, TimeZone};
+use habitquest_lib::app_data::{AppData, AppDataPatch, Completion, Rarity};
+use habitquest_lib::habit_streaks::HabitFrequency;
+use serde_json::json;
+
+/// `StoredData` as the frontend serializes it
+fn stored_data() -> serde_json::Value {
+    json!({
+        "habits": [
+            {
+                "id": "read",
+                "title": "Read",
+                "frequency": "weekly",
+                "category": "LEARNING",
+                "xpOnComplete": 20,
+                "streak": 2,
+                "bestStreak": 4,
+                "lastCompletedAt": "2025-06-09T18:00:00.000Z",
+                "completions": {
+                    "2025-W24": "2025-06-09T18:00:00.000Z",
+                    "2025-W23": "2025-06-02T18:00:00.000Z",
+                    "2025-W20": true
+                },
+                "isRecurring": true,
+                "specificDate": null,
+                "icon": "book"
+            },
+            {
+                "id": "dentist",
+                "title": "Dentist",
+                "frequency": "daily",
+                "category": "HEALTH",
+                "xpOnComplete": 30,
+                "streak": 0,
+                "bestStreak": 0,
+                "lastCompletedAt": null,
+                "completions": {},
+                "isRecurring": false,
+                "specificDate": "2025-06-20"
+            }
+        ],
+        "points": 100,
+        "totalXP": 50,
+        "goals": { "LEARNING": { "monthlyTargetXP": 200 } },
+        "inventory": [{ "id": "movie", "name": "Movie night", "cost": 120, "rarity": "epic", "redeemedAt": "2025-06-07T20:00:00.000Z" }],
+        "shop": [{ "id": "movie", "name": "Movie night", "cost": 120, "rarity": "epic" }],
+        "categories": ["LEARNING", "HEALTH"],
+        "achievements": [],
+        "userStats": {
+            "totalHabitsCompleted": 3,
+            "longestStreak": 4,
+            "totalDaysActive": 3,
+            "favoriteCategory": "LEARNING",
+            "weeklyAverage": 1.5,
+            "monthlyAverage": 3
+        },
+        "settings": { "theme": "auto", "language": "fr", "startOfWeek": 1 },
+        "version": "3.3.0"
+    })
+}
+
+#[test]
+fn round_trips_the_frontends_stored_data() {
+    let data: AppData = serde_json::from_value(stored_data()).unwrap();
+    assert_eq!(data.habits[0].completions["2025-W20"], Completion::Legacy(true));
+    assert_eq!(data.habits[1].specific_date.unwrap().to_string(), "2025-06-20");
+    assert_eq!(data.shop[0].rarity, Some(Rarity::Epic));
+    assert_eq!(data.settings.language, "fr");
+
+    let json = serde_json::to_value(&data).unwrap();
+    assert_eq!(json["totalXP"], 50);
+    assert_eq!(json["habits"][0]["xpOnComplete"], 20);
+    assert_eq!(json["habits"][0]["completions"]["2025-W20"], true);
+    assert!(json["habits"][1].get("description").is_none());
+    assert_eq!(serde_json::from_value::<AppData>(json).unwrap(), data);
+}
+
+#[test]
+fn patches_replace_only_the_fields_they_carry() {
+    let mut data: AppData = serde_json::from_value(stored_data()).unwrap();
+    let patch: AppDataPatch = serde_json::from_value(json!({ "points": 40, "totalXP": 70, "habits": [] })).unwrap();
+
+    patch.apply(&mut data);
+    assert_eq!((data.points, data.total_xp, data.habits.len()), (40, 70, 0));
+    assert_eq!(data.shop.len(), 1);
+    assert_eq!(data.settings.language, "fr");
+}
+
+#[test]
+fn habits_give_the_background_service_their_streaks_and_progress() {
+    let data: AppData = serde_json::from_value(stored_data()).unwrap();
+
+    let streaks = data.habit_streaks();
+    assert_eq!((streaks[0].habit_name.as_str(), streaks[0].frequency), ("Read", HabitFrequency::Weekly));
+    assert_eq!(streaks[0].completions.len(), 2);
+    assert!(streaks[0].completions[0] < streaks[0].completions[1]);
+    assert!(!streaks[1].is_recurring && streaks[1].specific_date.is_some());
+
+    let progress = data.progress();
+    assert_eq!(progress.goals["LEARNING"].monthly_target_xp, 200);
+    assert_eq!(progress.inventory[0].name, "Movie night");
+}
+
+#[test]
+fn habits_need_unique_ids() {
+    let mut data: AppData = serde_json::from_value(stored_data()).unwrap();
+    assert!(data.validate().is_ok());
+
+    data.habits[1].id = "read".to_string();
+    assert_eq!(data.validate().unwrap_err(), "More than one habit has the id read");
+
+    data.habits[1].id = " ".to_string();
+    assert!(data.validate().unwrap_err().contains("Dentist"));
+}
+
+#[test]
+fn completing_a_habit_counts_it_once_per_period() {
+    let mut data: AppData = serde_json::from_value(stored_data()).unwrap();
+    let monday = Local.with_ymd_and_hms(2025, 6, 16, 8, 0, 0).unwrap();
+
+    assert_eq!(data.complete_habit("read", monday), Ok(true));
+    let read = &data.habits[0];
+    assert_eq!((read.streak, read.best_streak, read.last_completed_at), (3, 4, Some(monday)));
+    assert_eq!(read.completions["2025-W25"], Completion::At(monday));
+    assert_eq!((data.total_xp, data.points), (70, 140));
+
+    // Later the same week changes nothing
+    assert_eq!(data.complete_habit("read", monday + chrono::Duration::days(3)), Ok(false));
+    assert_eq!((data.habits[0].streak, data.total_xp), (3, 70));
+
+    assert_eq!(data.complete_habit("walk", monday), Err("No habit has the id walk".to_string()));
+}
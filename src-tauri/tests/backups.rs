@@ -0,0 +1,193 @@
+mod common;
+
+use chrono::{DateTime, Duration, Local};
+use common::at;
+use habitquest_lib::app_data::{AppData, DATABASE_FILE};
+use habitquest_lib::app_settings::{AppSettings, APP_SETTINGS_SCHEMA};
+use habitquest_lib::backups::{
+    create_backup, expired_backups, needs_migration, read_backups, remove_backup, restore_files, validate_backup, Backup,
+    BackupReason, DataDirs, BACKUPS_DIR, RETENTION,
+};
+use habitquest_lib::event_log::{events_between, EventLog, EVENT_LOG_FILE, SNAPSHOT_FILE};
+use habitquest_lib::notification_history::{NotificationHistory, NOTIFICATION_HISTORY_SCHEMA};
+use habitquest_lib::persistence::{self, load_json, save_json};
+use habitquest_lib::storage::{SqliteStorage, Storage};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::fs;
+
+/// Fresh config and data directories under the system temp dir for one test
+fn temp_dirs(name: &str) -> DataDirs {
+    let root = common::temp_dir(name);
+    let dirs = DataDirs { config: root.join("config"), data: root.join("data") };
+    fs::create_dir_all(&dirs.config).unwrap();
+    fs::create_dir_all(&dirs.data).unwrap();
+    dirs
+}
+
+fn settings(language: &str) -> AppSettings {
+    AppSettings { language: language.to_string(), ..AppSettings::default() }
+}
+
+fn data(total_xp: u32) -> AppData {
+    AppData { total_xp, points: total_xp, ..AppData::default() }
+}
+
+/// Settings, a database and an event log holding `data(total_xp)`
+fn write_live_files(dirs: &DataDirs, language: &str, total_xp: u32) {
+    save_json(&dirs.config.join("app_settings.json"), &settings(language), &APP_SETTINGS_SCHEMA).unwrap();
+    SqliteStorage::open(&dirs.data.join(DATABASE_FILE)).unwrap().save_app_data(&data(total_xp), at(2025, 7, 1, 3, 0)).unwrap();
+
+    let mut log = EventLog::open(&dirs.data).unwrap();
+    let before = log.replay().unwrap();
+    log.append(at(2025, 7, 1, 3, 0), events_between(before.as_ref(), &data(total_xp))).unwrap();
+}
+
+#[test]
+fn restore_brings_back_every_file_as_it_was() {
+    let dirs = temp_dirs("restore");
+    assert!(create_backup(&dirs, BackupReason::Scheduled, at(2025, 7, 1, 3, 0)).unwrap().is_none());
+
+    write_live_files(&dirs, "de", 120);
+    let backup = create_backup(&dirs, BackupReason::Scheduled, at(2025, 7, 1, 3, 0)).unwrap().unwrap();
+    assert_eq!(backup.files.len(), 3);
+    assert_eq!(read_backups(&dirs).unwrap(), vec![backup.clone()]);
+
+    // Changes since, including a file the backup doesn't have
+    write_live_files(&dirs, "fr", 500);
+    let mut log = EventLog::open(&dirs.data).unwrap();
+    log.save_snapshot(Some(&data(500))).unwrap();
+
+    let validated = validate_backup(&dirs, &backup.id).unwrap();
+    restore_files(&dirs, &validated).unwrap();
+
+    let restored_settings = load_json::<AppSettings>(&dirs.config.join("app_settings.json"), &APP_SETTINGS_SCHEMA).unwrap().unwrap();
+    assert_eq!(restored_settings.value.language, "de");
+    let storage = SqliteStorage::open(&dirs.data.join(DATABASE_FILE)).unwrap();
+    assert_eq!(storage.load_app_data().unwrap(), Some(data(120)));
+    assert_eq!(EventLog::open(&dirs.data).unwrap().replay().unwrap(), Some(data(120)));
+    assert!(!dirs.data.join(SNAPSHOT_FILE).exists());
+}
+
+#[test]
+fn restoring_a_backup_from_before_a_file_existed_leaves_nothing_to_load() {
+    let dirs = temp_dirs("restore-missing");
+    write_live_files(&dirs, "de", 120);
+    let backup = create_backup(&dirs, BackupReason::Scheduled, at(2025, 7, 1, 3, 0)).unwrap().unwrap();
+
+    // Saved twice, so a last-known-good copy exists as well
+    let history_path = dirs.config.join("notification_history.json");
+    for _ in 0..2 {
+        save_json(&history_path, &NotificationHistory::default(), &NOTIFICATION_HISTORY_SCHEMA).unwrap();
+    }
+    assert!(persistence::backup_path(&history_path).exists());
+
+    restore_files(&dirs, &validate_backup(&dirs, &backup.id).unwrap()).unwrap();
+
+    // Loading finds nothing, so the reloaded store keeps its defaults
+    assert!(load_json::<NotificationHistory>(&history_path, &NOTIFICATION_HISTORY_SCHEMA).unwrap().is_none());
+    assert!(!persistence::backup_path(&history_path).exists());
+}
+
+#[test]
+fn a_restore_replaces_nothing_until_every_file_is_copied() {
+    let dirs = temp_dirs("restore-staged");
+    write_live_files(&dirs, "de", 120);
+    let backup = validate_backup(&dirs, &create_backup(&dirs, BackupReason::Scheduled, at(2025, 7, 1, 3, 0)).unwrap().unwrap().id).unwrap();
+
+    // Saved twice, so the last-known-good copy is newer than the backup too
+    let settings_path = dirs.config.join("app_settings.json");
+    for _ in 0..2 {
+        write_live_files(&dirs, "fr", 500);
+    }
+    let language = || load_json::<AppSettings>(&settings_path, &APP_SETTINGS_SCHEMA).unwrap().unwrap().value.language;
+
+    // A file the backup loses after it's checked stops the restore before any is replaced
+    let log_copy = dirs.backups().join(&backup.id).join("data").join(EVENT_LOG_FILE);
+    let log_contents = fs::read(&log_copy).unwrap();
+    fs::remove_file(&log_copy).unwrap();
+    assert!(restore_files(&dirs, &backup).is_err());
+    assert_eq!(language(), "fr");
+    assert_eq!(EventLog::open(&dirs.data).unwrap().replay().unwrap(), Some(data(500)));
+    for dir in [&dirs.config, &dirs.data] {
+        assert!(fs::read_dir(dir).unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().ends_with(".restoring")));
+    }
+
+    fs::write(&log_copy, log_contents).unwrap();
+    restore_files(&dirs, &backup).unwrap();
+    assert_eq!(language(), "de");
+    assert!(!persistence::backup_path(&settings_path).exists());
+}
+
+#[test]
+fn damaged_backups_are_rejected_before_anything_is_replaced() {
+    let dirs = temp_dirs("damaged");
+    write_live_files(&dirs, "de", 120);
+    let backup = create_backup(&dirs, BackupReason::BeforeImport, at(2025, 7, 1, 3, 0)).unwrap().unwrap();
+
+    let copy = dirs.backups().join(&backup.id).join("data").join(DATABASE_FILE);
+    let size = fs::metadata(&copy).unwrap().len() as usize;
+    fs::write(&copy, vec![0x42; size]).unwrap();
+    let error = validate_backup(&dirs, &backup.id).unwrap_err().to_string();
+    assert!(error.contains(DATABASE_FILE), "{}", error);
+
+    fs::write(&copy, b"short").unwrap();
+    let error = validate_backup(&dirs, &backup.id).unwrap_err().to_string();
+    assert!(error.contains("bytes"), "{}", error);
+
+    // Only backups can be named
+    assert!(validate_backup(&dirs, "../config").is_err());
+    assert!(validate_backup(&dirs, BACKUPS_DIR).is_err());
+
+    remove_backup(&dirs, &backup.id).unwrap();
+    assert!(read_backups(&dirs).unwrap().is_empty());
+}
+
+fn backup(id: &str, created_at: DateTime<Local>, reason: BackupReason) -> Backup {
+    Backup { id: id.to_string(), created_at, reason, app_version: "4.2.0".to_string(), files: Vec::new() }
+}
+
+#[test]
+fn retention_keeps_the_last_days_weeks_and_months() {
+    // Daily from 2024-01-01 to Monday 2025-02-03, and a few imports along the way
+    let mut backups: Vec<Backup> = (0..400)
+        .map(|day| {
+            let created_at = at(2024, 1, 1, 3, 0) + Duration::days(day);
+            backup(&created_at.format("%Y-%m-%d").to_string(), created_at, BackupReason::Scheduled)
+        })
+        .collect();
+    backups.extend((1..=8).map(|n| backup(&format!("import-{}", n), at(2024, n, 15, 3, 0), BackupReason::BeforeImport)));
+
+    let expired: HashSet<String> = expired_backups(&backups, &RETENTION).into_iter().collect();
+    let kept: HashSet<&str> = backups.iter().map(|b| b.id.as_str()).filter(|id| !expired.contains(*id)).collect();
+
+    let daily = (28..=31).map(|day| format!("2025-01-{:02}", day)).chain((1..=3).map(|day| format!("2025-02-{:02}", day)));
+    let weekly = ["2025-01-26", "2025-01-19"].map(String::from);
+    let monthly = ["2024-12-31", "2024-11-30", "2024-10-31", "2024-09-30", "2024-08-31", "2024-07-31", "2024-06-30", "2024-05-31", "2024-04-30", "2024-03-31"]
+        .map(String::from);
+    let imports = (4..=8).map(|n| format!("import-{}", n));
+    let expected: HashSet<String> = daily.chain(weekly).chain(monthly).chain(imports).collect();
+
+    assert_eq!(kept, expected.iter().map(String::as_str).collect());
+}
+
+#[test]
+fn files_from_older_schemas_need_a_backup_before_loading() {
+    let dirs = temp_dirs("migration");
+    assert!(!needs_migration(&dirs));
+
+    // Settings from before the schema envelope
+    let settings_path = dirs.config.join("app_settings.json");
+    fs::write(&settings_path, r#"{"language": "de"}"#).unwrap();
+    assert!(needs_migration(&dirs));
+    save_json(&settings_path, &settings("de"), &APP_SETTINGS_SCHEMA).unwrap();
+    assert!(!needs_migration(&dirs));
+
+    // A database older than the first migration
+    let database_path = dirs.data.join(DATABASE_FILE);
+    Connection::open(&database_path).unwrap().execute_batch("CREATE TABLE early (id INTEGER)").unwrap();
+    assert!(needs_migration(&dirs));
+    fs::remove_file(&database_path).unwrap();
+    SqliteStorage::open(&database_path).unwrap();
+    assert!(!needs_migration(&dirs));
+}
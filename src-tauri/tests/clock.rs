@@ -0,0 +1,94 @@
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use habitquest_lib::clock::{simulated_clock_from_args, ClockDrift, DriftDetector, SimulatedNow};
+use std::time::Instant;
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn parses_signed_offsets() {
+    assert_eq!(SimulatedNow::parse("+36h"), Ok(SimulatedNow::Offset(Duration::hours(36))));
+    assert_eq!(SimulatedNow::parse("-2d"), Ok(SimulatedNow::Offset(Duration::days(-2))));
+    assert_eq!(SimulatedNow::parse("+90m"), Ok(SimulatedNow::Offset(Duration::minutes(90))));
+}
+
+#[test]
+fn parses_frozen_datetimes_with_or_without_seconds() {
+    let expected = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(1, 30, 0).unwrap();
+
+    assert_eq!(SimulatedNow::parse("2025-03-30T01:30"), Ok(SimulatedNow::Frozen(expected)));
+    assert_eq!(SimulatedNow::parse("2025-03-30T01:30:00"), Ok(SimulatedNow::Frozen(expected)));
+}
+
+#[test]
+fn rejects_malformed_values() {
+    assert!(SimulatedNow::parse("+").is_err());
+    assert!(SimulatedNow::parse("+3w").is_err());
+    assert!(SimulatedNow::parse("+xh").is_err());
+    assert!(SimulatedNow::parse("tomorrow").is_err());
+    assert!(SimulatedNow::parse("++5h").is_err());
+    assert!(SimulatedNow::parse("+-5h").is_err());
+}
+
+#[test]
+fn rejects_multibyte_units_and_out_of_range_offsets() {
+    assert!(SimulatedNow::parse("+5é").is_err());
+    assert!(SimulatedNow::parse("+é").is_err());
+    assert!(SimulatedNow::parse("+9223372036854775807d").is_err());
+    assert!(SimulatedNow::parse("-9223372036854775807m").is_err());
+
+    // In range for a duration, but not once added to the current time
+    let far = SimulatedNow::parse("+100000000d").unwrap();
+    assert!(far.into_clock().is_err());
+}
+
+#[test]
+fn frozen_clock_comes_from_the_flag() {
+    let clock = simulated_clock_from_args(args(&["habitquest", "--simulate-now=2025-06-02T18:00"]))
+        .unwrap()
+        .unwrap();
+    let expected = Local.with_ymd_and_hms(2025, 6, 2, 18, 0, 0).earliest().unwrap();
+
+    assert_eq!(clock.now(), expected);
+    assert_eq!(clock.now(), expected);
+}
+
+#[test]
+fn offset_clock_runs_ahead_of_system_time() {
+    let clock = simulated_clock_from_args(args(&["habitquest", "--simulate-now=+2d"]))
+        .unwrap()
+        .unwrap();
+    let ahead = clock.now() - Local::now();
+
+    assert!(ahead > Duration::hours(47) && ahead <= Duration::days(2), "{:?}", ahead);
+}
+
+#[test]
+fn no_flag_means_no_simulated_clock() {
+    assert!(simulated_clock_from_args(args(&["habitquest", "--autostart"])).unwrap().is_none());
+    assert!(simulated_clock_from_args(args(&["habitquest", "--simulate-now=soon"])).is_err());
+}
+
+#[test]
+fn drift_detector_reports_suspend_and_clock_changes() {
+    let wall = Local.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap();
+    let monotonic = Instant::now();
+    let mut detector = DriftDetector::new(wall, monotonic);
+
+    // A minute of both: timer jitter at most
+    let minute = std::time::Duration::from_secs(60);
+    assert_eq!(detector.check(wall + Duration::seconds(61), monotonic + minute), None);
+
+    // Eight hours of wall time in one minute of monotonic time: the lid was closed
+    assert_eq!(
+        detector.check(wall + Duration::seconds(61) + Duration::hours(8), monotonic + minute * 2),
+        Some(ClockDrift::Forward(Duration::hours(8) - Duration::minutes(1)))
+    );
+
+    // Clock set back an hour
+    assert_eq!(
+        detector.check(wall + Duration::seconds(121) + Duration::hours(7), monotonic + minute * 3),
+        Some(ClockDrift::Backward(Duration::hours(1)))
+    );
+}
@@ -0,0 +1,43 @@
+//! Helpers shared by the integration tests. Each test crate declares `mod common;` and uses
+//! only some of them.
+#![allow(dead_code)]
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use habitquest_lib::habit_streaks::{HabitFrequency, HabitStreak};
+use std::fs;
+use std::path::PathBuf;
+
+/// Local time on the given day; the earlier one if a DST change repeats it
+pub fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(year, month, day, hour, minute, 0).earliest().unwrap()
+}
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// Fresh directory under the system temp dir for one test
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("habitquest-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A recurring daily habit named `name` worth 10 XP, with its id the lowercased name, never
+/// completed. Tests set what they need with struct update syntax:
+/// `HabitStreak { streak: 5, ..habit("Read") }`.
+pub fn habit(name: &str) -> HabitStreak {
+    HabitStreak {
+        habit_id: name.to_lowercase(),
+        habit_name: name.to_string(),
+        frequency: HabitFrequency::Daily,
+        streak: 0,
+        last_completed_at: None,
+        is_recurring: true,
+        specific_date: None,
+        xp_on_complete: 10,
+        category: String::new(),
+        completions: Vec::new(),
+    }
+}
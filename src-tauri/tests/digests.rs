@@ -0,0 +1,49 @@
+mod common;
+
+use common::{at, habit};
+use habitquest_lib::digests::{EveningRecap, MorningDigest, RecapStreak};
+use habitquest_lib::habit_streaks::HabitStreak;
+
+/// Wednesday: Meditate done this morning, Read's streak still open, Water never done
+fn habits() -> Vec<HabitStreak> {
+    vec![
+        HabitStreak { streak: 46, last_completed_at: Some(at(2025, 6, 4, 7, 0)), ..habit("Meditate") },
+        HabitStreak { xp_on_complete: 15, streak: 5, last_completed_at: Some(at(2025, 6, 3, 21, 0)), ..habit("Read") },
+        HabitStreak { xp_on_complete: 5, ..habit("Water") },
+    ]
+}
+
+#[test]
+fn morning_digest_lists_due_habits_and_their_xp() {
+    let digest = MorningDigest::build(&habits(), at(2025, 6, 4, 8, 30)).unwrap();
+
+    assert_eq!(digest.due_habits, vec!["Read", "Water"]);
+    assert_eq!(digest.xp_available, 20);
+    assert_eq!(digest.notification_text("en").1, "2 habits today, worth 20 XP: Read, Water");
+}
+
+#[test]
+fn morning_digest_is_skipped_when_nothing_is_due() {
+    let done = vec![HabitStreak { streak: 46, last_completed_at: Some(at(2025, 6, 4, 7, 0)), ..habit("Meditate") }];
+    assert_eq!(MorningDigest::build(&done, at(2025, 6, 4, 8, 30)), None);
+}
+
+#[test]
+fn evening_recap_counts_progress_and_streak_changes() {
+    // Water was marked done from a notification, which completes it in the app data too
+    let mut habits = habits();
+    habits[2].streak = 1;
+    habits[2].last_completed_at = Some(at(2025, 6, 4, 12, 0));
+
+    let recap = EveningRecap::build(&habits, at(2025, 6, 4, 20, 30)).unwrap();
+
+    assert_eq!(recap.completed, vec!["Meditate", "Water"]);
+    assert_eq!(recap.remaining, vec!["Read"]);
+    assert_eq!(recap.xp_earned, 15);
+    assert_eq!(recap.streaks_extended, vec![RecapStreak { habit_name: "Meditate".to_string(), streak: 46 }]);
+    assert_eq!(recap.streaks_at_risk, vec![RecapStreak { habit_name: "Read".to_string(), streak: 5 }]);
+    assert_eq!(
+        recap.notification_text("en").1,
+        "2 of 3 habits done, 15 XP earned\n🔥 Streaks extended: Meditate (46)\n⚠️ Streaks ending tonight: Read (5)"
+    );
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use common::temp_dir;
+use habitquest_lib::encryption::{
+    self, open_with, seal_with, DataKey, EncryptionStatus, InterruptedChange, KdfParams, KeyFile, LockedError, Sealed,
+    WrongPassphraseError,
+};
+use habitquest_lib::persistence::{backup_path, load_json, reseal_file, save_json, unchanged, Schema};
+use std::fs;
+use std::sync::Mutex;
+
+const SCHEMA: Schema = Schema { migrations: &[unchanged] };
+
+/// The key state is process-wide, so tests that enable, lock or unlock take turns
+static KEY_STATE: Mutex<()> = Mutex::new(());
+
+/// The smallest costs Argon2 accepts, so tests don't spend seconds deriving keys
+fn cheap_kdf() -> KdfParams {
+    KdfParams::with_costs(8, 1, 1)
+}
+
+#[test]
+fn sealed_data_opens_only_unchanged_and_with_its_key() {
+    let key = DataKey::generate();
+    let sealed = seal_with(&key, b"streak: 12");
+    assert_eq!(open_with(&key, &sealed).unwrap().as_slice(), b"streak: 12");
+
+    // Same plaintext, fresh nonce
+    assert_ne!(seal_with(&key, b"streak: 12").ciphertext, sealed.ciphertext);
+
+    let mut ciphertext = BASE64.decode(&sealed.ciphertext).unwrap();
+    ciphertext[0] ^= 1;
+    let tampered = Sealed { ciphertext: BASE64.encode(ciphertext), ..sealed.clone() };
+    assert!(open_with(&key, &tampered).is_err());
+
+    assert!(open_with(&DataKey::generate(), &sealed).is_err());
+}
+
+#[test]
+fn key_file_rejects_a_wrong_passphrase() {
+    let (key_file, key) = KeyFile::create("correct horse", cheap_kdf()).unwrap();
+
+    let keys = key_file.unlock("correct horse").unwrap();
+    assert_eq!(keys[0].id(), key.id());
+
+    let err = key_file.unlock("battery staple").unwrap_err();
+    assert!(err.is::<WrongPassphraseError>());
+    assert_eq!(err.to_string(), "Wrong passphrase");
+}
+
+#[test]
+fn passphrases_shorter_than_the_minimum_are_refused() {
+    assert!(KeyFile::create("short", cheap_kdf()).is_err());
+}
+
+#[test]
+fn locked_files_cannot_be_read_or_written() {
+    let _guard = KEY_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("locked");
+    let path = dir.join("data.json");
+    encryption::init(&dir);
+
+    save_json(&path, &vec![1, 2], &SCHEMA).unwrap();
+    encryption::enable(&dir, "correct horse", cheap_kdf()).unwrap();
+    reseal_file(&path).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(encryption::is_sealed(&contents));
+    assert!(!contents.contains("[1,2]") && !contents.contains("\"version\""));
+
+    encryption::lock();
+    assert_eq!(encryption::status(), EncryptionStatus::Locked);
+    assert!(load_json::<Vec<u32>>(&path, &SCHEMA).unwrap_err().is::<LockedError>());
+    assert!(save_json(&path, &vec![3], &SCHEMA).unwrap_err().is::<LockedError>());
+    // Nothing was overwritten while locked
+    assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+
+    assert!(encryption::unlock(&dir, "battery staple").unwrap_err().is::<WrongPassphraseError>());
+    assert!(encryption::is_locked());
+
+    assert_eq!(encryption::unlock(&dir, "correct horse").unwrap(), None);
+    assert_eq!(load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap().value, vec![1, 2]);
+
+    encryption::finish_disabling(&dir).unwrap();
+}
+
+#[test]
+fn passphrase_change_reencrypts_with_a_new_key() {
+    let _guard = KEY_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("change");
+    let path = dir.join("data.json");
+    encryption::init(&dir);
+
+    encryption::enable(&dir, "correct horse", cheap_kdf()).unwrap();
+    save_json(&path, &vec![1], &SCHEMA).unwrap();
+    save_json(&path, &vec![1, 2], &SCHEMA).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+    let before = fs::read_to_string(&path).unwrap();
+
+    assert!(encryption::change_passphrase(&dir, "wrong guess", "battery staple", cheap_kdf())
+        .unwrap_err()
+        .is::<WrongPassphraseError>());
+    encryption::change_passphrase(&dir, "correct horse", "battery staple", cheap_kdf()).unwrap();
+
+    // Interrupted before re-encryption: both keys still open the data
+    encryption::lock();
+    assert_eq!(encryption::unlock(&dir, "battery staple").unwrap(), Some(InterruptedChange::Reseal));
+    assert_eq!(load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap().value, vec![1, 2]);
+
+    reseal_file(&path).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+    assert_ne!(fs::read_to_string(&path).unwrap(), before);
+    assert!(encryption::is_sealed(&fs::read_to_string(backup_path(&path)).unwrap()));
+
+    encryption::lock();
+    assert!(encryption::unlock(&dir, "correct horse").unwrap_err().is::<WrongPassphraseError>());
+    assert_eq!(encryption::unlock(&dir, "battery staple").unwrap(), None);
+    assert_eq!(load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap().value, vec![1, 2]);
+
+    encryption::finish_disabling(&dir).unwrap();
+}
+
+#[test]
+fn disabling_writes_everything_back_in_plain_text() {
+    let _guard = KEY_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("disable");
+    let path = dir.join("data.json");
+    encryption::init(&dir);
+
+    encryption::enable(&dir, "correct horse", cheap_kdf()).unwrap();
+    save_json(&path, &vec![1], &SCHEMA).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+    encryption::lock();
+
+    assert!(encryption::begin_disabling(&dir, "battery staple").unwrap_err().is::<WrongPassphraseError>());
+    encryption::begin_disabling(&dir, "correct horse").unwrap();
+    reseal_file(&path).unwrap();
+    encryption::finish_disabling(&dir).unwrap();
+
+    assert_eq!(encryption::status(), EncryptionStatus::Disabled);
+    assert!(!dir.join(encryption::KEY_FILE).exists());
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(!encryption::is_sealed(&contents));
+    assert_eq!(load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap().value, vec![1]);
+}
+
+/// Two data files, for changes cut short after the first is converted
+fn files(dir: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    (dir.join("first.json"), dir.join("second.json"))
+}
+
+#[test]
+fn an_interrupted_enable_is_finished_at_the_next_unlock() {
+    let _guard = KEY_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("interrupted-enable");
+    let (first, second) = files(&dir);
+    encryption::init(&dir);
+    save_json(&first, &vec![1], &SCHEMA).unwrap();
+    save_json(&second, &vec![2], &SCHEMA).unwrap();
+
+    encryption::enable(&dir, "correct horse", cheap_kdf()).unwrap();
+    reseal_file(&first).unwrap();
+
+    // Restarted: the key file locks the data, and unlocking finds the unfinished re-encryption
+    assert_eq!(encryption::init(&dir), EncryptionStatus::Locked);
+    assert_eq!(encryption::unlock(&dir, "correct horse").unwrap(), Some(InterruptedChange::Reseal));
+    assert_eq!(load_json::<Vec<u32>>(&first, &SCHEMA).unwrap().unwrap().value, vec![1]);
+    assert_eq!(load_json::<Vec<u32>>(&second, &SCHEMA).unwrap().unwrap().value, vec![2]);
+
+    reseal_file(&second).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+    assert!(encryption::is_sealed(&fs::read_to_string(&second).unwrap()));
+    encryption::lock();
+    assert_eq!(encryption::unlock(&dir, "correct horse").unwrap(), None);
+
+    encryption::finish_disabling(&dir).unwrap();
+}
+
+#[test]
+fn an_interrupted_passphrase_change_is_finished_at_the_next_unlock() {
+    let _guard = KEY_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("interrupted-rekey");
+    let (first, second) = files(&dir);
+    encryption::init(&dir);
+    encryption::enable(&dir, "correct horse", cheap_kdf()).unwrap();
+    save_json(&first, &vec![1], &SCHEMA).unwrap();
+    save_json(&second, &vec![2], &SCHEMA).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+
+    encryption::change_passphrase(&dir, "correct horse", "battery staple", cheap_kdf()).unwrap();
+    reseal_file(&first).unwrap();
+
+    // Only the new passphrase unlocks, with the old key kept for the file still sealed with it
+    assert_eq!(encryption::init(&dir), EncryptionStatus::Locked);
+    assert!(encryption::unlock(&dir, "correct horse").unwrap_err().is::<WrongPassphraseError>());
+    assert_eq!(encryption::unlock(&dir, "battery staple").unwrap(), Some(InterruptedChange::Reseal));
+    assert_eq!(load_json::<Vec<u32>>(&first, &SCHEMA).unwrap().unwrap().value, vec![1]);
+    assert_eq!(load_json::<Vec<u32>>(&second, &SCHEMA).unwrap().unwrap().value, vec![2]);
+    assert!(encryption::change_passphrase(&dir, "battery staple", "tr0ub4dor&3", cheap_kdf()).is_err());
+
+    reseal_file(&second).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+    encryption::lock();
+    assert_eq!(encryption::unlock(&dir, "battery staple").unwrap(), None);
+    assert_eq!(load_json::<Vec<u32>>(&second, &SCHEMA).unwrap().unwrap().value, vec![2]);
+
+    encryption::finish_disabling(&dir).unwrap();
+}
+
+#[test]
+fn an_interrupted_disable_is_finished_at_the_next_unlock() {
+    let _guard = KEY_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("interrupted-disable");
+    let (first, second) = files(&dir);
+    encryption::init(&dir);
+    encryption::enable(&dir, "correct horse", cheap_kdf()).unwrap();
+    save_json(&first, &vec![1], &SCHEMA).unwrap();
+    save_json(&second, &vec![2], &SCHEMA).unwrap();
+    encryption::finish_resealing(&dir).unwrap();
+
+    encryption::begin_disabling(&dir, "correct horse").unwrap();
+    reseal_file(&first).unwrap();
+    assert!(!encryption::is_sealed(&fs::read_to_string(&first).unwrap()));
+
+    // Unlocking goes back to saving in plain text, rather than sealing the decrypted file again
+    assert_eq!(encryption::init(&dir), EncryptionStatus::Locked);
+    assert_eq!(encryption::unlock(&dir, "correct horse").unwrap(), Some(InterruptedChange::Disable));
+    assert!(!encryption::is_sealing());
+    assert_eq!(load_json::<Vec<u32>>(&first, &SCHEMA).unwrap().unwrap().value, vec![1]);
+    assert_eq!(load_json::<Vec<u32>>(&second, &SCHEMA).unwrap().unwrap().value, vec![2]);
+
+    reseal_file(&second).unwrap();
+    encryption::finish_disabling(&dir).unwrap();
+    assert_eq!(encryption::init(&dir), EncryptionStatus::Disabled);
+    assert!(!encryption::is_sealed(&fs::read_to_string(&second).unwrap()));
+    assert_eq!(load_json::<Vec<u32>>(&second, &SCHEMA).unwrap().unwrap().value, vec![2]);
+}
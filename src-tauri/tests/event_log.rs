@@ -0,0 +1,171 @@
+mod common;
+
+use common::{at, temp_dir};
+use habitquest_lib::app_data::{AppData, Completion, InventoryItem};
+use habitquest_lib::event_log::{events_between, Event, EventLog, EVENT_LOG_FILE};
+use serde_json::json;
+use std::fs;
+
+fn app_data() -> AppData {
+    let habit = |id: &str| {
+        json!({
+            "id": id, "title": id, "frequency": "daily", "category": "HEALTH", "xpOnComplete": 10,
+            "streak": 1, "bestStreak": 1, "completions": { "2025-07-01": at(2025, 7, 1, 7, 0) }, "isRecurring": true
+        })
+    };
+    serde_json::from_value(json!({
+        "habits": [habit("stretch"), habit("read")],
+        "points": 100,
+        "totalXP": 20,
+        "shop": [{ "id": "tea", "name": "Tea", "cost": 30 }],
+        "settings": { "language": "en" }
+    }))
+    .unwrap()
+}
+
+/// The next day's save from the frontend: "stretch" completed and a reward redeemed
+fn next_day(before: &AppData) -> AppData {
+    let mut after = before.clone();
+    let stretch = &mut after.habits[0];
+    stretch.completions.insert("2025-07-02".to_string(), Completion::At(at(2025, 7, 2, 7, 0)));
+    stretch.streak = 2;
+    stretch.best_streak = 2;
+    after.total_xp += 10;
+    // 20 points for the completion, less the 30 the tea cost
+    after.points -= 10;
+    after.inventory.insert(0, InventoryItem {
+        id: Some("tea".to_string()),
+        name: "Tea".to_string(),
+        cost: 30,
+        redeemed_at: at(2025, 7, 2, 8, 0),
+        description: None,
+        icon: None,
+        rarity: None,
+    });
+    after
+}
+
+#[test]
+fn saves_become_the_events_that_explain_them() {
+    let before = app_data();
+    let after = next_day(&before);
+
+    let events = events_between(Some(&before), &after);
+    let kinds: Vec<_> = events.iter().map(|event| serde_json::to_value(event).unwrap()["type"].clone()).collect();
+    assert_eq!(kinds, vec!["completion_toggled", "habit_edited", "reward_redeemed", "xp_granted"]);
+    assert_eq!(events[3], Event::XpGranted { xp: 10, points: -10 });
+    assert!(events_between(Some(&after), &after).is_empty());
+
+    // Reordering habits has no event of its own, so the save is logged whole
+    let mut reordered = after.clone();
+    reordered.habits.reverse();
+    assert!(matches!(events_between(Some(&after), &reordered)[..], [Event::DataReplaced { .. }]));
+}
+
+#[test]
+fn replays_from_the_latest_snapshot() {
+    let dir = temp_dir("replay");
+    let first = app_data();
+    let second = next_day(&first);
+    let mut third = second.clone();
+    third.habits.remove(1);
+    third.settings.language = "fr".to_string();
+
+    let mut log = EventLog::open(&dir).unwrap();
+    assert_eq!(log.replay().unwrap(), None);
+    log.append(at(2025, 7, 1, 9, 0), events_between(None, &first)).unwrap();
+    log.append(at(2025, 7, 2, 9, 0), events_between(Some(&first), &second)).unwrap();
+    log.save_snapshot(Some(&second)).unwrap();
+    log.append(at(2025, 7, 3, 9, 0), vec![Event::ActivityRecorded]).unwrap();
+    log.append(at(2025, 7, 3, 9, 0), events_between(Some(&second), &third)).unwrap();
+
+    let reopened = EventLog::open(&dir).unwrap();
+    assert_eq!(reopened.last_seq(), 8);
+    assert_eq!(reopened.replay().unwrap(), Some(third));
+    assert_eq!(reopened.events_after(5).unwrap().iter().map(|event| event.seq).collect::<Vec<_>>(), vec![6, 7, 8]);
+
+    // The snapshot only saves work: the log alone gives the same result
+    fs::remove_file(dir.join("events_snapshot.json")).unwrap();
+    assert_eq!(EventLog::open(&dir).unwrap().replay().unwrap(), reopened.replay().unwrap());
+}
+
+#[test]
+fn snapshots_keep_every_event_and_replay_reads_from_their_offset() {
+    let dir = temp_dir("snapshot-offset");
+    let path = dir.join(EVENT_LOG_FILE);
+    let first = app_data();
+    let second = next_day(&first);
+
+    let mut log = EventLog::open(&dir).unwrap();
+    log.append(at(2025, 7, 1, 9, 0), events_between(None, &first)).unwrap();
+    log.save_snapshot(Some(&first)).unwrap();
+    log.append(at(2025, 7, 2, 9, 0), events_between(Some(&first), &second)).unwrap();
+    let before_snapshot = fs::read_to_string(&path).unwrap();
+    log.save_snapshot(Some(&second)).unwrap();
+
+    // Snapshots leave the log as it was, with the whole history
+    assert_eq!(fs::read_to_string(&path).unwrap(), before_snapshot);
+    let seqs: Vec<_> = log.events_after(0).unwrap().iter().map(|event| event.seq).collect();
+    assert_eq!(seqs, vec![1, 2, 3, 4, 5]);
+    assert_eq!(log.replay_until(1).unwrap(), Some(first.clone()));
+    assert!(log.replay_until(6).is_err());
+
+    // Replay starts at the snapshot's offset, so what it covers isn't read again
+    fs::write(&path, before_snapshot.replace(|c: char| c != '\n', "x")).unwrap();
+    let mut reopened = EventLog::open(&dir).unwrap();
+    assert_eq!(reopened.last_seq(), 5);
+    assert_eq!(reopened.replay().unwrap(), Some(second.clone()));
+    assert!(reopened.events_after(0).is_err());
+
+    reopened.append(at(2025, 7, 3, 9, 0), vec![Event::XpGranted { xp: 5, points: 5 }]).unwrap();
+    let mut third = second;
+    third.total_xp += 5;
+    third.points += 5;
+    assert_eq!(EventLog::open(&dir).unwrap().replay().unwrap(), Some(third));
+}
+
+#[test]
+fn replaying_to_an_earlier_event_gives_the_data_to_revert_to() {
+    let dir = temp_dir("revert");
+    let first = app_data();
+    let second = next_day(&first);
+
+    let mut log = EventLog::open(&dir).unwrap();
+    log.append(at(2025, 7, 1, 9, 0), events_between(None, &first)).unwrap();
+    log.append(at(2025, 7, 2, 9, 0), events_between(Some(&first), &second)).unwrap();
+    log.save_snapshot(Some(&second)).unwrap();
+    log.append(at(2025, 7, 2, 10, 0), vec![Event::ActivityRecorded]).unwrap();
+
+    // Undoing the second day appends the changes back, keeping what it undoes in the log
+    let reverted = log.replay_until(1).unwrap().unwrap();
+    assert_eq!(reverted, first);
+    let undo = events_between(Some(&second), &reverted);
+    assert!(undo.contains(&Event::XpGranted { xp: -10, points: 10 }));
+    log.append(at(2025, 7, 2, 11, 0), undo).unwrap();
+
+    let reopened = EventLog::open(&dir).unwrap();
+    assert_eq!(reopened.replay().unwrap(), Some(first));
+    assert_eq!(reopened.replay_until(6).unwrap(), Some(second));
+    assert_eq!(reopened.events_after(0).unwrap().len() as u64, reopened.last_seq());
+}
+
+#[test]
+fn drops_an_append_cut_short_but_not_corruption() {
+    let dir = temp_dir("torn");
+    let path = dir.join(EVENT_LOG_FILE);
+    let mut log = EventLog::open(&dir).unwrap();
+    log.append(at(2025, 7, 1, 9, 0), vec![Event::ActivityRecorded, Event::ActivityRecorded]).unwrap();
+
+    let complete = fs::read_to_string(&path).unwrap();
+    fs::write(&path, format!("{}{{\"seq\":3,\"at\":", complete)).unwrap();
+    let mut log = EventLog::open(&dir).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), complete);
+
+    log.append(at(2025, 7, 1, 10, 0), vec![Event::HabitCompletionRecorded { habit_id: "read".to_string() }]).unwrap();
+    assert_eq!(log.events_after(0).unwrap().last().unwrap().seq, 3);
+
+    let mut lines: Vec<_> = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect();
+    lines[1] = "not json".to_string();
+    fs::write(&path, lines.join("\n") + "\n").unwrap();
+    assert!(EventLog::open(&dir).is_err());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use chrono::Weekday;
+use common::{at, date};
+use habitquest_lib::habit_reminders::{self, HabitReminder};
+
+fn reminder(habit_id: &str, hour: u32, minute: u32) -> HabitReminder {
+    HabitReminder {
+        id: String::new(),
+        habit_id: habit_id.to_string(),
+        habit_name: None,
+        hour,
+        minute,
+        weekdays: Vec::new(),
+        enabled: true,
+    }
+}
+
+#[test]
+fn new_reminders_get_an_id_unless_they_have_one() {
+    let now = at(2025, 6, 4, 9, 0);
+
+    let created = reminder("read", 7, 30).prepare_new(now).unwrap();
+    assert_eq!(created.id, format!("reminder_read_{}", now.timestamp_millis()));
+
+    let named = HabitReminder { id: "morning".to_string(), ..reminder("read", 7, 30) };
+    assert_eq!(named.prepare_new(now).unwrap().id, "morning");
+}
+
+#[test]
+fn reminders_need_a_habit_and_a_valid_time() {
+    let now = at(2025, 6, 4, 9, 0);
+
+    assert!(reminder("", 7, 30).prepare_new(now).is_err());
+    assert!(reminder("read", 24, 0).prepare_new(now).is_err());
+    assert!(reminder("read", 7, 60).prepare_new(now).is_err());
+    assert!(reminder("read", 23, 59).validate().is_ok());
+}
+
+#[test]
+fn upserting_replaces_the_reminder_with_the_same_id() {
+    let mut reminders = Vec::new();
+    let morning = HabitReminder { id: "morning".to_string(), ..reminder("read", 7, 30) };
+    let evening = HabitReminder { id: "evening".to_string(), ..reminder("read", 21, 0) };
+
+    habit_reminders::upsert(&mut reminders, morning.clone());
+    habit_reminders::upsert(&mut reminders, evening.clone());
+    habit_reminders::upsert(&mut reminders, HabitReminder { hour: 8, ..morning.clone() });
+
+    assert_eq!(reminders, vec![HabitReminder { hour: 8, ..morning }, evening]);
+}
+
+#[test]
+fn removing_reports_whether_the_reminder_existed() {
+    let mut reminders = vec![HabitReminder { id: "morning".to_string(), ..reminder("read", 7, 30) }];
+
+    assert!(!habit_reminders::remove(&mut reminders, "evening"));
+    assert!(habit_reminders::remove(&mut reminders, "morning"));
+    assert!(reminders.is_empty());
+}
+
+#[test]
+fn reminders_fire_on_their_weekdays_only() {
+    let weekdays = HabitReminder { weekdays: vec![Weekday::Mon, Weekday::Wed], ..reminder("read", 7, 30) };
+
+    // Wednesday, then Thursday
+    assert_eq!(weekdays.occurrence_on(date(2025, 6, 4)), Some(at(2025, 6, 4, 7, 30)));
+    assert_eq!(weekdays.occurrence_on(date(2025, 6, 5)), None);
+    assert_eq!(reminder("read", 7, 30).occurrence_on(date(2025, 6, 5)), Some(at(2025, 6, 5, 7, 30)));
+    assert_eq!(HabitReminder { enabled: false, ..weekdays }.occurrence_on(date(2025, 6, 4)), None);
+}
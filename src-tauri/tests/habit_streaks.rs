@@ -0,0 +1,86 @@
+mod common;
+
+use common::{at, date, habit};
+use habitquest_lib::background_notifications::Reminder;
+use habitquest_lib::habit_streaks::{HabitFrequency, HabitStreak};
+use habitquest_lib::quiet_hours::QuietHours;
+
+#[test]
+fn warning_stages_scale_with_streak_length() {
+    let stages = |streak| HabitStreak { streak, ..habit("Meditate") }.warning_stages(3);
+
+    assert_eq!(stages(2), 0);
+    assert_eq!(stages(3), 2);
+    assert_eq!(stages(6), 3);
+    assert_eq!(stages(12), 4);
+    assert_eq!(stages(400), 4);
+}
+
+#[test]
+fn periods_follow_the_frontend_period_keys() {
+    // Wednesday 2025-06-04
+    let now = at(2025, 6, 4, 15, 0);
+    let bounds = |frequency: HabitFrequency| frequency.period_bounds(now).unwrap();
+
+    assert_eq!(bounds(HabitFrequency::Daily), (at(2025, 6, 4, 0, 0), at(2025, 6, 5, 0, 0)));
+    assert_eq!(bounds(HabitFrequency::Weekly), (at(2025, 6, 2, 0, 0), at(2025, 6, 9, 0, 0)));
+    assert_eq!(bounds(HabitFrequency::Monthly), (at(2025, 6, 1, 0, 0), at(2025, 7, 1, 0, 0)));
+    assert_eq!(bounds(HabitFrequency::Yearly), (at(2025, 1, 1, 0, 0), at(2026, 1, 1, 0, 0)));
+    assert_eq!(
+        HabitFrequency::Monthly.period_bounds(at(2025, 12, 31, 23, 0)).unwrap().1,
+        at(2026, 1, 1, 0, 0)
+    );
+}
+
+#[test]
+fn period_keys_match_the_frontend() {
+    let key = |frequency: HabitFrequency, year, month, day| frequency.period_key(date(year, month, day));
+
+    assert_eq!(key(HabitFrequency::Daily, 2025, 6, 9), "2025-06-09");
+    assert_eq!(key(HabitFrequency::Weekly, 2025, 6, 9), "2025-W24");
+    // ISO weeks belong to the year of their Thursday
+    assert_eq!(key(HabitFrequency::Weekly, 2024, 12, 30), "2025-W01");
+    assert_eq!(key(HabitFrequency::Monthly, 2025, 6, 9), "2025-06");
+    assert_eq!(key(HabitFrequency::Yearly, 2025, 6, 9), "2025");
+}
+
+#[test]
+fn only_streaks_extended_last_period_are_at_risk() {
+    let weekly = HabitStreak { frequency: HabitFrequency::Weekly, streak: 10, ..habit("Meditate") };
+    let now = at(2025, 6, 6, 12, 0);
+
+    // Completed last week: at risk until Monday
+    assert_eq!(weekly.at_risk_until(Some(at(2025, 5, 28, 9, 0)), now), Some(at(2025, 6, 9, 0, 0)));
+    // Already completed this week
+    assert_eq!(weekly.at_risk_until(Some(at(2025, 6, 2, 9, 0)), now), None);
+    // Missed last week, so the streak is already gone
+    assert_eq!(weekly.at_risk_until(Some(at(2025, 5, 20, 9, 0)), now), None);
+    assert_eq!(weekly.at_risk_until(None, now), None);
+}
+
+#[test]
+fn warnings_in_quiet_hours_move_before_them() {
+    let quiet_hours = QuietHours::from_active_window(8, 22).unwrap();
+    let times = HabitStreak { streak: 3, ..habit("Meditate") }.warning_times(3, &quiet_hours, at(2025, 6, 5, 0, 0));
+
+    assert_eq!(times, vec![at(2025, 6, 4, 20, 0), at(2025, 6, 4, 21, 45)]);
+}
+
+#[test]
+fn warning_text_names_the_streak_and_time_left() {
+    let reminder = |hours_left, final_warning| Reminder::StreakAtRisk {
+        habit_id: "meditate".to_string(),
+        habit_name: "Meditation".to_string(),
+        frequency: HabitFrequency::Daily,
+        streak: 45,
+        hours_left,
+        final_warning,
+    };
+
+    let (title, body) = reminder(2, true).notification_text("en");
+    assert!(title.contains("Last Chance"));
+    assert_eq!(body, "Your 45-day \"Meditation\" streak ends in 2 hours!");
+
+    let (_, body) = reminder(72, false).notification_text("de");
+    assert_eq!(body, "Deine 45-Tage-Serie „Meditation“ endet in 3 Tagen. Lass sie nicht abreißen!");
+}
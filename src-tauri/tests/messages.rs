@@ -0,0 +1,91 @@
+use habitquest_lib::app_settings::AppSettings;
+use habitquest_lib::background_notifications::Reminder;
+use habitquest_lib::messages::{list, message, render, resolve_locale, template, SUPPORTED_LOCALES};
+use habitquest_lib::notification_actions::NotificationAction;
+use serde_json::json;
+
+fn items(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn placeholders_are_filled_in_by_name() {
+    assert_eq!(render("{habit} for {streak} days, {habit}!", &[("habit", "Read"), ("streak", "5")]), "Read for 5 days, Read!");
+    // Unknown placeholders are kept, unused parameters ignored
+    assert_eq!(render("{count} left in {unit}", &[("count", "2"), ("xp", "10")]), "2 left in {unit}");
+
+    assert_eq!(
+        message("de", "habit_reminder.body", &[("habit", "Lesen")]),
+        "Zeit für „Lesen“! Halte deine Serie am Leben. ✅"
+    );
+}
+
+#[test]
+fn missing_locales_and_keys_fall_back_to_english() {
+    assert_eq!(resolve_locale("es-MX"), "es");
+    assert_eq!(resolve_locale("FR_ca"), "fr");
+    assert_eq!(resolve_locale("it"), "en");
+
+    assert_eq!(template("it", "habit_reminder.title"), "⏰ HabitQuest - Habit Reminder");
+    assert_eq!(template("it", "action.open"), "Open");
+    assert_eq!(message("pt-BR", "list.more", &[("count", "2")]), "+2 more");
+    assert_eq!(template("de", "no.such.key"), "HabitQuest");
+}
+
+#[test]
+fn reminders_are_worded_in_the_locale() {
+    let reminder = Reminder::HabitSpecific {
+        reminder_id: "morning".to_string(),
+        habit_id: "read".to_string(),
+        habit_name: Some("Leer".to_string()),
+    };
+    assert_eq!(
+        reminder.notification_text("es"),
+        (
+            "⏰ HabitQuest - Recordatorio de hábito".to_string(),
+            "¡Es hora de \"Leer\"! Mantén viva tu racha. ✅".to_string()
+        )
+    );
+
+    let (title, body) = Reminder::StreakProtection { hours_inactive: 20 }.notification_text("fr");
+    assert_eq!(title, "🎯 HabitQuest - Protection de la série");
+    assert!(body.starts_with("Cela fait 20 heures"));
+}
+
+#[test]
+fn the_locale_follows_the_language_from_the_frontend() {
+    let settings: AppSettings = serde_json::from_value(json!({ "theme": "dark", "language": "de-DE" })).unwrap();
+    assert_eq!(settings.locale(), "de");
+
+    let unset: AppSettings = serde_json::from_value(json!({ "theme": "dark" })).unwrap();
+    assert_eq!(unset.locale(), "en");
+}
+
+#[test]
+fn action_buttons_are_translated_in_every_locale() {
+    let keys = ["action.mark_done", "action.snooze", "action.open"];
+    for locale in SUPPORTED_LOCALES.iter().filter(|locale| **locale != "en") {
+        for key in keys {
+            assert_ne!(template(locale, key), template("en", key), "{} {}", locale, key);
+        }
+    }
+}
+
+#[test]
+fn lists_name_three_items_and_count_the_rest_per_locale() {
+    assert_eq!(list("en", items(&["Water", "Read"])), "Water, Read");
+    assert_eq!(list("en", items(&["Water", "Walk", "Read"])), "Water, Walk, Read");
+    assert_eq!(list("en", items(&["Water", "Walk", "Read", "Write", "Run"])), "Water, Walk, Read, +2 more");
+    assert_eq!(list("es", items(&["Agua", "Paseo", "Leer", "Escribir"])), "Agua, Paseo, Leer, +1 más");
+    assert_eq!(list("fr", items(&["Eau", "Marche", "Lire", "Écrire"])), "Eau, Marche, Lire, +1 autres");
+    assert_eq!(list("de", items(&["Wasser", "Gehen", "Lesen", "Schreiben"])), "Wasser, Gehen, Lesen, +1 weitere");
+    assert_eq!(list("en", Vec::new()), "");
+}
+
+#[test]
+fn action_buttons_are_labelled_in_the_locale() {
+    assert_eq!(NotificationAction::MarkDone.label("en"), "Mark done");
+    assert_eq!(NotificationAction::Snooze.label("es"), "Posponer 1 h");
+    assert_eq!(NotificationAction::Open.label("fr"), "Ouvrir");
+    assert_eq!(NotificationAction::MarkDone.label("de-AT"), "Erledigt");
+}
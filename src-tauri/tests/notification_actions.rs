@@ -0,0 +1,161 @@
+mod common;
+
+use chrono::{DateTime, Duration, Local};
+use common::{at, temp_dir};
+use habitquest_lib::app_data::AppData;
+use habitquest_lib::background_notifications::{ActivityData, Reminder, SnoozedReminder, ACTIVITY_DATA_SCHEMA};
+use habitquest_lib::notification_actions::{
+    handle_action, ActionPrompt, NotificationAction, NotificationActionHandler, SNOOZE_MINUTES,
+};
+use habitquest_lib::persistence::{load_json, save_json};
+use serde_json::json;
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+
+/// Handler applying actions the way `AppActionHandler` does, to plain data and a file
+struct TestHandler {
+    app_data: RefCell<AppData>,
+    activity_path: PathBuf,
+    windows_opened: Cell<u32>,
+    now: DateTime<Local>,
+}
+
+impl TestHandler {
+    fn new(name: &str) -> Self {
+        let app_data = serde_json::from_value(json!({
+            "habits": [{
+                "id": "meditate",
+                "title": "Meditate",
+                "frequency": "daily",
+                "category": "HEALTH",
+                "xpOnComplete": 10,
+                "streak": 4,
+                "bestStreak": 4,
+                "lastCompletedAt": at(2025, 6, 3, 7, 0),
+                "completions": { "2025-06-03": at(2025, 6, 3, 7, 0) },
+                "isRecurring": true,
+                "specificDate": null
+            }],
+            "points": 0,
+            "totalXP": 0
+        }))
+        .unwrap();
+        Self {
+            app_data: RefCell::new(app_data),
+            activity_path: temp_dir(name).join("activity_data.json"),
+            windows_opened: Cell::new(0),
+            now: at(2025, 6, 4, 12, 0),
+        }
+    }
+
+    fn snoozed(&self) -> Vec<SnoozedReminder> {
+        load_json::<ActivityData>(&self.activity_path, &ACTIVITY_DATA_SCHEMA)
+            .unwrap()
+            .map(|loaded| loaded.value.snoozed_reminders)
+            .unwrap_or_default()
+    }
+}
+
+impl NotificationActionHandler for TestHandler {
+    fn complete_habit(&self, habit_id: &str) -> Result<(), String> {
+        self.app_data.borrow_mut().complete_habit(habit_id, self.now).map(|_| ())
+    }
+
+    fn snooze(&self, reminder: Reminder, until: DateTime<Local>) -> Result<(), String> {
+        let mut activity = ActivityData { snoozed_reminders: self.snoozed(), ..ActivityData::default() };
+        activity.snoozed_reminders.push(SnoozedReminder { reminder, due: until });
+        save_json(&self.activity_path, &activity, &ACTIVITY_DATA_SCHEMA).map_err(|e| e.to_string())
+    }
+
+    fn open_main_window(&self) -> Result<(), String> {
+        self.windows_opened.set(self.windows_opened.get() + 1);
+        Ok(())
+    }
+}
+
+fn habit_reminder(habit_id: &str) -> Reminder {
+    Reminder::HabitSpecific {
+        reminder_id: format!("{}_reminder", habit_id),
+        habit_id: habit_id.to_string(),
+        habit_name: None,
+    }
+}
+
+#[test]
+fn mark_done_completes_the_reminders_habit() {
+    let handler = TestHandler::new("mark-done");
+
+    handle_action(&handler, NotificationAction::MarkDone, &habit_reminder("meditate"), handler.now).unwrap();
+
+    let data = handler.app_data.borrow();
+    assert_eq!((data.habits[0].streak, data.habits[0].last_completed_at), (5, Some(handler.now)));
+    assert_eq!(data.total_xp, 10);
+}
+
+#[test]
+fn mark_done_needs_a_habit() {
+    let handler = TestHandler::new("mark-done-without-habit");
+    let reminder = Reminder::StreakProtection { hours_inactive: 20 };
+
+    assert_eq!(NotificationAction::for_reminder(&reminder), vec![NotificationAction::Snooze, NotificationAction::Open]);
+    assert_eq!(
+        handle_action(&handler, NotificationAction::MarkDone, &reminder, handler.now),
+        Err("This notification is not tied to a habit".to_string())
+    );
+    assert_eq!(
+        handle_action(&handler, NotificationAction::MarkDone, &habit_reminder("walk"), handler.now),
+        Err("No habit has the id walk".to_string())
+    );
+    assert_eq!(handler.app_data.borrow().total_xp, 0);
+}
+
+#[test]
+fn snooze_saves_the_reminder_for_an_hour_later() {
+    let handler = TestHandler::new("snooze");
+
+    handle_action(&handler, NotificationAction::Snooze, &habit_reminder("meditate"), handler.now).unwrap();
+
+    let snoozed = handler.snoozed();
+    assert_eq!(snoozed.len(), 1);
+    assert_eq!(snoozed[0].reminder, habit_reminder("meditate"));
+    assert_eq!(snoozed[0].due, handler.now + Duration::minutes(SNOOZE_MINUTES));
+    assert_eq!(handler.app_data.borrow().habits[0].streak, 4);
+}
+
+#[test]
+fn open_only_shows_the_window() {
+    let handler = TestHandler::new("open");
+
+    handle_action(&handler, NotificationAction::Open, &habit_reminder("meditate"), handler.now).unwrap();
+
+    assert_eq!(handler.windows_opened.get(), 1);
+    assert!(handler.snoozed().is_empty());
+    assert_eq!(handler.app_data.borrow().habits[0].streak, 4);
+}
+
+#[test]
+fn action_ids_round_trip() {
+    for action in NotificationAction::for_reminder(&habit_reminder("meditate")) {
+        assert_eq!(NotificationAction::from_id(action.id()), Some(action));
+    }
+    assert_eq!(NotificationAction::from_id("__closed"), None);
+}
+
+#[test]
+fn prompts_for_the_webview_carry_the_reminder_and_labelled_buttons() {
+    let prompt = ActionPrompt::new("Meditate", "Time for a few quiet minutes", &habit_reminder("meditate"), "es");
+    let buttons: Vec<_> = prompt.actions.iter().map(|button| (button.action, button.label.as_str())).collect();
+    assert_eq!(
+        buttons,
+        vec![
+            (NotificationAction::MarkDone, "Marcar como hecho"),
+            (NotificationAction::Snooze, "Posponer 1 h"),
+            (NotificationAction::Open, "Abrir"),
+        ]
+    );
+
+    // The webview hands the reminder back with the button clicked
+    let json = serde_json::to_value(&prompt).unwrap();
+    assert_eq!(json["actions"][1]["action"], "snooze");
+    assert_eq!(serde_json::from_value::<Reminder>(json["reminder"].clone()).unwrap(), habit_reminder("meditate"));
+}
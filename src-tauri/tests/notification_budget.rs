@@ -0,0 +1,62 @@
+mod common;
+
+use chrono::Duration;
+use common::at;
+use habitquest_lib::notification_budget::{BudgetCategory, BudgetLimits, NotificationBudgets};
+
+fn limits(streak_protection: u32) -> BudgetLimits {
+    BudgetLimits { streak_protection, ..BudgetLimits::default() }
+}
+
+#[test]
+fn sends_count_for_24_hours_not_until_midnight() {
+    let mut budgets = NotificationBudgets::default();
+    budgets.record(BudgetCategory::StreakProtection, at(2025, 6, 2, 22, 0));
+
+    assert!(!budgets.has_room(BudgetCategory::StreakProtection, &limits(1), at(2025, 6, 3, 0, 30)));
+    assert!(!budgets.has_room(BudgetCategory::StreakProtection, &limits(1), at(2025, 6, 3, 21, 59)));
+    assert!(budgets.has_room(BudgetCategory::StreakProtection, &limits(1), at(2025, 6, 3, 22, 0)));
+}
+
+#[test]
+fn categories_have_separate_budgets() {
+    let mut budgets = NotificationBudgets::default();
+    budgets.record(BudgetCategory::StreakProtection, at(2025, 6, 2, 12, 0));
+    budgets.record(BudgetCategory::StreakProtection, at(2025, 6, 2, 18, 0));
+
+    assert_eq!(budgets.remaining(BudgetCategory::StreakProtection, &limits(2), at(2025, 6, 2, 20, 0)), 0);
+    assert_eq!(budgets.remaining(BudgetCategory::Digest, &limits(2), at(2025, 6, 2, 20, 0)), 2);
+    assert_eq!(budgets.sent_in_window(BudgetCategory::HabitSpecific, at(2025, 6, 2, 20, 0)), 0);
+}
+
+#[test]
+fn recording_drops_sends_outside_the_window() {
+    let mut budgets = NotificationBudgets::default();
+    budgets.record(BudgetCategory::Digest, at(2025, 6, 2, 8, 0));
+    budgets.record(BudgetCategory::StreakProtection, at(2025, 6, 3, 12, 0));
+
+    assert!(!budgets.sent.contains_key(&BudgetCategory::Digest));
+    assert_eq!(budgets.sent[&BudgetCategory::StreakProtection], vec![at(2025, 6, 3, 12, 0)]);
+}
+
+#[test]
+fn budget_state_survives_serialization() {
+    let mut budgets = NotificationBudgets::default();
+    budgets.record(BudgetCategory::Achievement, at(2025, 6, 2, 9, 0));
+    budgets.record(BudgetCategory::Achievement, at(2025, 6, 2, 9, 0) + Duration::minutes(5));
+
+    let json = serde_json::to_string(&budgets).unwrap();
+    let restored: NotificationBudgets = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored, budgets);
+    assert_eq!(restored.sent_in_window(BudgetCategory::Achievement, at(2025, 6, 2, 10, 0)), 2);
+}
+
+#[test]
+fn daily_cap_maps_onto_reminder_categories() {
+    let limits = BudgetLimits::from_daily_cap(4);
+
+    assert_eq!(limits.limit(BudgetCategory::StreakProtection), 4);
+    assert_eq!(limits.limit(BudgetCategory::Random), 4);
+    assert_eq!(limits.limit(BudgetCategory::HabitSpecific), BudgetLimits::default().habit_specific);
+}
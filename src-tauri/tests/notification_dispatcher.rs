@@ -0,0 +1,119 @@
+use chrono::{DateTime, Local, TimeZone};
+use habitquest_lib::notification_budget::BudgetCategory;
+use habitquest_lib::notification_dispatcher::{dispatch, DispatchPolicy};
+use habitquest_lib::notification_history::{DeliveryResult, NotificationKind};
+use habitquest_lib::notification_sink::{MemoryNotificationSink, OutgoingNotification};
+use std::cell::{Cell, RefCell};
+
+/// Policy with fixed quiet hours and a plain counter as the budget
+struct TestPolicy {
+    quiet: bool,
+    budget: u32,
+    used: Cell<u32>,
+    recorded: RefCell<Vec<(String, DeliveryResult, Option<String>)>>,
+}
+
+impl TestPolicy {
+    fn new(quiet: bool, budget: u32) -> Self {
+        Self {
+            quiet,
+            budget,
+            used: Cell::new(0),
+            recorded: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn results(&self) -> Vec<DeliveryResult> {
+        self.recorded.borrow().iter().map(|(_, result, _)| *result).collect()
+    }
+}
+
+impl DispatchPolicy for TestPolicy {
+    fn is_quiet_time(&self, _now: DateTime<Local>) -> bool {
+        self.quiet
+    }
+
+    fn budget_available(&self, _category: BudgetCategory, _now: DateTime<Local>) -> bool {
+        self.used.get() < self.budget
+    }
+
+    fn consume_budget(&self, _category: BudgetCategory, _now: DateTime<Local>) {
+        self.used.set(self.used.get() + 1);
+    }
+
+    fn record(&self, notification: &OutgoingNotification, result: DeliveryResult, error: Option<String>) {
+        self.recorded.borrow_mut().push((notification.title.clone(), result, error));
+    }
+}
+
+fn now() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2025, 6, 2, 18, 0, 0).earliest().unwrap()
+}
+
+fn reminder(title: &str) -> OutgoingNotification {
+    OutgoingNotification::new(NotificationKind::StreakProtection, "test", title, "body")
+        .with_budget(Some(BudgetCategory::StreakProtection))
+}
+
+#[test]
+fn delivers_and_records_history() {
+    let sink = MemoryNotificationSink::default();
+    let policy = TestPolicy::new(false, 3);
+
+    assert_eq!(dispatch(&sink, &policy, &reminder("first"), now()), Ok(DeliveryResult::Delivered));
+    assert_eq!(sink.delivered().len(), 1);
+    assert_eq!(sink.delivered()[0].title, "first");
+    assert_eq!(policy.results(), vec![DeliveryResult::Delivered]);
+    assert_eq!(policy.used.get(), 1);
+}
+
+#[test]
+fn quiet_hours_suppress_delivery() {
+    let sink = MemoryNotificationSink::default();
+    let policy = TestPolicy::new(true, 3);
+
+    assert_eq!(dispatch(&sink, &policy, &reminder("late"), now()), Ok(DeliveryResult::Suppressed));
+    assert!(sink.delivered().is_empty());
+    assert_eq!(policy.results(), vec![DeliveryResult::Suppressed]);
+    assert_eq!(policy.used.get(), 0);
+}
+
+#[test]
+fn requested_notifications_ignore_quiet_hours() {
+    let sink = MemoryNotificationSink::default();
+    let policy = TestPolicy::new(true, 3);
+    let test = OutgoingNotification::new(NotificationKind::Test, "test", "enabled", "body").ignoring_quiet_hours();
+
+    assert_eq!(dispatch(&sink, &policy, &test, now()), Ok(DeliveryResult::Delivered));
+    assert_eq!(sink.delivered().len(), 1);
+}
+
+#[test]
+fn budget_only_limits_budgeted_notifications() {
+    let sink = MemoryNotificationSink::default();
+    let policy = TestPolicy::new(false, 1);
+    let scheduled = reminder("scheduled").with_budget(None);
+
+    assert_eq!(dispatch(&sink, &policy, &reminder("one"), now()), Ok(DeliveryResult::Delivered));
+    assert_eq!(dispatch(&sink, &policy, &reminder("two"), now()), Ok(DeliveryResult::Suppressed));
+    assert_eq!(dispatch(&sink, &policy, &scheduled, now()), Ok(DeliveryResult::Delivered));
+
+    let titles: Vec<_> = sink.delivered().into_iter().map(|n| n.title).collect();
+    assert_eq!(titles, vec!["one", "scheduled"]);
+}
+
+#[test]
+fn failed_delivery_is_recorded_and_not_charged() {
+    let sink = MemoryNotificationSink::failing("daemon unavailable");
+    let policy = TestPolicy::new(false, 3);
+
+    assert_eq!(
+        dispatch(&sink, &policy, &reminder("broken"), now()),
+        Err("daemon unavailable".to_string())
+    );
+    assert_eq!(
+        policy.recorded.borrow()[0],
+        ("broken".to_string(), DeliveryResult::Failed, Some("daemon unavailable".to_string()))
+    );
+    assert_eq!(policy.used.get(), 0);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use chrono::Duration;
+use common::at;
+use habitquest_lib::background_notifications::Reminder;
+use habitquest_lib::notification_history::{
+    DeliveryResult, HistoryQuery, NotificationHistory, NotificationKind, DEFAULT_PAGE_SIZE, MAX_HISTORY_ENTRIES,
+};
+
+/// A delivered streak reminder every minute from 08:00 on 2025-06-04, with every tenth one failed
+fn history(count: usize) -> NotificationHistory {
+    let mut history = NotificationHistory::default();
+    for i in 0..count {
+        let (result, error) = if i % 10 == 9 {
+            (DeliveryResult::Failed, Some("No notification daemon".to_string()))
+        } else {
+            (DeliveryResult::Delivered, None)
+        };
+        let sent_at = at(2025, 6, 4, 8, 0) + Duration::minutes(i as i64);
+        history.record(sent_at, NotificationKind::StreakProtection, "Inactive", format!("Reminder {}", i), result, error);
+    }
+    history
+}
+
+#[test]
+fn history_keeps_the_newest_entries() {
+    let page = history(MAX_HISTORY_ENTRIES + 5).query(&HistoryQuery { limit: Some(1000), ..HistoryQuery::default() });
+
+    assert_eq!(page.total, MAX_HISTORY_ENTRIES);
+    assert_eq!(page.entries.len(), MAX_HISTORY_ENTRIES);
+    assert_eq!(page.entries.first().unwrap().id, MAX_HISTORY_ENTRIES as u64 + 4);
+    assert_eq!(page.entries.last().unwrap().id, 5);
+}
+
+#[test]
+fn history_pages_newest_first() {
+    let history = history(120);
+
+    let first = history.query(&HistoryQuery::default());
+    assert_eq!(first.total, 120);
+    assert_eq!(first.entries.len(), DEFAULT_PAGE_SIZE);
+    assert_eq!(first.entries[0].title, "Reminder 119");
+
+    let last = history.query(&HistoryQuery { offset: 100, limit: Some(30), ..HistoryQuery::default() });
+    assert_eq!(last.total, 120);
+    let ids: Vec<u64> = last.entries.iter().map(|entry| entry.id).collect();
+    assert_eq!(ids, (0..20).rev().collect::<Vec<u64>>());
+}
+
+#[test]
+fn history_filters_by_kind_result_and_time() {
+    let mut history = history(30);
+    history.record(at(2025, 6, 4, 9, 0), NotificationKind::Startup, "Launched", "Ready", DeliveryResult::Suppressed, None);
+
+    let kind = history.query(&HistoryQuery { kind: Some(NotificationKind::Startup), ..HistoryQuery::default() });
+    assert_eq!((kind.total, kind.entries[0].trigger.as_str()), (1, "Launched"));
+
+    let failed = history.query(&HistoryQuery { result: Some(DeliveryResult::Failed), ..HistoryQuery::default() });
+    let titles: Vec<&str> = failed.entries.iter().map(|entry| entry.title.as_str()).collect();
+    assert_eq!(titles, vec!["Reminder 29", "Reminder 19", "Reminder 9"]);
+    assert_eq!(failed.entries[0].error.as_deref(), Some("No notification daemon"));
+
+    // `since` is inclusive and `until` exclusive
+    let window = history.query(&HistoryQuery {
+        since: Some(at(2025, 6, 4, 8, 10)),
+        until: Some(at(2025, 6, 4, 8, 20)),
+        ..HistoryQuery::default()
+    });
+    assert_eq!(window.total, 10);
+    assert_eq!(window.entries.first().unwrap().title, "Reminder 19");
+    assert_eq!(window.entries.last().unwrap().title, "Reminder 10");
+}
+
+#[test]
+fn queries_from_the_frontend_default_their_paging() {
+    let query: HistoryQuery = serde_json::from_str(r#"{ "kind": "habit_specific", "result": "suppressed" }"#).unwrap();
+
+    assert_eq!(query.kind, Some(NotificationKind::HabitSpecific));
+    assert_eq!(query.result, Some(DeliveryResult::Suppressed));
+    assert_eq!((query.offset, query.limit), (0, None));
+}
+
+#[test]
+fn reminders_record_their_kind_and_why_they_fired() {
+    let reminder = Reminder::HabitSpecific {
+        reminder_id: "morning".to_string(),
+        habit_id: "read".to_string(),
+        habit_name: None,
+    };
+
+    assert_eq!(NotificationKind::from(&reminder), NotificationKind::HabitSpecific);
+    assert_eq!(reminder.trigger_reason(), "Scheduled reminder morning for habit read");
+    assert_eq!(
+        Reminder::StreakProtection { hours_inactive: 20 }.trigger_reason(),
+        "No activity for 20 hours and no habit completed today"
+    );
+}
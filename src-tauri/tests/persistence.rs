@@ -0,0 +1,88 @@
+mod common;
+
+use common::temp_dir;
+use habitquest_lib::persistence::{backup_path, decode, load_json, save_json, unchanged, Schema, StorageRecovery};
+use std::fs;
+
+const SCHEMA: Schema = Schema { migrations: &[unchanged] };
+
+#[test]
+fn save_keeps_the_previous_version_as_backup() {
+    let path = temp_dir("backup").join("data.json");
+
+    save_json(&path, &vec![1, 2], &SCHEMA).unwrap();
+    assert!(!backup_path(&path).exists());
+
+    save_json(&path, &vec![1, 2, 3], &SCHEMA).unwrap();
+    assert_eq!(decode::<Vec<u32>>(&fs::read_to_string(&path).unwrap(), &SCHEMA).unwrap(), vec![1, 2, 3]);
+    assert_eq!(decode::<Vec<u32>>(&fs::read_to_string(backup_path(&path)).unwrap(), &SCHEMA).unwrap(), vec![1, 2]);
+    assert!(!path.with_file_name("data.json.tmp").exists());
+}
+
+#[test]
+fn truncated_file_loads_from_backup_and_never_replaces_it() {
+    let path = temp_dir("truncated").join("data.json");
+    save_json(&path, &vec![1], &SCHEMA).unwrap();
+    save_json(&path, &vec![1, 2], &SCHEMA).unwrap();
+
+    // A crash mid-write under the old fs::write
+    fs::write(&path, "[1, 2").unwrap();
+
+    let loaded = load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap();
+    assert_eq!(loaded.value, vec![1]);
+    assert!(matches!(loaded.recovery, Some(StorageRecovery { ref file, .. }) if file == "data.json"));
+
+    save_json(&path, &vec![1, 2, 3], &SCHEMA).unwrap();
+    assert_eq!(load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap().value, vec![1, 2, 3]);
+    assert_eq!(decode::<Vec<u32>>(&fs::read_to_string(backup_path(&path)).unwrap(), &SCHEMA).unwrap(), vec![1]);
+}
+
+#[test]
+fn missing_files_load_as_nothing_and_unrecoverable_ones_fail() {
+    let dir = temp_dir("missing");
+    assert!(load_json::<Vec<u32>>(&dir.join("absent.json"), &SCHEMA).unwrap().is_none());
+
+    let path = dir.join("corrupt.json");
+    fs::write(&path, "{").unwrap();
+    assert!(load_json::<Vec<u32>>(&path, &SCHEMA).is_err());
+
+    // The primary is gone but its backup survived
+    fs::remove_file(&path).unwrap();
+    fs::write(backup_path(&path), "[7]").unwrap();
+    let loaded = load_json::<Vec<u32>>(&path, &SCHEMA).unwrap().unwrap();
+    assert_eq!(loaded.value, vec![7]);
+    assert!(loaded.recovery.is_some());
+}
+
+fn add_one(data: serde_json::Value) -> Result<serde_json::Value, String> {
+    let n = data.as_u64().ok_or("not a number")?;
+    Ok(serde_json::json!(n + 1))
+}
+
+fn double(data: serde_json::Value) -> Result<serde_json::Value, String> {
+    let n = data.as_u64().ok_or("not a number")?;
+    Ok(serde_json::json!(n * 2))
+}
+
+#[test]
+fn migrations_run_in_order_from_the_files_version() {
+    const CHAIN: Schema = Schema { migrations: &[add_one, double, add_one] };
+
+    assert_eq!(decode::<u64>("5", &CHAIN).unwrap(), 13);
+    assert_eq!(decode::<u64>(r#"{"schema_version": 1, "data": 5}"#, &CHAIN).unwrap(), 11);
+    assert_eq!(decode::<u64>(r#"{"schema_version": 3, "data": 5}"#, &CHAIN).unwrap(), 5);
+}
+
+#[test]
+fn files_from_a_newer_version_are_rejected_and_kept() {
+    let path = temp_dir("newer").join("data.json");
+    let newer = r#"{"schema_version": 2, "data": {"renamed": [1, 2]}}"#;
+    fs::write(&path, newer).unwrap();
+    fs::write(backup_path(&path), "[1]").unwrap();
+
+    let error = load_json::<Vec<u32>>(&path, &SCHEMA).unwrap_err().to_string();
+    assert!(error.contains("newer version of HabitQuest (schema version 2, this version supports up to 1)"), "{}", error);
+
+    assert!(save_json(&path, &vec![3], &SCHEMA).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::at;
+use habitquest_lib::quiet_hours::{QuietHours, QuietHoursProfile, QuietPeriod};
+
+fn every_day(periods: Vec<QuietPeriod>) -> QuietHours {
+    QuietHours {
+        enabled: true,
+        weekday: QuietHoursProfile { periods: periods.clone() },
+        weekend: QuietHoursProfile { periods },
+    }
+}
+
+#[test]
+fn periods_end_before_their_last_minute() {
+    // Wednesday 2025-06-04
+    let quiet_hours = every_day(vec![QuietPeriod::new(21, 0, 23, 0)]);
+
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 20, 59)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 4, 21, 0)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 4, 22, 59)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 23, 0)));
+}
+
+#[test]
+fn periods_ending_earlier_than_they_start_wrap_past_midnight() {
+    let period = QuietPeriod::new(22, 0, 7, 0);
+    let quiet_hours = every_day(vec![period]);
+
+    assert!(period.wraps_midnight());
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 21, 59)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 4, 22, 0)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 4, 23, 59)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 5, 0, 0)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 5, 6, 59)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 5, 7, 0)));
+}
+
+#[test]
+fn a_period_that_starts_where_it_ends_is_empty() {
+    let quiet_hours = every_day(vec![QuietPeriod::new(22, 0, 22, 0)]);
+
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 21, 59)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 22, 0)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 5, 3, 0)));
+}
+
+#[test]
+fn periods_follow_the_profile_of_the_day_they_start_on() {
+    let quiet_hours = QuietHours {
+        enabled: true,
+        weekday: QuietHoursProfile { periods: vec![QuietPeriod::new(22, 0, 7, 0)] },
+        weekend: QuietHoursProfile { periods: vec![QuietPeriod::new(23, 0, 9, 0)] },
+    };
+
+    // Friday night keeps the weekday period into Saturday morning
+    assert!(quiet_hours.is_quiet(at(2025, 6, 6, 22, 30)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 7, 6, 59)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 7, 8, 0)));
+    // Saturday and Sunday nights run late into the next morning, Monday included
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 7, 22, 30)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 8, 8, 0)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 9, 8, 59)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 9, 9, 0)));
+    // Monday night is a weekday again
+    assert!(quiet_hours.is_quiet(at(2025, 6, 9, 22, 30)));
+}
+
+#[test]
+fn disabled_quiet_hours_are_never_quiet() {
+    let quiet_hours = QuietHours { enabled: false, ..every_day(vec![QuietPeriod::new(0, 0, 24, 0)]) };
+
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 12, 0)));
+}
+
+#[test]
+fn active_windows_are_quiet_outside_their_hours() {
+    let quiet_hours = QuietHours::from_active_window(8, 22).unwrap();
+
+    assert_eq!(quiet_hours, QuietHours::default());
+    assert!(quiet_hours.is_quiet(at(2025, 6, 4, 7, 59)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 8, 0)));
+    assert!(!quiet_hours.is_quiet(at(2025, 6, 4, 21, 59)));
+    assert!(quiet_hours.is_quiet(at(2025, 6, 4, 22, 0)));
+    // Active all day
+    assert!(!QuietHours::from_active_window(0, 24).unwrap().is_quiet(at(2025, 6, 4, 3, 0)));
+}
+
+#[test]
+fn an_active_window_that_starts_where_it_ends_is_rejected() {
+    assert!(QuietHours::from_active_window(9, 9).is_err());
+    assert!(QuietHours::from_active_window(30, 24).is_err());
+}
+
+#[test]
+fn out_of_range_periods_are_rejected() {
+    assert!(every_day(vec![QuietPeriod::new(22, 0, 24, 0)]).validate().is_ok());
+    assert!(every_day(vec![QuietPeriod::new(25, 0, 7, 0)]).validate().is_err());
+    assert!(every_day(vec![QuietPeriod::new(22, 60, 7, 0)]).validate().is_err());
+}
@@ -0,0 +1,66 @@
+use chrono::{NaiveDate, NaiveTime};
+use habitquest_lib::random_reminders::{random_reminder_times, RandomReminderPlan};
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn plan(count: u32, start_hour: u32, end_hour: u32, min_spacing_minutes: u32) -> RandomReminderPlan {
+    RandomReminderPlan {
+        count,
+        window_start: time(start_hour, 0),
+        window_end: time(end_hour, 0),
+        min_spacing_minutes,
+    }
+}
+
+fn june(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, day).unwrap()
+}
+
+#[test]
+fn schedule_is_fixed_for_date_and_seed() {
+    let plan = plan(3, 8, 22, 90);
+
+    assert_eq!(random_reminder_times(june(2), 42, &plan), vec![time(11, 51), time(17, 3), time(18, 50)]);
+    assert_eq!(random_reminder_times(june(3), 42, &plan), vec![time(12, 8), time(17, 36), time(21, 47)]);
+    assert_eq!(random_reminder_times(june(2), 7, &plan), vec![time(8, 13), time(13, 35), time(20, 28)]);
+}
+
+#[test]
+fn same_inputs_give_same_schedule() {
+    let plan = plan(4, 9, 21, 60);
+
+    assert_eq!(
+        random_reminder_times(june(10), 1234, &plan),
+        random_reminder_times(june(10), 1234, &plan)
+    );
+}
+
+#[test]
+fn times_stay_in_window_with_minimum_spacing() {
+    let plan = plan(5, 9, 18, 75);
+
+    for seed in 0..200 {
+        let times = random_reminder_times(june(15), seed, &plan);
+        assert_eq!(times.len(), 5);
+        assert!(times.iter().all(|t| *t >= time(9, 0) && *t < time(18, 0)), "seed {}: {:?}", seed, times);
+        for pair in times.windows(2) {
+            assert!((pair[1] - pair[0]).num_minutes() >= 75, "seed {}: {:?}", seed, times);
+        }
+    }
+}
+
+#[test]
+fn count_is_limited_to_what_fits_in_window() {
+    // A two hour window only has room for two reminders an hour apart
+    let times = random_reminder_times(june(1), 5, &plan(10, 9, 11, 60));
+
+    assert_eq!(times.len(), 2);
+}
+
+#[test]
+fn empty_window_or_zero_count_gives_nothing() {
+    assert!(random_reminder_times(june(1), 5, &plan(3, 22, 8, 60)).is_empty());
+    assert!(random_reminder_times(june(1), 5, &plan(0, 8, 22, 60)).is_empty());
+}
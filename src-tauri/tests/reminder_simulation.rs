@@ -0,0 +1,415 @@
+//! Multi-day runs of the reminder policy against a manual clock, stepping from deadline to
+//! deadline the way the scheduler does. Times are in a fixed Central European zone so DST
+//! transitions land on known dates (2025-03-30 and 2025-10-26).
+
+mod common;
+
+use chrono::{DateTime, Duration, Local, Weekday};
+use common::{date, habit};
+use habitquest_lib::background_notifications::{
+    decide_reminders, next_reminder_deadline, reminders_due_since, ActivityData, NotificationConfig, Reminder,
+};
+use habitquest_lib::clock::{Clock, ManualClock};
+use habitquest_lib::digests::DigestConfig;
+use habitquest_lib::habit_reminders::HabitReminder;
+use habitquest_lib::habit_streaks::HabitStreak;
+use habitquest_lib::notification_budget::BudgetLimits;
+use habitquest_lib::quiet_hours::QuietHours;
+use habitquest_lib::weekly_review::WeeklyReviewConfig;
+
+/// POSIX rule for Europe/Berlin, so the tests don't depend on the host's zone database
+const CENTRAL_EUROPE: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+fn use_central_europe() {
+    std::env::set_var("TZ", CENTRAL_EUROPE);
+}
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+    use_central_europe();
+    common::at(year, month, day, hour, minute)
+}
+
+fn habit_reminder(habit_id: &str, hour: u32, minute: u32) -> HabitReminder {
+    HabitReminder {
+        id: format!("{}_reminder", habit_id),
+        habit_id: habit_id.to_string(),
+        habit_name: None,
+        hour,
+        minute,
+        weekdays: Vec::new(),
+        enabled: true,
+    }
+}
+
+/// Only streak protection at fixed hours, no digests, weekly review, learning or backoff
+fn streak_config(hours: Vec<u32>, threshold: u32) -> NotificationConfig {
+    NotificationConfig {
+        random_reminders: false,
+        digests: DigestConfig { morning_enabled: false, evening_enabled: false, ..DigestConfig::default() },
+        weekly_review: WeeklyReviewConfig { enabled: false, ..WeeklyReviewConfig::default() },
+        intelligent_timing: false,
+        adaptive_frequency: false,
+        streak_protection_hours: hours,
+        inactivity_threshold_hours: threshold,
+        ..NotificationConfig::default()
+    }
+}
+
+struct Simulation {
+    clock: ManualClock,
+    config: NotificationConfig,
+    habit_reminders: Vec<HabitReminder>,
+    habit_streaks: Vec<HabitStreak>,
+    activity: ActivityData,
+    delivered: Vec<(DateTime<Local>, Reminder)>,
+}
+
+impl Simulation {
+    fn new(config: NotificationConfig, start: DateTime<Local>) -> Self {
+        Self {
+            clock: ManualClock::new(start),
+            config,
+            habit_reminders: Vec::new(),
+            habit_streaks: Vec::new(),
+            activity: ActivityData { last_activity: start, random_seed: 42, ..ActivityData::default() },
+            delivered: Vec::new(),
+        }
+    }
+
+    /// Evaluate every deadline up to and including `end`, then leave the clock at `end`
+    fn run_until(&mut self, end: DateTime<Local>) {
+        while let Some(deadline) = next_reminder_deadline(
+            &self.config,
+            &self.habit_reminders,
+            &self.habit_streaks,
+            &self.activity,
+            self.clock.now(),
+        ) {
+            if deadline > end {
+                break;
+            }
+            self.clock.set(deadline);
+
+            let now = self.clock.now();
+            for due in decide_reminders(&self.config, &self.habit_reminders, &self.habit_streaks, &mut self.activity, now) {
+                if let Some(category) = due.budget {
+                    if !self.activity.budgets.has_room(category, &self.config.effective_budget_limits(), now) {
+                        continue;
+                    }
+                    self.activity.budgets.record(category, now);
+                }
+                self.delivered.push((now, due.reminder));
+            }
+        }
+        self.clock.set(end);
+    }
+
+    fn open_app(&mut self, at: DateTime<Local>) {
+        self.run_until(at);
+        self.activity.record_session(self.clock.now());
+    }
+
+    /// Complete a daily habit the way the app data store does, adding it if it isn't tracked yet
+    fn complete_habit(&mut self, habit_id: &str, at: DateTime<Local>) {
+        self.run_until(at);
+        let now = self.clock.now();
+        match self.habit_streaks.iter_mut().find(|habit| habit.habit_id == habit_id) {
+            Some(habit) => {
+                habit.streak += 1;
+                habit.last_completed_at = Some(now);
+                habit.completions.push(now);
+            }
+            None => self.habit_streaks.push(HabitStreak { completions: vec![now], streak: 1, last_completed_at: Some(now), ..habit(habit_id) }),
+        }
+        self.activity.record_habit_completion(now);
+    }
+
+    fn delivery_times(&self) -> Vec<DateTime<Local>> {
+        self.delivered.iter().map(|(at, _)| *at).collect()
+    }
+}
+
+#[test]
+fn streak_reminder_fires_once_per_inactivity_episode() {
+    let mut sim = Simulation::new(streak_config(vec![12, 18, 20], 20), at(2025, 6, 2, 9, 0));
+
+    // Inactive since Monday morning: one reminder on Tuesday, nothing more while inactive
+    sim.run_until(at(2025, 6, 4, 10, 0));
+    sim.open_app(at(2025, 6, 4, 10, 0));
+    sim.run_until(at(2025, 6, 6, 0, 0));
+
+    assert_eq!(sim.delivery_times(), vec![at(2025, 6, 3, 12, 0), at(2025, 6, 5, 12, 0)]);
+    assert_eq!(sim.delivered[0].1, Reminder::StreakProtection { hours_inactive: 27 });
+    assert_eq!(sim.delivered[1].1, Reminder::StreakProtection { hours_inactive: 26 });
+}
+
+#[test]
+fn completion_only_covers_its_own_day() {
+    let mut sim = Simulation::new(streak_config(vec![12], 20), at(2025, 6, 2, 9, 0));
+
+    sim.complete_habit("read", at(2025, 6, 3, 8, 0));
+    sim.run_until(at(2025, 6, 5, 0, 0));
+
+    assert_eq!(sim.delivery_times(), vec![at(2025, 6, 4, 12, 0)]);
+}
+
+#[test]
+fn budgets_are_counted_per_category() {
+    let config = NotificationConfig {
+        random_reminders: true,
+        random_reminders_per_day: 1,
+        budgets: Some(BudgetLimits { streak_protection: 1, random: 2, ..BudgetLimits::default() }),
+        ..streak_config(vec![8], 1)
+    };
+    let mut sim = Simulation::new(config, at(2025, 6, 2, 0, 0));
+    for day in 2..=4 {
+        sim.open_app(at(2025, 6, day, 7, 0));
+    }
+    sim.run_until(at(2025, 6, 5, 0, 0));
+
+    // The daily streak reminder doesn't use up the random check-in's budget
+    let count = |streak: bool| {
+        sim.delivered
+            .iter()
+            .filter(|(_, reminder)| matches!(reminder, Reminder::StreakProtection { .. }) == streak)
+            .count()
+    };
+    assert_eq!(count(true), 3);
+    assert_eq!(count(false), 3);
+}
+
+#[test]
+fn budget_window_rolls_over_midnight() {
+    let config = NotificationConfig {
+        quiet_hours: Some(QuietHours { enabled: false, ..QuietHours::default() }),
+        budgets: Some(BudgetLimits { habit_specific: 1, ..BudgetLimits::default() }),
+        ..streak_config(Vec::new(), 20)
+    };
+    let mut sim = Simulation::new(config, at(2025, 6, 2, 12, 0));
+    sim.habit_reminders.push(HabitReminder {
+        weekdays: vec![Weekday::Mon],
+        ..habit_reminder("evening", 21, 0)
+    });
+    sim.habit_reminders.push(habit_reminder("morning", 9, 0));
+
+    sim.run_until(at(2025, 6, 5, 0, 0));
+
+    // Tuesday's 09:00 reminder is within 24 hours of Monday's 21:00 one
+    assert_eq!(sim.delivery_times(), vec![at(2025, 6, 2, 21, 0), at(2025, 6, 4, 9, 0)]);
+}
+
+#[test]
+fn quiet_hours_hold_back_overnight_reminders() {
+    let mut sim = Simulation::new(streak_config(vec![6, 12], 20), at(2025, 6, 2, 5, 0));
+
+    // 06:00 on Tuesday is before the 08:00-22:00 window, so the reminder waits until noon
+    sim.run_until(at(2025, 6, 3, 23, 0));
+
+    assert_eq!(sim.delivery_times(), vec![at(2025, 6, 3, 12, 0)]);
+}
+
+#[test]
+fn habit_reminder_skips_the_hour_lost_to_spring_forward() {
+    let config = NotificationConfig {
+        quiet_hours: Some(QuietHours { enabled: false, ..QuietHours::default() }),
+        ..streak_config(Vec::new(), 20)
+    };
+    let mut sim = Simulation::new(config, at(2025, 3, 29, 0, 0));
+    sim.habit_reminders.push(habit_reminder("stretch", 2, 30));
+
+    sim.run_until(at(2025, 4, 1, 0, 0));
+
+    // 02:30 doesn't exist on 2025-03-30
+    assert_eq!(
+        sim.delivery_times(),
+        vec![at(2025, 3, 29, 2, 30), at(2025, 3, 31, 2, 30)]
+    );
+}
+
+#[test]
+fn habit_reminder_fires_once_in_the_repeated_fall_back_hour() {
+    let config = NotificationConfig {
+        quiet_hours: Some(QuietHours { enabled: false, ..QuietHours::default() }),
+        ..streak_config(Vec::new(), 20)
+    };
+    let mut sim = Simulation::new(config, at(2025, 10, 26, 0, 0));
+    sim.habit_reminders.push(habit_reminder("stretch", 2, 30));
+
+    sim.run_until(at(2025, 10, 27, 0, 0));
+
+    assert_eq!(sim.delivery_times(), vec![at(2025, 10, 26, 2, 30)]);
+}
+
+#[test]
+fn inactivity_counts_elapsed_hours_across_dst() {
+    // Spring forward: noon to noon is only 23 hours
+    let mut sim = Simulation::new(streak_config(vec![12], 24), at(2025, 3, 29, 12, 0));
+    sim.run_until(at(2025, 4, 1, 0, 0));
+    assert_eq!(sim.delivered, vec![(at(2025, 3, 31, 12, 0), Reminder::StreakProtection { hours_inactive: 47 })]);
+
+    // Fall back: noon to noon is 25 hours
+    let mut sim = Simulation::new(streak_config(vec![12], 25), at(2025, 10, 25, 12, 0));
+    sim.run_until(at(2025, 10, 27, 0, 0));
+    assert_eq!(sim.delivered, vec![(at(2025, 10, 26, 12, 0), Reminder::StreakProtection { hours_inactive: 25 })]);
+}
+
+#[test]
+fn manual_clock_moves_only_when_told() {
+    let clock = ManualClock::new(at(2025, 3, 30, 1, 30));
+    assert_eq!(clock.now(), clock.now());
+
+    // One real hour after 01:30 CET is 03:30 CEST
+    clock.advance(Duration::hours(1));
+    assert_eq!(clock.now(), at(2025, 3, 30, 3, 30));
+}
+
+fn streak_warnings(sim: &Simulation) -> Vec<(DateTime<Local>, i64, bool)> {
+    sim.delivered
+        .iter()
+        .filter_map(|(at, reminder)| match reminder {
+            Reminder::StreakAtRisk { hours_left, final_warning, .. } => Some((*at, *hours_left, *final_warning)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn long_streaks_get_earlier_and_more_warnings() {
+    let mut sim = Simulation::new(streak_config(Vec::new(), 20), at(2025, 6, 3, 0, 0));
+    sim.habit_streaks.push(HabitStreak { streak: 45, last_completed_at: Some(at(2025, 6, 2, 20, 0)), ..habit("meditate") });
+    sim.habit_streaks.push(HabitStreak { streak: 3, last_completed_at: Some(at(2025, 6, 2, 20, 0)), ..habit("read") });
+
+    sim.run_until(at(2025, 6, 5, 0, 0));
+
+    // The 22:00 warning falls in quiet hours and goes out just before them. Nothing on
+    // Wednesday, the streaks ended at midnight.
+    let warnings = |habit: &str| -> Vec<_> {
+        sim.delivered
+            .iter()
+            .filter(|(_, reminder)| reminder.habit_id() == Some(habit))
+            .map(|(at, _)| *at)
+            .collect()
+    };
+    assert_eq!(
+        warnings("meditate"),
+        vec![at(2025, 6, 3, 12, 0), at(2025, 6, 3, 16, 0), at(2025, 6, 3, 20, 0), at(2025, 6, 3, 21, 45)]
+    );
+    assert_eq!(warnings("read"), vec![at(2025, 6, 3, 20, 0), at(2025, 6, 3, 21, 45)]);
+    assert_eq!(
+        streak_warnings(&sim).last(),
+        Some(&(at(2025, 6, 3, 21, 45), 2, true))
+    );
+}
+
+#[test]
+fn completing_a_habit_stops_its_streak_warnings() {
+    let mut sim = Simulation::new(streak_config(Vec::new(), 20), at(2025, 6, 3, 0, 0));
+    sim.habit_streaks.push(HabitStreak { streak: 45, last_completed_at: Some(at(2025, 6, 2, 20, 0)), ..habit("meditate") });
+
+    sim.complete_habit("meditate", at(2025, 6, 3, 13, 0));
+    sim.run_until(at(2025, 6, 4, 0, 0));
+
+    assert_eq!(streak_warnings(&sim), vec![(at(2025, 6, 3, 12, 0), 12, false)]);
+}
+
+#[test]
+fn missed_warnings_are_coalesced_into_the_most_urgent() {
+    let mut sim = Simulation::new(streak_config(Vec::new(), 20), at(2025, 6, 3, 0, 0));
+    sim.habit_streaks.push(HabitStreak { streak: 45, last_completed_at: Some(at(2025, 6, 2, 20, 0)), ..habit("meditate") });
+
+    // The app wasn't running until 20:30; one catch-up evaluation covers three warnings
+    sim.clock.set(at(2025, 6, 3, 20, 30));
+    let now = sim.clock.now();
+    let due = decide_reminders(&sim.config, &sim.habit_reminders, &sim.habit_streaks, &mut sim.activity, now);
+
+    assert_eq!(due.len(), 1);
+    assert!(matches!(
+        due[0].reminder,
+        Reminder::StreakAtRisk { hours_left: 3, final_warning: false, .. }
+    ));
+}
+
+#[test]
+fn reminders_missed_during_suspend_are_coalesced() {
+    let config = NotificationConfig {
+        quiet_hours: Some(QuietHours { enabled: false, ..QuietHours::default() }),
+        ..streak_config(Vec::new(), 20)
+    };
+    let mut sim = Simulation::new(config, at(2025, 6, 2, 8, 0));
+    for (habit, hour) in [("water", 9), ("walk", 12), ("read", 15), ("stretch", 19)] {
+        sim.habit_reminders.push(habit_reminder(habit, hour, 0));
+    }
+
+    // Suspended from 08:30 until the 19:00 reminder is due
+    let due = reminders_due_since(
+        &sim.config,
+        &sim.habit_reminders,
+        &sim.habit_streaks,
+        &mut sim.activity,
+        at(2025, 6, 2, 8, 30),
+        at(2025, 6, 2, 19, 1),
+    );
+
+    let missed_habits = |reminder: &Reminder| match reminder {
+        Reminder::CatchUp { missed } => missed.iter().filter_map(Reminder::habit_id).map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    assert_eq!(due.len(), 2);
+    assert_eq!(missed_habits(&due[0].reminder), vec!["water", "walk", "read"]);
+    assert_eq!(due[0].budget, None);
+    assert_eq!(due[1].reminder.habit_id(), Some("stretch"));
+}
+
+#[test]
+fn a_single_missed_reminder_is_sent_as_is() {
+    let mut sim = Simulation::new(streak_config(vec![12], 20), at(2025, 6, 2, 9, 0));
+    sim.habit_reminders.push(habit_reminder("water", 9, 30));
+
+    let due = reminders_due_since(
+        &sim.config,
+        &sim.habit_reminders,
+        &sim.habit_streaks,
+        &mut sim.activity,
+        at(2025, 6, 2, 9, 0),
+        at(2025, 6, 2, 11, 0),
+    );
+
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].reminder.habit_id(), Some("water"));
+}
+
+#[test]
+fn digests_go_out_at_their_times_outside_quiet_hours() {
+    let config = NotificationConfig {
+        digests: DigestConfig { morning_hour: 7, morning_minute: 0, ..DigestConfig::default() },
+        ..streak_config(Vec::new(), 20)
+    };
+    let mut sim = Simulation::new(config, at(2025, 6, 2, 0, 0));
+    sim.habit_streaks.push(HabitStreak { streak: 1, last_completed_at: Some(at(2025, 6, 1, 9, 0)), ..habit("meditate") });
+
+    sim.run_until(at(2025, 6, 3, 0, 0));
+
+    // 07:00 is inside the default 22:00-08:00 quiet hours, so only the recap is sent
+    assert_eq!(sim.delivery_times(), vec![at(2025, 6, 2, 20, 30)]);
+    assert!(matches!(sim.delivered[0].1, Reminder::EveningRecap { .. }));
+}
+
+#[test]
+fn weekly_review_covers_the_previous_week() {
+    let config = NotificationConfig {
+        weekly_review: WeeklyReviewConfig::default(),
+        ..streak_config(Vec::new(), 20)
+    };
+    let mut sim = Simulation::new(config, at(2025, 6, 4, 0, 0));
+
+    sim.run_until(at(2025, 6, 17, 0, 0));
+
+    // Mondays at 09:00, each reviewing the Monday-to-Sunday week before
+    assert_eq!(
+        sim.delivered,
+        vec![
+            (at(2025, 6, 9, 9, 0), Reminder::WeeklyReview { week_start: date(2025, 6, 2) }),
+            (at(2025, 6, 16, 9, 0), Reminder::WeeklyReview { week_start: date(2025, 6, 9) }),
+        ]
+    );
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use chrono::{DateTime, Local};
+use common::{at, habit};
+use habitquest_lib::background_notifications::{
+    decide_reminders, next_reminder_deadline, ActivityData, DueReminder, NotificationConfig, Reminder, SnoozedReminder,
+};
+use habitquest_lib::digests::DigestConfig;
+use habitquest_lib::habit_reminders::HabitReminder;
+use habitquest_lib::habit_streaks::HabitStreak;
+use habitquest_lib::notification_budget::BudgetCategory;
+use habitquest_lib::weekly_review::WeeklyReviewConfig;
+
+/// Last seen at 08:00 on Wednesday 2025-06-04, with nothing completed since
+fn inactive_since_morning() -> ActivityData {
+    ActivityData { last_activity: at(2025, 6, 4, 8, 0), ..ActivityData::default() }
+}
+
+/// Default settings without random reminders, digests or the weekly review, so only the
+/// reminders under test fire
+fn defaults() -> NotificationConfig {
+    NotificationConfig {
+        random_reminders: false,
+        digests: DigestConfig { morning_enabled: false, evening_enabled: false, ..DigestConfig::default() },
+        weekly_review: WeeklyReviewConfig { enabled: false, ..WeeklyReviewConfig::default() },
+        ..NotificationConfig::default()
+    }
+}
+
+/// Streak protection checked at the top of every hour
+fn config(threshold: u32) -> NotificationConfig {
+    NotificationConfig {
+        inactivity_threshold_hours: threshold,
+        streak_protection_hours: (0..24).collect(),
+        ..defaults()
+    }
+}
+
+fn reminds(config: &NotificationConfig, activity: &mut ActivityData, now: DateTime<Local>) -> bool {
+    !decide_reminders(config, &[], &[], activity, now).is_empty()
+}
+
+#[test]
+fn streak_protection_waits_for_the_inactivity_threshold() {
+    let mut activity = inactive_since_morning();
+
+    assert!(!reminds(&config(10), &mut activity, at(2025, 6, 4, 17, 0)));
+    assert_eq!(
+        decide_reminders(&config(10), &[], &[], &mut activity, at(2025, 6, 4, 18, 0)),
+        vec![DueReminder { reminder: Reminder::StreakProtection { hours_inactive: 10 }, budget: Some(BudgetCategory::StreakProtection) }]
+    );
+}
+
+#[test]
+fn streak_protection_fires_once_per_inactivity_episode() {
+    let mut activity = inactive_since_morning();
+
+    assert!(reminds(&config(4), &mut activity, at(2025, 6, 4, 12, 0)));
+    assert!(!reminds(&config(4), &mut activity, at(2025, 6, 4, 18, 0)));
+
+    // Activity starts a new episode
+    activity.last_activity = at(2025, 6, 4, 13, 0);
+    assert!(!reminds(&config(4), &mut activity, at(2025, 6, 4, 16, 0)));
+    assert!(reminds(&config(4), &mut activity, at(2025, 6, 4, 17, 0)));
+}
+
+#[test]
+fn streak_protection_skips_days_with_a_completion() {
+    let mut activity = inactive_since_morning();
+    let habits = [HabitStreak { last_completed_at: Some(at(2025, 6, 4, 7, 30)), ..habit("Read") }];
+
+    assert!(decide_reminders(&config(4), &[], &habits, &mut activity, at(2025, 6, 4, 20, 0)).is_empty());
+    // Yesterday's completion doesn't protect today
+    assert!(!decide_reminders(&config(4), &[], &habits, &mut activity, at(2025, 6, 5, 9, 0)).is_empty());
+}
+
+#[test]
+fn streak_protection_respects_disabled_reminders() {
+    let mut activity = inactive_since_morning();
+    let disabled = NotificationConfig { streak_reminders: false, ..config(4) };
+
+    assert!(!reminds(&disabled, &mut activity, at(2025, 6, 4, 20, 0)));
+    assert_eq!(activity.streak_reminder_sent_for, None);
+}
+
+#[test]
+fn reminders_wait_for_active_hours() {
+    let mut activity = ActivityData { last_activity: at(2025, 6, 3, 20, 0), ..ActivityData::default() };
+
+    assert!(!reminds(&config(4), &mut activity, at(2025, 6, 4, 7, 0)));
+    assert!(reminds(&config(4), &mut activity, at(2025, 6, 4, 8, 0)));
+
+    // The window ends at 22:00 sharp
+    activity.last_activity = at(2025, 6, 4, 9, 0);
+    assert!(!reminds(&config(4), &mut activity, at(2025, 6, 4, 22, 0)));
+    assert!(reminds(&config(4), &mut activity, at(2025, 6, 4, 21, 0)));
+}
+
+#[test]
+fn reminders_stop_while_their_budget_is_spent() {
+    let capped = NotificationConfig { max_reminders_per_day: 1, ..config(4) };
+    let mut activity = inactive_since_morning();
+
+    assert!(reminds(&capped, &mut activity, at(2025, 6, 4, 12, 0)));
+    // Delivered, so the dispatcher charged it
+    activity.budgets.record(BudgetCategory::StreakProtection, at(2025, 6, 4, 12, 0));
+    activity.last_activity = at(2025, 6, 4, 13, 0);
+    assert!(!reminds(&capped, &mut activity, at(2025, 6, 4, 18, 0)));
+    assert!(!reminds(&capped, &mut activity, at(2025, 6, 5, 11, 0)));
+    // The episode that hit the limit is reminded once the send leaves the 24 hour window
+    assert!(reminds(&capped, &mut activity, at(2025, 6, 5, 12, 0)));
+}
+
+#[test]
+fn streak_protection_fires_only_at_its_hours() {
+    let hours = NotificationConfig { inactivity_threshold_hours: 4, ..defaults() };
+    let mut activity = ActivityData { last_activity: at(2025, 6, 3, 20, 0), ..ActivityData::default() };
+
+    assert_eq!(hours.streak_protection_hours, vec![12, 18, 20]);
+    assert!(!reminds(&hours, &mut activity, at(2025, 6, 4, 11, 0)));
+    assert!(!reminds(&hours, &mut activity, at(2025, 6, 4, 12, 5)));
+    assert!(reminds(&hours, &mut activity, at(2025, 6, 4, 12, 4)));
+}
+
+#[test]
+fn the_next_deadline_is_the_next_streak_protection_hour() {
+    let hours = NotificationConfig { streak_protection_hours: vec![20, 12, 18, 12, 30], ..defaults() };
+    let deadline = |now| next_reminder_deadline(&hours, &[], &[], &ActivityData::default(), now);
+
+    assert_eq!(deadline(at(2025, 6, 4, 9, 17)), Some(at(2025, 6, 4, 12, 0)));
+    // Strictly after now
+    assert_eq!(deadline(at(2025, 6, 4, 12, 0)), Some(at(2025, 6, 4, 18, 0)));
+    assert_eq!(deadline(at(2025, 6, 4, 20, 1)), Some(at(2025, 6, 5, 12, 0)));
+}
+
+#[test]
+fn there_is_no_deadline_without_reminders() {
+    let disabled = NotificationConfig { enabled: false, ..defaults() };
+    let no_hours = NotificationConfig { streak_protection_hours: Vec::new(), ..defaults() };
+
+    assert_eq!(next_reminder_deadline(&disabled, &[], &[], &ActivityData::default(), at(2025, 6, 4, 9, 0)), None);
+    assert_eq!(next_reminder_deadline(&no_hours, &[], &[], &ActivityData::default(), at(2025, 6, 4, 9, 0)), None);
+}
+
+fn habit_reminder(habit_id: &str, hour: u32, minute: u32) -> HabitReminder {
+    HabitReminder {
+        id: format!("{}_reminder", habit_id),
+        habit_id: habit_id.to_string(),
+        habit_name: Some("Read".to_string()),
+        hour,
+        minute,
+        weekdays: Vec::new(),
+        enabled: true,
+    }
+}
+
+#[test]
+fn habit_reminders_fire_at_their_time_outside_the_daily_cap() {
+    let no_streaks = NotificationConfig { streak_reminders: false, max_reminders_per_day: 0, ..defaults() };
+    let reminders = [habit_reminder("read", 9, 30)];
+    let mut activity = ActivityData { last_activity: at(2025, 6, 4, 8, 0), ..ActivityData::default() };
+
+    assert!(decide_reminders(&no_streaks, &reminders, &[], &mut activity, at(2025, 6, 4, 9, 29)).is_empty());
+    assert_eq!(
+        decide_reminders(&no_streaks, &reminders, &[], &mut activity, at(2025, 6, 4, 9, 30)),
+        vec![DueReminder {
+            reminder: Reminder::HabitSpecific {
+                reminder_id: "read_reminder".to_string(),
+                habit_id: "read".to_string(),
+                habit_name: Some("Read".to_string()),
+            },
+            budget: Some(BudgetCategory::HabitSpecific),
+        }]
+    );
+}
+
+#[test]
+fn habit_reminders_skip_habits_completed_today() {
+    let reminders = [habit_reminder("read", 9, 30), habit_reminder("run", 9, 30)];
+    let habits = [HabitStreak { last_completed_at: Some(at(2025, 6, 4, 8, 15)), ..habit("Read") }];
+    let mut activity = ActivityData { last_activity: at(2025, 6, 4, 8, 0), ..ActivityData::default() };
+
+    let due = decide_reminders(&defaults(), &reminders, &habits, &mut activity, at(2025, 6, 4, 9, 31));
+    assert!(matches!(due.as_slice(), [DueReminder { reminder: Reminder::HabitSpecific { habit_id, .. }, .. }] if habit_id == "run"));
+}
+
+#[test]
+fn the_next_deadline_includes_habit_reminders() {
+    let reminders = [habit_reminder("read", 9, 30), HabitReminder { enabled: false, ..habit_reminder("run", 7, 0) }];
+    let deadline = |now| next_reminder_deadline(&defaults(), &reminders, &[], &ActivityData::default(), now);
+
+    assert_eq!(deadline(at(2025, 6, 4, 6, 0)), Some(at(2025, 6, 4, 9, 30)));
+    assert_eq!(deadline(at(2025, 6, 4, 9, 30)), Some(at(2025, 6, 4, 12, 0)));
+    assert_eq!(deadline(at(2025, 6, 4, 21, 0)), Some(at(2025, 6, 5, 9, 30)));
+}
+
+#[test]
+fn snoozed_reminders_come_back_when_due_outside_quiet_hours() {
+    let no_streaks = NotificationConfig { streak_reminders: false, ..defaults() };
+    let snoozed = |due| SnoozedReminder { reminder: Reminder::StreakProtection { hours_inactive: 20 }, due };
+    let mut activity = ActivityData {
+        snoozed_reminders: vec![snoozed(at(2025, 6, 4, 13, 0)), snoozed(at(2025, 6, 4, 22, 30))],
+        ..ActivityData::default()
+    };
+
+    assert_eq!(next_reminder_deadline(&no_streaks, &[], &[], &activity, at(2025, 6, 4, 12, 30)), Some(at(2025, 6, 4, 13, 0)));
+    assert!(decide_reminders(&no_streaks, &[], &[], &mut activity, at(2025, 6, 4, 12, 59)).is_empty());
+    assert_eq!(
+        decide_reminders(&no_streaks, &[], &[], &mut activity, at(2025, 6, 4, 13, 0)),
+        vec![DueReminder { reminder: Reminder::StreakProtection { hours_inactive: 20 }, budget: None }]
+    );
+    assert_eq!(activity.snoozed_reminders.len(), 1);
+
+    // Held through quiet hours
+    assert!(decide_reminders(&no_streaks, &[], &[], &mut activity, at(2025, 6, 4, 22, 30)).is_empty());
+    assert_eq!(activity.snoozed_reminders.len(), 1);
+}
@@ -0,0 +1,92 @@
+//! Every persisted file, as written by each past schema version, must still load. Fixtures live
+//! in `tests/fixtures/schemas/v<N>/`; v0 files are from before the schema envelope.
+
+use habitquest_lib::app_data::{AppData, APP_DATA_SCHEMA};
+use habitquest_lib::app_settings::{AppSettings, StartupBehavior, APP_SETTINGS_SCHEMA};
+use habitquest_lib::background_notifications::{
+    ActivityData, NotificationConfig, ACTIVITY_DATA_SCHEMA, NOTIFICATION_CONFIG_SCHEMA,
+};
+use habitquest_lib::habit_reminders::{HabitReminder, HABIT_REMINDERS_SCHEMA};
+use habitquest_lib::habit_streaks::{HabitStreak, HABIT_STREAKS_SCHEMA};
+use habitquest_lib::notification_history::{HistoryQuery, NotificationHistory, NOTIFICATION_HISTORY_SCHEMA};
+use habitquest_lib::persistence::{load_json, Schema};
+use habitquest_lib::weekly_review::{ProgressData, PROGRESS_SCHEMA};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+fn fixture(version: u32, file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/schemas")
+        .join(format!("v{}", version))
+        .join(file)
+}
+
+/// The file loaded from the fixture of every version up to the current one
+fn load_every_version<T: DeserializeOwned>(file: &str, schema: &Schema) -> Vec<T> {
+    (0..=schema.version())
+        .map(|version| {
+            let path = fixture(version, file);
+            let loaded = load_json::<T>(&path, schema)
+                .unwrap_or_else(|e| panic!("v{} {}: {}", version, file, e))
+                .unwrap_or_else(|| panic!("missing fixture {}", path.display()));
+            assert!(loaded.recovery.is_none());
+            loaded.value
+        })
+        .collect()
+}
+
+#[test]
+fn notification_config_loads_from_every_version() {
+    let configs = load_every_version::<NotificationConfig>("notification_config.json", &NOTIFICATION_CONFIG_SCHEMA);
+
+    // Fields added since the first release take their defaults
+    assert_eq!(configs[0].streak_warning_threshold, 5);
+    assert_eq!(configs[0].streak_protection_hours, vec![12, 18]);
+    assert_eq!(configs[0].inactivity_threshold_hours, NotificationConfig::default().inactivity_threshold_hours);
+    assert!(configs[0].quiet_hours.is_none());
+    assert_eq!(configs[1].random_reminder_seed, Some(7));
+}
+
+#[test]
+fn activity_data_loads_from_every_version() {
+    let activity = load_every_version::<ActivityData>("activity_data.json", &ACTIVITY_DATA_SCHEMA);
+
+    assert!(activity.iter().all(|activity| activity.daily_sessions.len() == 1));
+    assert!(activity[1..].iter().all(|activity| activity.random_seed == 42));
+}
+
+#[test]
+fn habit_data_loads_from_every_version() {
+    let reminders = load_every_version::<Vec<HabitReminder>>("habit_reminders.json", &HABIT_REMINDERS_SCHEMA);
+    assert!(reminders.iter().all(|reminders| reminders[0].habit_id == "meditate" && reminders[0].hour == 7));
+
+    let streaks = load_every_version::<Vec<HabitStreak>>("habit_streaks.json", &HABIT_STREAKS_SCHEMA);
+    assert!(streaks.iter().all(|streaks| streaks[0].streak == 12));
+    assert!(streaks[0][0].is_recurring && streaks[0][0].completions.is_empty());
+
+    let progress = load_every_version::<ProgressData>("progress.json", &PROGRESS_SCHEMA);
+    assert!(progress.iter().all(|progress| progress.goals["health"].monthly_target_xp == 400));
+}
+
+#[test]
+fn history_and_settings_load_from_every_version() {
+    let histories = load_every_version::<NotificationHistory>("notification_history.json", &NOTIFICATION_HISTORY_SCHEMA);
+    assert!(histories.iter().all(|history| history.query(&HistoryQuery::default()).total == 1));
+
+    let settings = load_every_version::<AppSettings>("app_settings.json", &APP_SETTINGS_SCHEMA);
+    assert!(settings.iter().all(|settings| settings.theme.as_deref() == Some("dark") && settings.language == "de"));
+    assert_eq!(settings[0].startup_behavior, StartupBehavior::Summary);
+    assert_eq!(settings[1].startup_behavior, StartupBehavior::Silent);
+}
+
+#[test]
+fn app_data_loads_from_every_version() {
+    let data = load_every_version::<AppData>("app_data.json", &APP_DATA_SCHEMA);
+
+    assert!(data.iter().all(|data| data.habits[0].id == "meditate" && data.total_xp == 120 && data.settings.language == "de"));
+    // Exports from before completions were timestamped
+    assert_eq!(data[0].habits[0].habit_streak().completions.len(), 2);
+    assert_eq!(data[0].habits[0].best_streak, 0);
+    assert!(data[0].inventory[0].id.is_none());
+    assert_eq!(data[1].achievements[0].target, 7.0);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use chrono::{DateTime, Local};
+use common::{at, date, habit};
+use habitquest_lib::habit_streaks::{HabitFrequency, HabitStreak};
+use habitquest_lib::startup_summary::StartupSummary;
+
+/// A daily habit whose streak was last extended at `last_completed_at`
+fn streak(name: &str, streak: u32, last_completed_at: DateTime<Local>) -> HabitStreak {
+    HabitStreak { streak, last_completed_at: Some(last_completed_at), ..habit(name) }
+}
+
+fn names(summary: &StartupSummary) -> Vec<&str> {
+    summary.due.iter().map(|habit| habit.habit_name.as_str()).collect()
+}
+
+#[test]
+fn lists_unfinished_habits_with_streaks_at_stake_first() {
+    let now = at(2025, 6, 4, 8, 0);
+    let habits = vec![
+        habit("Water"),
+        streak("Read", 5, at(2025, 6, 3, 21, 0)),
+        streak("Meditate", 45, at(2025, 6, 3, 7, 0)),
+        streak("Stretch", 9, at(2025, 6, 4, 7, 30)),
+        HabitStreak { frequency: HabitFrequency::Weekly, ..streak("Review", 3, at(2025, 6, 2, 18, 0)) },
+    ];
+
+    let summary = StartupSummary::build(&habits, now);
+
+    assert_eq!(names(&summary), vec!["Meditate", "Read", "Water"]);
+    let at_stake: Vec<_> = summary.streaks_at_stake().map(|habit| habit.streak).collect();
+    assert_eq!(at_stake, vec![45, 5]);
+}
+
+#[test]
+fn completed_habits_and_one_off_dates_are_respected() {
+    let now = at(2025, 6, 4, 8, 0);
+    let one_off = HabitStreak { is_recurring: false, specific_date: Some(date(2025, 6, 5)), ..habit("Dentist") };
+    let habits = vec![streak("Meditate", 46, at(2025, 6, 4, 7, 45)), one_off];
+
+    assert!(StartupSummary::build(&habits, now).due.is_empty());
+}
+
+#[test]
+fn notification_lists_a_few_habits_and_the_streaks() {
+    let now = at(2025, 6, 4, 8, 0);
+    let habits: Vec<_> = ["Water", "Walk", "Read", "Write"]
+        .iter()
+        .map(|name| habit(name))
+        .chain([streak("Meditate", 45, at(2025, 6, 3, 7, 0))])
+        .collect();
+
+    let (_, body) = StartupSummary::build(&habits, now).notification_text("en");
+    assert_eq!(body, "5 habits due: Meditate, Water, Walk, +2 more\n🔥 Streaks at stake: Meditate (45)");
+
+    let (_, body) = StartupSummary::default().notification_text("en");
+    assert_eq!(body, "Everything is done for now - nice work! 🎉");
+}
@@ -0,0 +1,197 @@
+mod common;
+
+use chrono::{Duration, Weekday};
+use common::{at, date};
+use habitquest_lib::app_data::{AppData, Completion};
+use habitquest_lib::notification_history::{DeliveryResult, HistoryQuery, NotificationHistory, NotificationKind};
+use habitquest_lib::storage::{run_migrations, CompletionQuery, SqliteStorage, Storage, XpReason, MIGRATIONS};
+use rusqlite::Connection;
+use serde_json::json;
+
+/// One daily habit completed on the first three days of July 2025 (Tuesday to Thursday)
+fn app_data() -> AppData {
+    serde_json::from_value(json!({
+        "habits": [{
+            "id": "stretch",
+            "title": "Stretch",
+            "frequency": "daily",
+            "category": "HEALTH",
+            "xpOnComplete": 10,
+            "streak": 3,
+            "bestStreak": 3,
+            "lastCompletedAt": at(2025, 7, 3, 7, 0),
+            "completions": {
+                "2025-07-01": at(2025, 7, 1, 7, 0),
+                "2025-07-02": at(2025, 7, 2, 7, 0),
+                "2025-07-03": at(2025, 7, 3, 7, 0),
+                "2025-06-01": true
+            },
+            "isRecurring": true,
+            "specificDate": null
+        }],
+        "points": 60,
+        "totalXP": 40,
+        "goals": { "HEALTH": { "monthlyTargetXP": 300 } },
+        "inventory": [{ "id": "tea", "name": "Tea", "cost": 20, "redeemedAt": at(2025, 7, 2, 16, 0) }],
+        "shop": [{ "id": "tea", "name": "Tea", "cost": 20, "rarity": "rare" }],
+        "categories": ["HEALTH"],
+        "settings": { "language": "es" },
+        "version": "4.2.0"
+    }))
+    .unwrap()
+}
+
+#[test]
+fn stores_app_data_in_tables_and_loads_it_back() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    assert!(storage.load_app_data().unwrap().is_none());
+
+    let data = app_data();
+    storage.save_app_data(&data, at(2025, 7, 3, 8, 0)).unwrap();
+    assert_eq!(storage.load_app_data().unwrap(), Some(data));
+}
+
+#[test]
+fn a_habit_completed_by_the_backend_loads_back_completed() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    let mut data = app_data();
+    storage.save_app_data(&data, at(2025, 7, 3, 8, 0)).unwrap();
+
+    assert_eq!(data.complete_habit("stretch", at(2025, 7, 4, 7, 0)), Ok(true));
+    storage.save_app_data(&data, at(2025, 7, 4, 7, 0)).unwrap();
+
+    let loaded = storage.load_app_data().unwrap().unwrap();
+    assert_eq!(loaded.habits[0].streak, 4);
+    assert_eq!(loaded.habits[0].completions["2025-07-04"], Completion::At(at(2025, 7, 4, 7, 0)));
+    assert_eq!(loaded, data);
+}
+
+#[test]
+fn instants_load_back_to_the_nanosecond() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    let mut data = app_data();
+    let now = at(2025, 7, 4, 7, 0) + Duration::nanoseconds(123_456_789);
+    assert_eq!(data.complete_habit("stretch", now), Ok(true));
+    data.inventory[0].redeemed_at += Duration::nanoseconds(999_999);
+    storage.save_app_data(&data, now).unwrap();
+
+    let loaded = storage.load_app_data().unwrap().unwrap();
+    assert_eq!(loaded.habits[0].completions["2025-07-04"], Completion::At(now));
+    assert_eq!(loaded, data);
+
+    let query = CompletionQuery { from: date(2025, 7, 4), to: date(2025, 7, 4), habit_id: None, weekday: None };
+    assert_eq!(storage.completions(&query).unwrap()[0].completed_at, now);
+}
+
+#[test]
+fn periods_marked_incomplete_load_back_and_ledger_as_undone() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    let mut data = app_data();
+    storage.save_app_data(&data, at(2025, 7, 3, 8, 0)).unwrap();
+
+    data.habits[0].completions.insert("2025-07-03".to_string(), Completion::Legacy(false));
+    data.total_xp -= 10;
+    storage.save_app_data(&data, at(2025, 7, 3, 9, 0)).unwrap();
+    assert_eq!(storage.load_app_data().unwrap(), Some(data.clone()));
+
+    data.habits[0].completions.insert("2025-07-03".to_string(), Completion::At(at(2025, 7, 3, 10, 0)));
+    data.total_xp += 10;
+    storage.save_app_data(&data, at(2025, 7, 3, 10, 0)).unwrap();
+    let reasons: Vec<_> = storage
+        .xp_ledger(date(2025, 7, 3), date(2025, 7, 3))
+        .unwrap()
+        .into_iter()
+        .map(|entry| (entry.xp, entry.reason))
+        .collect();
+    assert_eq!(reasons, vec![(40, XpReason::Opening), (-10, XpReason::Undo), (10, XpReason::Completion)]);
+}
+
+#[test]
+fn queries_completions_by_day_and_weekday() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    storage.save_app_data(&app_data(), at(2025, 7, 3, 8, 0)).unwrap();
+
+    let query = |from, to, weekday| CompletionQuery { from, to, habit_id: Some("stretch".to_string()), weekday };
+    let july = storage.completions(&query(date(2025, 7, 1), date(2025, 9, 30), None)).unwrap();
+    assert_eq!(july.iter().map(|c| c.period_key.as_str()).collect::<Vec<_>>(), vec!["2025-07-01", "2025-07-02", "2025-07-03"]);
+
+    let tuesdays = storage.completions(&query(date(2025, 7, 1), date(2025, 9, 30), Some(Weekday::Tue))).unwrap();
+    assert_eq!(tuesdays.len(), 1);
+    assert_eq!(tuesdays[0].completed_at, at(2025, 7, 1, 7, 0));
+
+    assert!(storage.completions(&query(date(2025, 7, 2), date(2025, 7, 2), Some(Weekday::Tue))).unwrap().is_empty());
+}
+
+#[test]
+fn ledgers_the_xp_each_save_gains_or_loses() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    let mut data = app_data();
+    storage.save_app_data(&data, at(2025, 7, 3, 8, 0)).unwrap();
+
+    // Complete on the 4th, undo the 3rd, then a bonus the completions don't explain
+    let habit = &mut data.habits[0];
+    habit.completions.insert("2025-07-04".to_string(), Completion::At(at(2025, 7, 4, 7, 0)));
+    habit.completions.remove("2025-07-03");
+    data.total_xp += 5;
+    storage.save_app_data(&data, at(2025, 7, 4, 8, 0)).unwrap();
+
+    let ledger: Vec<_> = storage
+        .xp_ledger(date(2025, 7, 1), date(2025, 7, 31))
+        .unwrap()
+        .into_iter()
+        .map(|entry| (entry.habit_id, entry.xp, entry.reason))
+        .collect();
+    assert_eq!(
+        ledger,
+        vec![
+            (None, 40, XpReason::Opening),
+            (Some("stretch".to_string()), 10, XpReason::Completion),
+            (Some("stretch".to_string()), -10, XpReason::Undo),
+            (None, 5, XpReason::Adjustment),
+        ]
+    );
+    assert_eq!(storage.xp_ledger(date(2025, 7, 4), date(2025, 7, 4)).unwrap().len(), 3);
+}
+
+#[test]
+fn keeps_every_notification_and_pages_newest_first() {
+    let mut storage = SqliteStorage::open_in_memory().unwrap();
+    let mut history = NotificationHistory::default();
+    let entries: Vec<_> = (0..3)
+        .map(|hour| history.record(at(2025, 7, 1, 8 + hour, 0), NotificationKind::Random, "random", "Hi", DeliveryResult::Delivered, None))
+        .collect();
+    let failed = history.record(at(2025, 7, 1, 12, 0), NotificationKind::Test, "test", "Test", DeliveryResult::Failed, Some("denied".into()));
+
+    storage.record_notifications(&entries).unwrap();
+    // Recording the same notifications again, as every launch does, adds nothing
+    storage.record_notifications(&history.entries().cloned().collect::<Vec<_>>()).unwrap();
+    storage.record_notifications(&[failed]).unwrap();
+
+    let page = storage.notifications(&HistoryQuery { kind: Some(NotificationKind::Random), limit: Some(2), ..Default::default() }).unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(page.entries.iter().map(|entry| entry.sent_at).collect::<Vec<_>>(), vec![at(2025, 7, 1, 10, 0), at(2025, 7, 1, 9, 0)]);
+
+    let failures = storage.notifications(&HistoryQuery { result: Some(DeliveryResult::Failed), ..Default::default() }).unwrap();
+    assert_eq!(failures.entries[0].error.as_deref(), Some("denied"));
+}
+
+#[test]
+fn migrations_upgrade_once_and_refuse_newer_databases() {
+    let path = std::env::temp_dir().join(format!("habitquest-storage-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    drop(SqliteStorage::open(&path).unwrap());
+    let mut conn = Connection::open(&path).unwrap();
+    let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+    assert_eq!(version as usize, MIGRATIONS.len());
+
+    // Already applied, so the failing SQL of the first migration never runs again
+    run_migrations(&mut conn, &vec!["not sql"; MIGRATIONS.len()]).unwrap();
+    run_migrations(&mut conn, &[MIGRATIONS, &["CREATE TABLE streak_freezes (habit_id TEXT)"]].concat()).unwrap();
+    drop(conn);
+
+    let error = SqliteStorage::open(&path).err().unwrap().to_string();
+    let expected = format!("schema version {}, this version supports up to {}", MIGRATIONS.len() + 1, MIGRATIONS.len());
+    assert!(error.contains(&expected), "{}", error);
+    std::fs::remove_file(&path).unwrap();
+}
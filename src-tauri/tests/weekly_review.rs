@@ -0,0 +1,132 @@
+mod common;
+
+use chrono::{DateTime, Duration, Local};
+use common::{at, date, habit, temp_dir};
+use habitquest_lib::habit_streaks::{HabitFrequency, HabitStreak};
+use habitquest_lib::weekly_review::{previous_week_start, CategoryReview, Goal, ProgressData, Redemption, WeeklyReview};
+use std::fs;
+
+/// `name` in `category`, completed at `completions`
+fn completed(name: &str, category: &str, completions: Vec<DateTime<Local>>) -> HabitStreak {
+    HabitStreak {
+        category: category.to_string(),
+        last_completed_at: completions.last().copied(),
+        completions,
+        ..habit(name)
+    }
+}
+
+/// The week of Monday 2025-06-02, plus completions on either side of it
+fn review() -> WeeklyReview {
+    let habits = vec![
+        completed("Meditate", "health", (0..8).map(|day| at(2025, 5, 31, 8, 0) + Duration::days(day)).collect()),
+        HabitStreak {
+            frequency: HabitFrequency::Weekly,
+            xp_on_complete: 50,
+            ..completed("Budget", "money", vec![at(2025, 5, 28, 18, 0), at(2025, 6, 8, 18, 0)])
+        },
+        HabitStreak {
+            frequency: HabitFrequency::Monthly,
+            xp_on_complete: 100,
+            ..completed("Deep clean", "health", vec![at(2025, 5, 31, 10, 0)])
+        },
+    ];
+    let progress = ProgressData {
+        goals: [("health".to_string(), Goal { monthly_target_xp: 400 }), ("learning".to_string(), Goal { monthly_target_xp: 200 })]
+            .into_iter()
+            .collect(),
+        inventory: vec![
+            Redemption { name: "Movie night".to_string(), cost: 120, redeemed_at: at(2025, 6, 7, 20, 0) },
+            Redemption { name: "Fancy coffee".to_string(), cost: 30, redeemed_at: at(2025, 5, 30, 9, 0) },
+        ],
+    };
+    WeeklyReview::build(date(2025, 6, 2), &habits, &progress)
+}
+
+#[test]
+fn reviews_the_last_full_week() {
+    assert_eq!(previous_week_start(date(2025, 6, 9)), date(2025, 6, 2));
+    assert_eq!(previous_week_start(date(2025, 6, 15)), date(2025, 6, 2));
+}
+
+#[test]
+fn counts_completions_and_streak_changes_per_habit() {
+    let review = review();
+    let summary: Vec<_> = review
+        .habits
+        .iter()
+        .map(|habit| (habit.habit_name.as_str(), habit.completed, habit.completion_rate(), habit.streak_delta))
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("Meditate", 6, Some(85), Some(6)),
+            ("Budget", 1, Some(100), Some(1)),
+            ("Deep clean", 0, None, Some(0)),
+        ]
+    );
+}
+
+#[test]
+fn totals_xp_per_category_against_monthly_goals() {
+    let review = review();
+
+    assert_eq!(
+        review.categories,
+        vec![
+            CategoryReview { category: "health".to_string(), week_xp: 60, month_xp: 70, monthly_target_xp: Some(400) },
+            CategoryReview { category: "learning".to_string(), week_xp: 0, month_xp: 0, monthly_target_xp: Some(200) },
+            CategoryReview { category: "money".to_string(), week_xp: 50, month_xp: 50, monthly_target_xp: None },
+        ]
+    );
+    assert_eq!(review.redeemed.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), vec!["Movie night"]);
+}
+
+#[test]
+fn renders_markdown_and_escaped_html() {
+    let mut review = review();
+    review.habits[0].habit_name = "Read <b>& write</b>".to_string();
+    review.habits[1].habit_name = "Budget |\nSave".to_string();
+
+    let markdown = review.to_markdown();
+    assert!(markdown.starts_with("# HabitQuest Weekly Review: 2025-06-02 to 2025-06-08\n"));
+    assert!(markdown.contains("| Read <b>& write</b> | health | 6/7 (85%) | 8 | +6 |"));
+    assert!(markdown.contains("| Budget \\| Save | money | 1/1 (100%) | 2 | +1 |"));
+    assert!(markdown.contains("| health | 60 | 70 / 400 (17%) | 400 |"));
+    assert!(markdown.contains("- Movie night (120 points) on 2025-06-07"));
+
+    let html = review.to_html();
+    assert!(html.contains("<td>Read &lt;b&gt;&amp; write&lt;/b&gt;</td>"));
+    assert!(html.contains("<td>Deep clean</td><td>health</td><td>0</td><td>1</td><td>+0</td>"));
+    assert!(!html.contains("<b>"));
+}
+
+#[test]
+fn streaks_are_counted_as_of_the_end_of_the_reviewed_week() {
+    // Completed every day of the week after, which the review of this week must not count
+    let habits = vec![completed("Stretch", "health", (0..14).map(|day| at(2025, 6, 4, 8, 0) + Duration::days(day)).collect())];
+
+    let review = WeeklyReview::build(date(2025, 6, 2), &habits, &ProgressData::default());
+    assert_eq!((review.habits[0].streak, review.habits[0].streak_delta), (5, None));
+    assert!(review.to_html().contains("<td>Stretch</td><td>health</td><td>5/7 (71%)</td><td>5</td><td>new</td>"));
+
+    let review = WeeklyReview::build(date(2025, 6, 9), &habits, &ProgressData::default());
+    assert_eq!((review.habits[0].streak, review.habits[0].streak_delta), (12, Some(7)));
+}
+
+#[test]
+fn reports_are_written_next_to_each_other_and_replaced_when_regenerated() {
+    let dir = temp_dir("weekly-review").join("reviews");
+    let mut review = review();
+
+    let markdown_path = review.write_reports(&dir).unwrap();
+    assert_eq!(markdown_path, dir.join("weekly-review-2025-06-02.md"));
+    assert_eq!(fs::read_to_string(&markdown_path).unwrap(), review.to_markdown());
+    assert_eq!(fs::read_to_string(dir.join("weekly-review-2025-06-02.html")).unwrap(), review.to_html());
+
+    review.redeemed.clear();
+    review.write_reports(&dir).unwrap();
+    assert!(fs::read_to_string(&markdown_path).unwrap().contains("_None this week_"));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+}
@@ -9,6 +9,7 @@ import { AddHabitModal, AddRewardModal, AddCategoryModal, UncategorizedHabitsMod
 import { EditRewardModal } from "./components/modals/EditRewardModal";
 import { DeleteRewardModal } from "./components/modals/DeleteRewardModal";
 import { PROTECTED_FALLBACK_CATEGORY } from "./constants";
+import { getPeriodKey, sameDay } from "./utils";
 import { SettingsModal } from "./components/modals/SettingsModal";
 import { useAppState } from "./hooks/useAppState";
 import { featureIcons } from "./utils/icons";
@@ -34,7 +35,8 @@ import { HabitListSection } from "./components/habits";
 import { DailyStatsOverview } from "./components/stats";
 import { GoalTracker } from "./components/goals";
 import { RewardsShop } from "./components/rewards";
-import { NotificationSystem } from "./components/notifications";
+import { NotificationSystem, NotificationActionPrompt } from "./components/notifications";
+import { UnlockPrompt } from "./components/encryption";
 
 // Phase 3: Modal Components
 // Modal components are now handled by ModalSystem
@@ -169,12 +171,15 @@ function HabitGoalTrackerV3() {
 
   // Enhanced habit completion handler that includes notification system
   const handleHabitCompleteWithNotifications = useCallback((habitId: string, date: Date) => {
+    const habit = habits.find(h => h.id === habitId);
+    const completing = habit && !habit.completions[getPeriodKey(habit.frequency, date)];
+
     // Call original handler
     handleHabitComplete(habitId, date);
     
-    // Record completion in notification system
-    const habit = habits.find(h => h.id === habitId);
-    if (habit) {
+    // Record today's completions in the notification system; the backend completes the habit
+    // in the current period too, so undoing one or back-filling another day isn't reported
+    if (habit && completing && sameDay(date, new Date())) {
       habitReminders.recordHabitCompletion(habitId, habit.title);
     }
   }, [handleHabitComplete, habits, habitReminders]);
@@ -658,6 +663,7 @@ function HabitGoalTrackerV3() {
       <SettingsModal
         isOpen={activeSettings}
         onClose={handleCloseSettings}
+        habits={habits}
         audioEnabled={audioEnabled}
         backgroundMusicEnabled={backgroundMusicEnabled}
         soundEffectsVolume={soundEffectsVolume}
@@ -707,6 +713,12 @@ function HabitGoalTrackerV3() {
         notificationMessage={notificationMessage}
         lastCompletedHabit={lastCompletedHabit || ''}
       />
+
+      {/* Reminder buttons where system notifications can't show them */}
+      <NotificationActionPrompt />
+
+      {/* Passphrase prompt while the encrypted data is locked */}
+      <UnlockPrompt />
     </AppBackground>
   );
 }
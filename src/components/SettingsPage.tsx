@@ -233,6 +233,31 @@ export function SettingsPage({ isOpen, onClose }: SettingsPageProps) {
                   </Button>
                 </div>
               </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <label className="font-medium">On System Startup</label>
+                  <p className="text-sm text-gray-600 dark:text-gray-400">
+                    What HabitQuest does when it starts with your computer
+                  </p>
+                </div>
+                <div className="flex gap-2">
+                  {([
+                    ['summary', 'Summary'],
+                    ['silent', 'Silent'],
+                    ['open_window', 'Open Window'],
+                  ] as const).map(([behavior, label]) => (
+                    <Button
+                      key={behavior}
+                      variant={(settings.startupBehavior ?? 'summary') === behavior ? 'primary' : 'outline'}
+                      size="sm"
+                      onClick={() => updateSettings({ startupBehavior: behavior })}
+                    >
+                      {label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
             </div>
           </Card>
 
@@ -0,0 +1,185 @@
+/**
+ * ================================================================================================
+ * ENCRYPTION SETTINGS COMPONENT
+ * ================================================================================================
+ *
+ * Turns passphrase encryption of the app data on and off, locks it and changes the passphrase
+ *
+ * @version 1.0.0
+ */
+
+import { useState, useEffect } from 'react';
+import { motion } from 'framer-motion';
+import { Lock, Unlock, Info } from 'lucide-react';
+import {
+  inTauri,
+  getEncryptionStatus,
+  enableEncryption,
+  lockAppData,
+  changeEncryptionPassphrase,
+  disableEncryption,
+  onEncryptionStatusChange,
+  type EncryptionStatus
+} from '../../services/encryption';
+
+type Action = 'enable' | 'change' | 'disable';
+
+const inputClass = 'w-full rounded-xl border border-neutral-300 dark:border-neutral-600 bg-white/80 dark:bg-neutral-900/60 px-3 py-2 text-sm text-neutral-900 dark:text-neutral-100';
+
+// ================================================================================================
+// COMPONENT
+// ================================================================================================
+
+export default function EncryptionSettings() {
+  const [status, setStatus] = useState<EncryptionStatus | null>(null);
+  const [action, setAction] = useState<Action | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [newPassphrase, setNewPassphrase] = useState('');
+  const [confirmation, setConfirmation] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!inTauri()) return;
+    getEncryptionStatus().then(setStatus).catch((e) => setError(String(e)));
+
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    onEncryptionStatusChange(setStatus)
+      .then((stop) => {
+        if (disposed) stop();
+        else unlisten = stop;
+      })
+      .catch((e) => console.warn('Failed to listen for encryption status changes:', e));
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  if (!inTauri() || status === null) return null;
+
+  const open = (next: Action | null) => {
+    setAction(next);
+    setPassphrase('');
+    setNewPassphrase('');
+    setConfirmation('');
+    setError(null);
+  };
+
+  const choosesPassphrase = action === 'enable' || action === 'change';
+  const mismatch = choosesPassphrase && newPassphrase !== confirmation;
+  const ready = !mismatch && (choosesPassphrase ? newPassphrase !== '' : true) && (action === 'enable' || passphrase !== '');
+
+  const submit = async () => {
+    if (!action || !ready) return;
+    setBusy(true);
+    setError(null);
+    try {
+      if (action === 'enable') setStatus(await enableEncryption(newPassphrase));
+      if (action === 'change') setStatus(await changeEncryptionPassphrase(passphrase, newPassphrase));
+      if (action === 'disable') setStatus(await disableEncryption(passphrase));
+      open(null);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const lock = async () => {
+    try {
+      setStatus(await lockAppData());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const enabled = status !== 'disabled';
+
+  return (
+    <div>
+      <h3 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100 mb-4 flex items-center gap-2">
+        {enabled ? (
+          <Lock className="w-5 h-5 text-indigo-500 dark:text-indigo-400" />
+        ) : (
+          <Unlock className="w-5 h-5 text-indigo-500 dark:text-indigo-400" />
+        )}
+        Encryption
+      </h3>
+
+      <div className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <div className="font-medium text-neutral-900 dark:text-neutral-100 text-sm">
+              {enabled ? 'Your data is encrypted' : 'Encryption is off'}
+            </div>
+            <div className="text-xs text-neutral-600 dark:text-neutral-400">
+              {enabled
+                ? 'HabitQuest asks for your passphrase each time it starts'
+                : 'Protect your habits and history with a passphrase'}
+            </div>
+          </div>
+          <div className="flex gap-2">
+            {enabled ? (
+              <>
+                <motion.button onClick={lock} className="px-3 py-1.5 rounded-lg text-sm bg-indigo-500/15 dark:bg-indigo-500/30 text-indigo-700 dark:text-indigo-200" whileHover={{ scale: 1.05 }} whileTap={{ scale: 0.95 }}>
+                  Lock now
+                </motion.button>
+                <motion.button onClick={() => open('change')} className="px-3 py-1.5 rounded-lg text-sm bg-indigo-500/15 dark:bg-indigo-500/30 text-indigo-700 dark:text-indigo-200" whileHover={{ scale: 1.05 }} whileTap={{ scale: 0.95 }}>
+                  Change passphrase
+                </motion.button>
+                <motion.button onClick={() => open('disable')} className="px-3 py-1.5 rounded-lg text-sm bg-red-500/15 dark:bg-red-500/30 text-red-700 dark:text-red-200" whileHover={{ scale: 1.05 }} whileTap={{ scale: 0.95 }}>
+                  Turn off
+                </motion.button>
+              </>
+            ) : (
+              <motion.button onClick={() => open('enable')} className="px-3 py-1.5 rounded-lg text-sm bg-indigo-500/15 dark:bg-indigo-500/30 text-indigo-700 dark:text-indigo-200" whileHover={{ scale: 1.05 }} whileTap={{ scale: 0.95 }}>
+                Turn on
+              </motion.button>
+            )}
+          </div>
+        </div>
+
+        {action && (
+          <div className="space-y-2">
+            {(action === 'change' || action === 'disable') && (
+              <input type="password" value={passphrase} onChange={(e) => setPassphrase(e.target.value)} placeholder="Current passphrase" className={inputClass} autoFocus />
+            )}
+            {choosesPassphrase && (
+              <>
+                <input type="password" value={newPassphrase} onChange={(e) => setNewPassphrase(e.target.value)} placeholder="New passphrase" className={inputClass} autoFocus={action === 'enable'} />
+                <input type="password" value={confirmation} onChange={(e) => setConfirmation(e.target.value)} placeholder="Repeat the new passphrase" className={inputClass} />
+              </>
+            )}
+            {mismatch && confirmation && (
+              <div className="text-xs text-red-600 dark:text-red-400">The passphrases don't match</div>
+            )}
+            <div className="flex gap-2 justify-end">
+              <button onClick={() => open(null)} disabled={busy} className="px-3 py-1.5 rounded-lg text-sm text-neutral-600 dark:text-neutral-300">
+                Cancel
+              </button>
+              <button onClick={submit} disabled={busy || !ready} className="px-3 py-1.5 rounded-lg text-sm bg-indigo-500 text-white disabled:opacity-50">
+                {busy ? 'Working...' : 'Confirm'}
+              </button>
+            </div>
+          </div>
+        )}
+
+        {error && <div className="text-xs text-red-600 dark:text-red-400">{error}</div>}
+
+        <div className="flex gap-2 p-3 rounded-xl bg-neutral-500/10 text-xs text-neutral-600 dark:text-neutral-400">
+          <Info className="w-4 h-4 flex-shrink-0" />
+          <div className="space-y-1">
+            <p>A forgotten passphrase can't be recovered, and neither can the data it protects.</p>
+            <p>
+              Weekly reviews aren't written while encryption is on, since the reports are plain files
+              anyone with access to this computer could open. Turning encryption on deletes the
+              reviews written so far.
+            </p>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
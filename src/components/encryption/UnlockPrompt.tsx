@@ -0,0 +1,115 @@
+/**
+ * ================================================================================================
+ * UNLOCK PROMPT COMPONENT
+ * ================================================================================================
+ *
+ * Covers the app while its encrypted data is locked and asks for the passphrase. Once the
+ * backend reports the data unlocked, the app reloads with it.
+ *
+ * @version 1.0.0
+ */
+
+import { useState, useEffect } from 'react';
+import { motion, AnimatePresence } from 'framer-motion';
+import { Lock } from 'lucide-react';
+import {
+  inTauri,
+  getEncryptionStatus,
+  unlockAppData,
+  onEncryptionStatusChange,
+  type EncryptionStatus
+} from '../../services/encryption';
+
+// ================================================================================================
+// COMPONENT
+// ================================================================================================
+
+export default function UnlockPrompt() {
+  const [status, setStatus] = useState<EncryptionStatus | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!inTauri()) return;
+    getEncryptionStatus()
+      .then(setStatus)
+      .catch((e) => console.warn('Failed to get the encryption status:', e));
+
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    onEncryptionStatusChange(setStatus)
+      .then((stop) => {
+        if (disposed) stop();
+        else unlisten = stop;
+      })
+      .catch((e) => console.warn('Failed to listen for encryption status changes:', e));
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  const unlock = async (event: React.FormEvent) => {
+    event.preventDefault();
+    if (!passphrase) return;
+    setBusy(true);
+    setError(null);
+    try {
+      setStatus(await unlockAppData(passphrase));
+      setPassphrase('');
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <AnimatePresence>
+      {status === 'locked' && (
+        <motion.div
+          className="fixed inset-0 z-[100] flex items-center justify-center bg-neutral-900/80 backdrop-blur-md"
+          initial={{ opacity: 0 }}
+          animate={{ opacity: 1 }}
+          exit={{ opacity: 0 }}
+        >
+          <motion.form
+            onSubmit={unlock}
+            className="w-full max-w-sm mx-4 p-6 rounded-2xl bg-white/90 dark:bg-neutral-800/90 border border-neutral-200/50 dark:border-neutral-700/50 shadow-2xl space-y-4"
+            initial={{ scale: 0.95, y: 20 }}
+            animate={{ scale: 1, y: 0 }}
+          >
+            <div className="flex items-center gap-3">
+              <div className="p-2 rounded-lg bg-indigo-500/15 dark:bg-indigo-500/30">
+                <Lock className="w-5 h-5 text-indigo-600 dark:text-indigo-300" />
+              </div>
+              <div>
+                <h3 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100">HabitQuest is locked</h3>
+                <p className="text-xs text-neutral-600 dark:text-neutral-400">Enter your passphrase to open your data</p>
+              </div>
+            </div>
+
+            <input
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder="Passphrase"
+              className="w-full rounded-xl border border-neutral-300 dark:border-neutral-600 bg-white/80 dark:bg-neutral-900/60 px-3 py-2 text-sm text-neutral-900 dark:text-neutral-100"
+              autoFocus
+            />
+            {error && <div className="text-xs text-red-600 dark:text-red-400">{error}</div>}
+
+            <button
+              type="submit"
+              disabled={busy || !passphrase}
+              className="w-full px-4 py-2 rounded-xl text-sm font-medium bg-indigo-500 text-white disabled:opacity-50"
+            >
+              {busy ? 'Unlocking...' : 'Unlock'}
+            </button>
+          </motion.form>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+}
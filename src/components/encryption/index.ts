@@ -0,0 +1,16 @@
+/**
+ * ================================================================================================
+ * ENCRYPTION MODULE INDEX
+ * ================================================================================================
+ *
+ * Central export file for the encryption at rest components
+ *
+ * @version 1.0.0
+ */
+
+// ================================================================================================
+// COMPONENTS
+// ================================================================================================
+
+export { default as EncryptionSettings } from './EncryptionSettings';
+export { default as UnlockPrompt } from './UnlockPrompt';
@@ -117,6 +117,7 @@ export function ModalSystem({
           <SettingsModal
             isOpen={activeSettings}
             onClose={onCloseSettings}
+            habits={habits}
             audioEnabled={true}
             backgroundMusicEnabled={false}
             soundEffectsVolume={0.75}
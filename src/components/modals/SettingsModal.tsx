@@ -6,11 +6,17 @@ import { useTheme } from '../../hooks/useTheme';
 import { useAppStore } from '../../store/appStore';
 import { calculateStorageHealth, formatBytes, getStorageHealthColor, getStorageHealthBgColor, cleanupCacheData, type StorageHealth } from '../../utils/storageHealth';
 import NotificationSettings from '../notifications/NotificationSettings';
+import NotificationHistory from '../notifications/NotificationHistory';
+import HabitReminderSettings from '../notifications/HabitReminderSettings';
+import WeeklyReviewSettings from '../notifications/WeeklyReviewSettings';
+import { EncryptionSettings } from '../encryption';
 import { useHabitReminders } from '../../hooks/useHabitReminders';
+import type { Habit } from '../../types';
 
 interface SettingsModalProps {
   isOpen: boolean;
   onClose: () => void;
+  habits: Habit[];
   audioEnabled: boolean;
   backgroundMusicEnabled: boolean;
   soundEffectsVolume: number;
@@ -24,6 +30,7 @@ interface SettingsModalProps {
 export function SettingsModal({
   isOpen,
   onClose,
+  habits,
   audioEnabled,
   backgroundMusicEnabled,
   soundEffectsVolume,
@@ -593,6 +600,36 @@ export function SettingsModal({
                   />
                 </motion.div>
 
+                {/* Habit Reminders */}
+                <motion.div 
+                  className="bg-white/60 dark:bg-neutral-800/60 backdrop-blur-sm rounded-2xl p-6 border border-neutral-200/50 dark:border-neutral-700/50"
+                  initial={{ opacity: 0, y: 20 }}
+                  animate={{ opacity: 1, y: 0 }}
+                  transition={{ duration: 0.3, delay: 0.25 }}
+                >
+                  <HabitReminderSettings habits={habits} />
+                </motion.div>
+
+                {/* Weekly Review */}
+                <motion.div 
+                  className="bg-white/60 dark:bg-neutral-800/60 backdrop-blur-sm rounded-2xl p-6 border border-neutral-200/50 dark:border-neutral-700/50"
+                  initial={{ opacity: 0, y: 20 }}
+                  animate={{ opacity: 1, y: 0 }}
+                  transition={{ duration: 0.3, delay: 0.25 }}
+                >
+                  <WeeklyReviewSettings />
+                </motion.div>
+
+                {/* Notification History */}
+                <motion.div 
+                  className="bg-white/60 dark:bg-neutral-800/60 backdrop-blur-sm rounded-2xl p-6 border border-neutral-200/50 dark:border-neutral-700/50"
+                  initial={{ opacity: 0, y: 20 }}
+                  animate={{ opacity: 1, y: 0 }}
+                  transition={{ duration: 0.3, delay: 0.25 }}
+                >
+                  <NotificationHistory />
+                </motion.div>
+
                 {/* Data Management */}
                 <motion.div 
                   className="bg-white/60 dark:bg-neutral-800/60 backdrop-blur-sm rounded-2xl p-6 border border-neutral-200/50 dark:border-neutral-700/50"
@@ -691,6 +728,16 @@ export function SettingsModal({
                   </div>
                 </motion.div>
 
+                {/* Encryption */}
+                <motion.div 
+                  className="bg-white/60 dark:bg-neutral-800/60 backdrop-blur-sm rounded-2xl p-6 border border-neutral-200/50 dark:border-neutral-700/50"
+                  initial={{ opacity: 0, y: 20 }}
+                  animate={{ opacity: 1, y: 0 }}
+                  transition={{ duration: 0.3, delay: 0.35 }}
+                >
+                  <EncryptionSettings />
+                </motion.div>
+
                 {/* Storage Health Section */}
                 <motion.div 
                   className="bg-white/60 dark:bg-neutral-800/60 backdrop-blur-sm rounded-2xl p-6 border border-neutral-200/50 dark:border-neutral-700/50"
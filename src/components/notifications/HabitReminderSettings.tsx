@@ -0,0 +1,178 @@
+/**
+ * ================================================================================================
+ * HABIT REMINDER SETTINGS COMPONENT
+ * ================================================================================================
+ *
+ * Schedules reminders for individual habits at a set time on chosen weekdays
+ *
+ * @version 1.0.0
+ */
+
+import { useState, useEffect } from 'react';
+import { AlarmClock, Plus, Trash2 } from 'lucide-react';
+import { inTauri } from '../../services/encryption';
+import {
+  listHabitReminders,
+  createHabitReminder,
+  updateHabitReminder,
+  deleteHabitReminder,
+  WEEKDAYS,
+  type HabitReminder,
+  type Weekday
+} from '../../services/habitReminders';
+import type { Habit } from '../../types';
+
+export interface HabitReminderSettingsProps {
+  habits: Habit[];
+}
+
+const inputClass = 'rounded-lg border border-neutral-300 dark:border-neutral-600 bg-white/80 dark:bg-neutral-900/60 px-2 py-1 text-sm text-neutral-900 dark:text-neutral-100';
+
+const pad = (n: number) => String(n).padStart(2, '0');
+
+// ================================================================================================
+// COMPONENT
+// ================================================================================================
+
+export default function HabitReminderSettings({ habits }: HabitReminderSettingsProps) {
+  const [reminders, setReminders] = useState<HabitReminder[] | null>(null);
+  const [habitId, setHabitId] = useState('');
+  const [time, setTime] = useState('09:00');
+  const [weekdays, setWeekdays] = useState<Weekday[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!inTauri()) return;
+    listHabitReminders().then(setReminders).catch((e) => setError(String(e)));
+  }, []);
+
+  if (!inTauri() || reminders === null) return null;
+
+  const habitName = (id: string) => habits.find((h) => h.id === id)?.title;
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const toggleWeekday = (day: Weekday) =>
+    setWeekdays((days) => (days.includes(day) ? days.filter((d) => d !== day) : [...days, day]));
+
+  const add = () =>
+    run(async () => {
+      const [hour, minute] = time.split(':').map(Number);
+      const created = await createHabitReminder({
+        id: '',
+        habit_id: habitId,
+        habit_name: habitName(habitId) ?? null,
+        hour,
+        minute,
+        weekdays: WEEKDAYS.filter((d) => weekdays.includes(d)),
+        enabled: true
+      });
+      setReminders((current) => [...(current ?? []), created]);
+      setHabitId('');
+      setWeekdays([]);
+    });
+
+  const setEnabled = (reminder: HabitReminder, enabled: boolean) =>
+    run(async () => {
+      const updated = await updateHabitReminder({ ...reminder, enabled });
+      setReminders((current) => (current ?? []).map((r) => (r.id === updated.id ? updated : r)));
+    });
+
+  const remove = (id: string) =>
+    run(async () => {
+      await deleteHabitReminder(id);
+      setReminders((current) => (current ?? []).filter((r) => r.id !== id));
+    });
+
+  return (
+    <div>
+      <h3 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100 mb-4 flex items-center gap-2">
+        <AlarmClock className="w-5 h-5 text-indigo-500 dark:text-indigo-400" />
+        Habit Reminders
+      </h3>
+
+      <div className="space-y-4">
+        {reminders.length === 0 ? (
+          <div className="text-xs text-neutral-600 dark:text-neutral-400">No habit reminders scheduled</div>
+        ) : (
+          <ul className="divide-y divide-neutral-200/60 dark:divide-neutral-700/60">
+            {reminders.map((reminder) => (
+              <li key={reminder.id} className="py-2 flex items-center justify-between gap-3 text-sm">
+                <div className="min-w-0">
+                  <div className="font-medium text-neutral-900 dark:text-neutral-100 truncate">
+                    {habitName(reminder.habit_id) ?? reminder.habit_name ?? reminder.habit_id}
+                  </div>
+                  <div className="text-xs text-neutral-600 dark:text-neutral-400">
+                    {pad(reminder.hour)}:{pad(reminder.minute)} ·{' '}
+                    {reminder.weekdays.length === 0 ? 'Every day' : reminder.weekdays.join(', ')}
+                  </div>
+                </div>
+                <div className="flex items-center gap-2 flex-shrink-0">
+                  <input
+                    type="checkbox"
+                    checked={reminder.enabled}
+                    disabled={busy}
+                    onChange={(e) => setEnabled(reminder, e.target.checked)}
+                    aria-label="Enabled"
+                  />
+                  <button onClick={() => remove(reminder.id)} disabled={busy} className="p-1 rounded-lg text-red-600 dark:text-red-400 disabled:opacity-50" aria-label="Delete reminder">
+                    <Trash2 className="w-4 h-4" />
+                  </button>
+                </div>
+              </li>
+            ))}
+          </ul>
+        )}
+
+        <div className="space-y-2">
+          <div className="flex gap-2">
+            <select value={habitId} onChange={(e) => setHabitId(e.target.value)} className={`${inputClass} flex-1 min-w-0`}>
+              <option value="">Choose a habit</option>
+              {habits.map((habit) => (
+                <option key={habit.id} value={habit.id}>{habit.title}</option>
+              ))}
+            </select>
+            <input type="time" value={time} onChange={(e) => setTime(e.target.value)} className={inputClass} />
+          </div>
+          <div className="flex flex-wrap gap-1">
+            {WEEKDAYS.map((day) => (
+              <button
+                key={day}
+                onClick={() => toggleWeekday(day)}
+                className={`px-2 py-1 rounded-lg text-xs ${
+                  weekdays.includes(day)
+                    ? 'bg-indigo-500 text-white'
+                    : 'bg-indigo-500/15 dark:bg-indigo-500/30 text-indigo-700 dark:text-indigo-200'
+                }`}
+              >
+                {day}
+              </button>
+            ))}
+          </div>
+          <div className="flex items-center justify-between">
+            <span className="text-xs text-neutral-600 dark:text-neutral-400">
+              {weekdays.length === 0 ? 'Fires every day' : 'Fires on the selected days'}
+            </span>
+            <button onClick={add} disabled={busy || !habitId || !time} className="px-3 py-1.5 rounded-lg text-sm bg-indigo-500 text-white disabled:opacity-50 flex items-center gap-1">
+              <Plus className="w-4 h-4" />
+              Add reminder
+            </button>
+          </div>
+        </div>
+
+        {error && <div className="text-xs text-red-600 dark:text-red-400">{error}</div>}
+      </div>
+    </div>
+  );
+}
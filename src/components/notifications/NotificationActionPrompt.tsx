@@ -0,0 +1,113 @@
+/**
+ * ================================================================================================
+ * NOTIFICATION ACTION PROMPT COMPONENT
+ * ================================================================================================
+ *
+ * Offers a reminder's "Mark done", "Snooze" and "Open" buttons inside the app on platforms whose
+ * system notifications can't show them. The backend sends each reminder's buttons with the
+ * notification, and the one clicked is handled back in the backend.
+ *
+ * @version 1.0.0
+ */
+
+import { useState, useEffect } from 'react';
+import { motion, AnimatePresence } from 'framer-motion';
+import { Bell, X } from 'lucide-react';
+import { inTauri } from '../../services/encryption';
+
+/** Sent by the backend as `notification-action-prompt` */
+interface ActionPrompt {
+  title: string;
+  body: string;
+  reminder: unknown;
+  actions: { action: string; label: string }[];
+}
+
+const ACTION_PROMPT_EVENT = 'notification-action-prompt';
+
+// Older prompts are dropped past this many
+const MAX_PROMPTS = 3;
+
+// ================================================================================================
+// COMPONENT
+// ================================================================================================
+
+export default function NotificationActionPrompt() {
+  const [prompts, setPrompts] = useState<(ActionPrompt & { id: number })[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!inTauri()) return;
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    let nextId = 0;
+    import('@tauri-apps/api/event')
+      .then(({ listen }) =>
+        listen<ActionPrompt>(ACTION_PROMPT_EVENT, (event) => {
+          const prompt = { ...event.payload, id: nextId++ };
+          setPrompts((current) => [...current, prompt].slice(-MAX_PROMPTS));
+        })
+      )
+      .then((stop) => {
+        if (disposed) stop();
+        else unlisten = stop;
+      })
+      .catch((e) => console.warn('Failed to listen for notification actions:', e));
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  const dismiss = (id: number) => setPrompts((current) => current.filter((prompt) => prompt.id !== id));
+
+  const act = async (prompt: ActionPrompt & { id: number }, action: string) => {
+    dismiss(prompt.id);
+    setError(null);
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      await invoke('handle_notification_action', { action, reminder: prompt.reminder });
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="fixed bottom-4 right-4 z-50 flex flex-col gap-2 w-full max-w-xs">
+      <AnimatePresence>
+        {prompts.map((prompt) => (
+          <motion.div
+            key={prompt.id}
+            className="p-4 rounded-xl shadow-lg bg-white/90 dark:bg-neutral-800/90 border border-neutral-200/50 dark:border-neutral-700/50"
+            initial={{ opacity: 0, x: 100, scale: 0.9 }}
+            animate={{ opacity: 1, x: 0, scale: 1 }}
+            exit={{ opacity: 0, x: 100, scale: 0.9 }}
+          >
+            <div className="flex items-start gap-3">
+              <Bell className="w-5 h-5 flex-shrink-0 text-indigo-500 dark:text-indigo-400" />
+              <div className="flex-1 min-w-0">
+                <p className="font-semibold text-sm text-neutral-900 dark:text-neutral-100">{prompt.title}</p>
+                <p className="text-xs text-neutral-600 dark:text-neutral-400">{prompt.body}</p>
+              </div>
+              <button onClick={() => dismiss(prompt.id)} className="text-neutral-400 hover:text-neutral-600 dark:hover:text-neutral-200">
+                <X className="w-4 h-4" />
+              </button>
+            </div>
+            <div className="flex gap-2 justify-end mt-3">
+              {prompt.actions.map(({ action, label }) => (
+                <button
+                  key={action}
+                  onClick={() => act(prompt, action)}
+                  className="px-3 py-1.5 rounded-lg text-xs font-medium bg-indigo-500/15 dark:bg-indigo-500/30 text-indigo-700 dark:text-indigo-200"
+                >
+                  {label}
+                </button>
+              ))}
+            </div>
+          </motion.div>
+        ))}
+      </AnimatePresence>
+      {error && <div className="text-xs text-red-600 dark:text-red-400 text-right">{error}</div>}
+    </div>
+  );
+}
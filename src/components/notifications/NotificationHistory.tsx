@@ -0,0 +1,155 @@
+/**
+ * ================================================================================================
+ * NOTIFICATION HISTORY COMPONENT
+ * ================================================================================================
+ *
+ * Lists the notifications the backend sent, suppressed or failed to deliver, newest first
+ *
+ * @version 1.0.0
+ */
+
+import { useState, useEffect } from 'react';
+import { History } from 'lucide-react';
+import { inTauri } from '../../services/encryption';
+import {
+  getNotificationHistory,
+  type DeliveryResult,
+  type HistoryPage,
+  type NotificationKind
+} from '../../services/notificationHistory';
+
+const PAGE_SIZE = 20;
+
+const KIND_LABELS: Record<NotificationKind, string> = {
+  streak_protection: 'Streak protection',
+  random: 'Check-in',
+  habit_specific: 'Habit reminder',
+  streak_at_risk: 'Streak at risk',
+  morning_digest: 'Morning digest',
+  evening_recap: 'Evening recap',
+  weekly_review: 'Weekly review',
+  catch_up: 'Catch-up',
+  custom: 'In-app',
+  startup: 'Startup',
+  test: 'Test'
+};
+
+const RESULT_CLASSES: Record<DeliveryResult, string> = {
+  delivered: 'text-green-600 dark:text-green-400',
+  suppressed: 'text-neutral-500 dark:text-neutral-400',
+  failed: 'text-red-600 dark:text-red-400'
+};
+
+const selectClass = 'rounded-lg border border-neutral-300 dark:border-neutral-600 bg-white/80 dark:bg-neutral-900/60 px-2 py-1 text-xs text-neutral-900 dark:text-neutral-100';
+
+// ================================================================================================
+// COMPONENT
+// ================================================================================================
+
+export default function NotificationHistory() {
+  const [kind, setKind] = useState<NotificationKind | ''>('');
+  const [result, setResult] = useState<DeliveryResult | ''>('');
+  const [offset, setOffset] = useState(0);
+  const [page, setPage] = useState<HistoryPage | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!inTauri()) return;
+    let disposed = false;
+    getNotificationHistory({ kind: kind || undefined, result: result || undefined, offset, limit: PAGE_SIZE })
+      .then((next) => {
+        if (!disposed) {
+          setPage(next);
+          setError(null);
+        }
+      })
+      .catch((e) => !disposed && setError(String(e)));
+    return () => {
+      disposed = true;
+    };
+  }, [kind, result, offset]);
+
+  if (!inTauri()) return null;
+
+  const total = page?.total ?? 0;
+  const lastShown = Math.min(offset + PAGE_SIZE, total);
+
+  return (
+    <div>
+      <h3 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100 mb-4 flex items-center gap-2">
+        <History className="w-5 h-5 text-indigo-500 dark:text-indigo-400" />
+        Notification History
+      </h3>
+
+      <div className="space-y-3">
+        <div className="flex gap-2">
+          <select
+            value={kind}
+            onChange={(e) => {
+              setKind(e.target.value as NotificationKind | '');
+              setOffset(0);
+            }}
+            className={selectClass}
+          >
+            <option value="">All kinds</option>
+            {(Object.keys(KIND_LABELS) as NotificationKind[]).map((k) => (
+              <option key={k} value={k}>{KIND_LABELS[k]}</option>
+            ))}
+          </select>
+          <select
+            value={result}
+            onChange={(e) => {
+              setResult(e.target.value as DeliveryResult | '');
+              setOffset(0);
+            }}
+            className={selectClass}
+          >
+            <option value="">All results</option>
+            <option value="delivered">Delivered</option>
+            <option value="suppressed">Suppressed</option>
+            <option value="failed">Failed</option>
+          </select>
+        </div>
+
+        {page && page.entries.length === 0 && (
+          <div className="text-xs text-neutral-600 dark:text-neutral-400">No notifications yet</div>
+        )}
+
+        {page && page.entries.length > 0 && (
+          <ul className="divide-y divide-neutral-200/60 dark:divide-neutral-700/60">
+            {page.entries.map((entry) => (
+              <li key={entry.id} className="py-2 flex items-start justify-between gap-3 text-sm">
+                <div className="min-w-0">
+                  <div className="font-medium text-neutral-900 dark:text-neutral-100 truncate">{entry.title}</div>
+                  <div className="text-xs text-neutral-600 dark:text-neutral-400">
+                    {KIND_LABELS[entry.kind] ?? entry.kind} · {new Date(entry.sent_at).toLocaleString()}
+                  </div>
+                  {entry.error && <div className="text-xs text-red-600 dark:text-red-400">{entry.error}</div>}
+                </div>
+                <span className={`text-xs capitalize flex-shrink-0 ${RESULT_CLASSES[entry.result]}`}>{entry.result}</span>
+              </li>
+            ))}
+          </ul>
+        )}
+
+        {total > PAGE_SIZE && (
+          <div className="flex items-center justify-between text-xs text-neutral-600 dark:text-neutral-400">
+            <span>
+              {offset + 1}–{lastShown} of {total}
+            </span>
+            <div className="flex gap-2">
+              <button onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))} disabled={offset === 0} className="px-2 py-1 rounded-lg disabled:opacity-50">
+                Newer
+              </button>
+              <button onClick={() => setOffset(offset + PAGE_SIZE)} disabled={lastShown >= total} className="px-2 py-1 rounded-lg disabled:opacity-50">
+                Older
+              </button>
+            </div>
+          </div>
+        )}
+
+        {error && <div className="text-xs text-red-600 dark:text-red-400">{error}</div>}
+      </div>
+    </div>
+  );
+}
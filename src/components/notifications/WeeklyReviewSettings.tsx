@@ -0,0 +1,67 @@
+/**
+ * ================================================================================================
+ * WEEKLY REVIEW SETTINGS COMPONENT
+ * ================================================================================================
+ *
+ * Writes the review of the last full week on demand and shows where the report went
+ *
+ * @version 1.0.0
+ */
+
+import { useState } from 'react';
+import { motion } from 'framer-motion';
+import { FileText } from 'lucide-react';
+import { inTauri } from '../../services/encryption';
+import { generateWeeklyReview } from '../../services/weeklyReview';
+
+// ================================================================================================
+// COMPONENT
+// ================================================================================================
+
+export default function WeeklyReviewSettings() {
+  const [path, setPath] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  if (!inTauri()) return null;
+
+  const generate = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      setPath(await generateWeeklyReview());
+    } catch (e) {
+      setPath(null);
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100 mb-4 flex items-center gap-2">
+        <FileText className="w-5 h-5 text-indigo-500 dark:text-indigo-400" />
+        Weekly Review
+      </h3>
+
+      <div className="space-y-2">
+        <div className="flex items-center justify-between gap-3">
+          <div className="text-xs text-neutral-600 dark:text-neutral-400">
+            Write a Markdown and HTML report of last week's completions and streaks
+          </div>
+          <motion.button onClick={generate} disabled={busy} className="px-3 py-1.5 rounded-lg text-sm bg-indigo-500/15 dark:bg-indigo-500/30 text-indigo-700 dark:text-indigo-200 disabled:opacity-50 flex-shrink-0" whileHover={{ scale: 1.05 }} whileTap={{ scale: 0.95 }}>
+            {busy ? 'Writing...' : 'Write now'}
+          </motion.button>
+        </div>
+
+        {path && (
+          <div className="text-xs text-neutral-600 dark:text-neutral-400 break-all">
+            Saved to <span className="font-mono">{path}</span>
+          </div>
+        )}
+        {error && <div className="text-xs text-red-600 dark:text-red-400">{error}</div>}
+      </div>
+    </div>
+  );
+}
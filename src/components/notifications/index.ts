@@ -14,9 +14,14 @@
 
 export { default as NotificationSettings } from './NotificationSettings';
 export { NotificationSystem } from './NotificationSystem';
+export { default as NotificationActionPrompt } from './NotificationActionPrompt';
+export { default as NotificationHistory } from './NotificationHistory';
+export { default as HabitReminderSettings } from './HabitReminderSettings';
+export { default as WeeklyReviewSettings } from './WeeklyReviewSettings';
 
 // ================================================================================================
 // TYPES
 // ================================================================================================
 
 export type { NotificationSettingsProps } from './NotificationSettings';
+export type { HabitReminderSettingsProps } from './HabitReminderSettings';
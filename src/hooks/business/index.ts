@@ -8,7 +8,7 @@
  * @version 4.0.0
  */
 
-import { useState, useMemo } from "react";
+import { useState, useMemo, useEffect, useRef } from "react";
 import { 
   todayISO, 
   getPeriodKey, 
@@ -30,15 +30,40 @@ import {
   defaultGoalsByCategory, 
   defaultRewards 
 } from "../../data";
-import type { Frequency, Habit, Reward } from "../../types";
+import { appDataCacheEnabled } from "../../services/encryption";
+import type { Frequency, Habit, Reward, StoredData } from "../../types";
+
+const inTauri = () => typeof window !== 'undefined' && Boolean((window as any).__TAURI__);
+
+// Sent with the updated data when the backend changes it on its own
+const APP_DATA_CHANGED_EVENT = 'app-data-changed';
+
+/**
+ * The backend's copy of the app data, the canonical one when running in Tauri. `null` if the
+ * backend has none yet; throws if it couldn't be read, so nothing overwrites it.
+ */
+const loadAppDataFromBackend = async (): Promise<StoredData | null> => {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<StoredData | null>('load_app_data');
+};
+
+// Save changed state to the backend, which also refreshes its streak warnings and weekly review
+const patchAppDataInBackend = async (patch: Partial<StoredData>) => {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke('patch_app_data', { patch });
+  } catch (error) {
+    console.warn('Failed to save app data to backend:', error);
+  }
+};
 
 // ================================================================================================
 // HABIT MANAGEMENT HOOK
 // ================================================================================================
 
 export function useHabitManagement() {
-  // Load saved state (SSR‑safe)
-  const saved = loadData();
+  // Load saved state (SSR‑safe); there's no cache to load while the data is encrypted
+  const saved = appDataCacheEnabled() ? loadData() : null;
 
   // State management
   const [habits, setHabits] = useState<Habit[]>(saved?.habits ?? defaultHabits);
@@ -57,6 +82,47 @@ export function useHabitManagement() {
       : [...baseCategories, PROTECTED_FALLBACK_CATEGORY];
   });
 
+  function applyBackendData(data: StoredData): void {
+    setHabits(data.habits);
+    setPoints(data.points);
+    setTotalXP(data.totalXP);
+    setGoals(data.goals);
+    setShop(data.shop);
+    setInventory(data.inventory);
+    if (data.categories.length > 0) setCategories(data.categories);
+  }
+
+  // Take the backend's data over the localStorage cache; seed the backend from the cache once
+  const backendHydrated = useRef(false);
+  useEffect(() => {
+    if (!inTauri()) return;
+    loadAppDataFromBackend()
+      .then((data) => {
+        if (data) applyBackendData(data);
+        backendHydrated.current = true;
+        if (!data) saveAppData();
+      })
+      .catch((error) => console.warn('Failed to load app data from backend:', error));
+  }, []);
+
+  // Changes made by the backend itself, such as a habit marked done from a notification
+  useEffect(() => {
+    if (!inTauri()) return;
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    import('@tauri-apps/api/event')
+      .then(({ listen }) => listen<StoredData>(APP_DATA_CHANGED_EVENT, (event) => applyBackendData(event.payload)))
+      .then((stop) => {
+        if (disposed) stop();
+        else unlisten = stop;
+      })
+      .catch((error) => console.warn('Failed to listen for app data changes:', error));
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
   // Derived level metrics
   const level = useMemo(() => getCurrentLevel(totalXP), [totalXP]);
   const levelProgress = useMemo(() => getLevelProgress(totalXP), [totalXP]);
@@ -278,7 +344,13 @@ export function useHabitManagement() {
 
   // Persistence
   function saveAppData(): void {
-    saveData({ habits, points, totalXP, goals, inventory, shop, categories });
+    if (appDataCacheEnabled()) {
+      saveData({ habits, points, totalXP, goals, inventory, shop, categories });
+    }
+    // Until the backend's data is loaded, saving would overwrite it with the cache
+    if (backendHydrated.current) {
+      patchAppDataInBackend({ habits, points, totalXP, goals, inventory, shop, categories });
+    }
   }
 
   return {
@@ -2,6 +2,7 @@ import { useState, useEffect, useMemo } from 'react';
 import { useSoundEffects } from './useSoundEffects';
 import { useHabitManagement } from './business';
 import { initializeTrayEventHandler } from '../services/trayEventHandler';
+import { initializeStorageWarnings } from '../services/storageWarnings';
 import type { Frequency, Habit, Reward } from '../types';
 import { getPeriodKey, startOfMonth, endOfMonth } from '../utils';
 
@@ -81,6 +82,11 @@ export function useAppState() {
     soundService
   } = useSoundEffects(soundConfig);
 
+  // Warn about backend data restored from a backup
+  useEffect(() => {
+    initializeStorageWarnings();
+  }, []);
+
   // Initialize tray event handler with sound service
   useEffect(() => {
     if (soundService) {
@@ -105,7 +111,7 @@ export function useAppState() {
   // Wrapper function to fix type compatibility
   const getPeriodKeyWrapper = (frequency: string, date: Date) => getPeriodKey(frequency as Frequency, date);
 
-  // Persist to localStorage whenever major state changes
+  // Persist to the backend, and to localStorage unless encryption is on, whenever major state changes
   useEffect(() => {
     saveAppData();
   }, [habits, points, totalXP, goals, inventory, shop, categories, saveAppData]);
@@ -0,0 +1,67 @@
+/**
+ * Encryption Service
+ * Calls the backend's encryption at rest commands and follows its encryption status. While
+ * encryption is enabled the app data isn't cached in localStorage, where it would sit in plain
+ * text.
+ */
+
+import { LS_KEY } from '../constants';
+
+export type EncryptionStatus = 'disabled' | 'locked' | 'unlocked';
+
+export const ENCRYPTION_EVENT = 'encryption-status-changed';
+
+// Set while the backend's data is encrypted, so the cache is skipped before the backend answers
+const ENCRYPTED_KEY = 'ghgt:encrypted';
+
+export const inTauri = () => typeof window !== 'undefined' && Boolean((window as any).__TAURI__);
+
+async function call(command: string, args?: Record<string, unknown>): Promise<EncryptionStatus> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<EncryptionStatus>(command, args);
+}
+
+export const getEncryptionStatus = () => call('get_encryption_status');
+
+export const enableEncryption = (passphrase: string) => call('enable_encryption', { passphrase });
+
+export const unlockAppData = (passphrase: string) => call('unlock_app_data', { passphrase });
+
+export const lockAppData = () => call('lock_app_data');
+
+export const changeEncryptionPassphrase = (currentPassphrase: string, newPassphrase: string) =>
+  call('change_encryption_passphrase', { currentPassphrase, newPassphrase });
+
+export const disableEncryption = (passphrase: string) => call('disable_encryption', { passphrase });
+
+/**
+ * Call `onChange` with every status the backend announces; resolves to a function that stops
+ * listening
+ */
+export async function onEncryptionStatusChange(onChange: (status: EncryptionStatus) => void): Promise<() => void> {
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<EncryptionStatus>(ENCRYPTION_EVENT, (event) => onChange(event.payload));
+}
+
+/** Whether the app data may be cached in localStorage, as of the last known status */
+export function appDataCacheEnabled(): boolean {
+  try {
+    return typeof window !== 'undefined' && window.localStorage.getItem(ENCRYPTED_KEY) === null;
+  } catch {
+    return true;
+  }
+}
+
+/** Remember `status` for `appDataCacheEnabled`, deleting the cache once encryption is on */
+export function rememberEncryptionStatus(status: EncryptionStatus): void {
+  try {
+    if (status === 'disabled') {
+      window.localStorage.removeItem(ENCRYPTED_KEY);
+    } else {
+      window.localStorage.setItem(ENCRYPTED_KEY, '1');
+      window.localStorage.removeItem(LS_KEY);
+    }
+  } catch {
+    // ignore availability errors
+  }
+}
@@ -0,0 +1,37 @@
+/**
+ * Habit Reminders Service
+ * Manages the per-habit reminder schedules the backend fires while the app runs in the
+ * background.
+ */
+
+export type Weekday = 'Mon' | 'Tue' | 'Wed' | 'Thu' | 'Fri' | 'Sat' | 'Sun';
+
+export const WEEKDAYS: Weekday[] = ['Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'];
+
+export interface HabitReminder {
+  /** Assigned by the backend when left empty on creation */
+  id: string;
+  habit_id: string;
+  /** Display name used in the notification text */
+  habit_name?: string | null;
+  hour: number;
+  minute: number;
+  /** Days the reminder fires on. Empty means every day. */
+  weekdays: Weekday[];
+  enabled: boolean;
+}
+
+async function call<T>(command: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<T>(command, args);
+}
+
+export const listHabitReminders = () => call<HabitReminder[]>('list_habit_reminders');
+
+export const createHabitReminder = (reminder: HabitReminder) =>
+  call<HabitReminder>('create_habit_reminder', { reminder });
+
+export const updateHabitReminder = (reminder: HabitReminder) =>
+  call<HabitReminder>('update_habit_reminder', { reminder });
+
+export const deleteHabitReminder = (id: string) => call<void>('delete_habit_reminder', { id });
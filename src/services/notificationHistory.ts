@@ -0,0 +1,51 @@
+/**
+ * Notification History Service
+ * Reads the backend's log of notifications it sent, suppressed or failed to deliver.
+ */
+
+export type NotificationKind =
+  | 'streak_protection'
+  | 'random'
+  | 'habit_specific'
+  | 'streak_at_risk'
+  | 'morning_digest'
+  | 'evening_recap'
+  | 'weekly_review'
+  | 'catch_up'
+  | 'custom'
+  | 'startup'
+  | 'test';
+
+export type DeliveryResult = 'delivered' | 'suppressed' | 'failed';
+
+export interface HistoryEntry {
+  id: number;
+  sent_at: string;
+  kind: NotificationKind;
+  trigger: string;
+  title: string;
+  result: DeliveryResult;
+  error?: string | null;
+}
+
+export interface HistoryQuery {
+  kind?: NotificationKind;
+  result?: DeliveryResult;
+  /** RFC 3339 timestamps */
+  since?: string;
+  until?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export interface HistoryPage {
+  /** Newest first */
+  entries: HistoryEntry[];
+  /** Number of entries matching the filters, before paging */
+  total: number;
+}
+
+export async function getNotificationHistory(query: HistoryQuery = {}): Promise<HistoryPage> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<HistoryPage>('get_notification_history', { query });
+}
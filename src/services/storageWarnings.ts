@@ -0,0 +1,63 @@
+/**
+ * Storage Warnings Service
+ * Surfaces backend data files that were corrupt and restored from their last good backup,
+ * and reloads the app when the user restores one of the rotating backups or unlocks
+ * encrypted data
+ */
+
+import { ENCRYPTION_EVENT, rememberEncryptionStatus, type EncryptionStatus } from './encryption';
+
+export interface StorageRecovery {
+  file: string;
+  error: string;
+}
+
+const RECOVERY_EVENT = 'storage-recovered-from-backup';
+const RESTORED_EVENT = 'app-data-restored';
+
+function reportRecovery(recovery: StorageRecovery) {
+  console.warn(`⚠️ ${recovery.file} was damaged and restored from its last backup:`, recovery.error);
+
+  // Emit custom event for other components to react
+  window.dispatchEvent(new CustomEvent('storage-warning', {
+    detail: {
+      message: `Some saved data (${recovery.file}) was damaged and has been restored from the last backup. Recent changes may be missing.`,
+      type: 'warning',
+      recovery
+    }
+  }));
+}
+
+let initialized = false;
+
+export async function initializeStorageWarnings() {
+  if (initialized || typeof window === 'undefined' || !(window as any).__TAURI__) {
+    return;
+  }
+  initialized = true;
+
+  try {
+    const { listen } = await import('@tauri-apps/api/event');
+    const { invoke } = await import('@tauri-apps/api/core');
+
+    await listen<StorageRecovery>(RECOVERY_EVENT, (event) => reportRecovery(event.payload));
+
+    // A restored backup replaces everything the app has loaded; start over from the backend
+    await listen(RESTORED_EVENT, () => window.location.reload());
+
+    // Unlocking or re-encrypting reloads every backend store; pick up what they loaded
+    await listen<EncryptionStatus>(ENCRYPTION_EVENT, (event) => {
+      rememberEncryptionStatus(event.payload);
+      if (event.payload === 'unlocked') {
+        window.location.reload();
+      }
+    });
+    rememberEncryptionStatus(await invoke<EncryptionStatus>('get_encryption_status'));
+
+    // Files are loaded before the webview starts listening
+    const pending = await invoke<StorageRecovery[]>('take_storage_warnings');
+    pending.forEach(reportRecovery);
+  } catch (error) {
+    console.warn('Failed to set up storage warnings:', error);
+  }
+}
@@ -0,0 +1,11 @@
+/**
+ * Weekly Review Service
+ * Asks the backend to write the review of the last full week on demand, instead of waiting for
+ * the scheduled one.
+ */
+
+/** Write the review of the last full week, resolving to the Markdown report's path */
+export async function generateWeeklyReview(): Promise<string> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<string>('generate_weekly_review');
+}
@@ -18,6 +18,7 @@ const defaultSettings: AppSettings = {
   notifications: true,
   language: 'en',
   startOfWeek: 0,
+  startupBehavior: 'summary',
 };
 
 const defaultUserStats: UserStats = {
@@ -60,6 +61,18 @@ interface AppStore extends StoredData {
   resetData: () => void;
 }
 
+// Keep the backend's copy of the settings current so native notifications use the UI language
+const syncSettingsToBackend = async (settings: AppSettings) => {
+  try {
+    if (typeof window !== 'undefined' && (window as any).__TAURI__) {
+      const { invoke } = await import('@tauri-apps/api/core');
+      await invoke('sync_app_settings', { settings });
+    }
+  } catch (error) {
+    console.warn('Failed to sync settings to backend:', error);
+  }
+};
+
 export const useAppStore = create<AppStore>()(
   persist(
     (set, get) => ({
@@ -160,9 +173,12 @@ export const useAppStore = create<AppStore>()(
         }
       })),
       
-      updateSettings: (newSettings) => set((state) => ({
-        settings: { ...state.settings, ...newSettings }
-      })),
+      updateSettings: (newSettings) => {
+        set((state) => ({
+          settings: { ...state.settings, ...newSettings }
+        }));
+        syncSettingsToBackend(get().settings);
+      },
       
       updateUserStats: (newStats) => set((state) => ({
         userStats: { ...state.userStats, ...newStats }
@@ -397,6 +413,9 @@ export const useAppStore = create<AppStore>()(
     {
       name: 'habitquest-storage',
       version: 1,
+      onRehydrateStorage: () => (state) => {
+        if (state) syncSettingsToBackend(state.settings);
+      },
     }
   )
 );
@@ -64,6 +64,7 @@ export type AppSettings = {
   notifications: boolean;
   language: string;
   startOfWeek: 0 | 1; // 0 = Sunday, 1 = Monday
+  startupBehavior?: 'summary' | 'silent' | 'open_window'; // Launch at system startup
 };
 
 export type Stored = {